@@ -484,3 +484,42 @@ fn main() {
     );
     println!("success, no leaks detected.");
 }
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::HashSet;
+
+    #[test]
+    fn unlink_while_traversing() {
+        const ITERATIONS: usize = 10_000;
+
+        let set = HashSet::with_buckets(1);
+        let mut handle = set.handle();
+        for value in 0..4 {
+            assert!(handle.insert(value));
+        }
+
+        // one thread repeatedly unlinks and re-inserts all nodes preceding 3, while the other
+        // traverses past them and must hence never miss the permanently linked 3
+        let mut writer = set.handle();
+        let thread = thread::spawn(move || {
+            for _ in 0..ITERATIONS {
+                for value in 0..3 {
+                    assert!(writer.remove(&value));
+                    assert!(writer.insert(value));
+                }
+            }
+        });
+
+        for _ in 0..ITERATIONS {
+            assert!(handle.contains(&3));
+        }
+
+        thread.join().unwrap();
+        for value in 0..4 {
+            assert!(handle.contains(&value));
+        }
+    }
+}
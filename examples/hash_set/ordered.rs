@@ -39,7 +39,7 @@ where
             if let Insert { prev, next } = self.find(elem, guards) {
                 node.next().store(next, Relaxed);
                 // (ORD:1) this `Release` CAS synchronizes-with the `Acquire` CAS (ORD:3) and the
-                // `Acquire` loads (ORD:4) and (ORD:5)
+                // `Acquire` loads (ORD:4), (ORD:5) and (ORD:7)
                 match prev.compare_exchange(next, node, Release, Relaxed) {
                     Ok(_) => break true,
                     Err(failure) => node = failure.input,
@@ -73,7 +73,7 @@ where
                     }
 
                     // (ORD:3) this `Release` CAS synchronizes-with the `Acquire` CAS (ORD:2) and
-                    // the `Acquire` loads (ORD:4) and (ORD:5)
+                    // the `Acquire` loads (ORD:4), (ORD:5) and (ORD:7)
                     match prev.compare_exchange(curr, next, Release, Relaxed) {
                         Ok(unlinked) => unsafe { unlinked.retire() },
                         Err(_) => {
@@ -135,14 +135,20 @@ where
                 match curr_next.load_marked_if_equal(next_raw, Acquire, &mut guards.next) {
                     Err(_) => continue 'retry,
                     Ok(next_marked) => {
-                        if prev.load_raw(Relaxed) != curr.as_marked_ptr() {
+                        // (ORD:7) if this `Acquire` load reads `curr`, it synchronizes-with the
+                        // `Release` CAS that stored `curr` in `prev`, i.e. (ORD:1) if `curr` was
+                        // inserted after `prev` or (ORD:3)/(ORD:6) if a node between them was
+                        // unlinked; reading `curr` establishes that `curr` was still linked after
+                        // `next` had been protected in (ORD:5), so `next` could not yet have been
+                        // unlinked through another predecessor and retired
+                        if prev.load_raw(Acquire) != curr.as_marked_ptr() {
                             continue 'retry;
                         }
 
                         let (next, next_tag) = Marked::decompose(next_marked);
                         if next_tag == DELETE_TAG {
                             // (ORD:6) this `Release` CAS synchronizes-with the `Acquire` CAS
-                            // (ORD:2) and the `Acquire` loads (ORD:4) and (ORD:5)
+                            // (ORD:2) and the `Acquire` loads (ORD:4), (ORD:5) and (ORD:7)
                             match prev.compare_exchange(curr, next, Release, Relaxed) {
                                 Ok(unlinked) => unsafe { unlinked.retire() },
                                 Err(_) => continue 'retry,
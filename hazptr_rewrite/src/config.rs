@@ -1,18 +1,47 @@
+use core::fmt;
+
+use crate::local::HAZARD_CACHE;
+
 const DEFAULT_SCAN_CACHE_SIZE: usize = 128;
 const DEFAULT_MAX_RESERVED_HAZARD_POINTERS: u32 = 16;
 const DEFAULT_OPS_COUNT_THRESHOLD: u32 = 128;
-const DEFAULT_COUNT_STRATEGY: Operation = Operation::Retire;
+const DEFAULT_COUNT_STRATEGY: CountStrategy = CountStrategy::Retire;
+const DEFAULT_INITIAL_RESERVED_HAZARDS: usize = 0;
+const DEFAULT_FENCE_MODE: FenceMode = FenceMode::SeqCst;
+const DEFAULT_COUNT_CLONES: bool = false;
+const DEFAULT_TOTAL_OPS_BUDGET: Option<u32> = None;
+const DEFAULT_MAX_HAZARD_POINTERS: Option<usize> = None;
+const DEFAULT_CAN_RECLAIM: Option<fn(usize) -> bool> = None;
+const DEFAULT_RETIRE_RING_CAPACITY: Option<usize> = None;
+const DEFAULT_RECLAIM_GRACE_SCANS: Option<usize> = None;
+const DEFAULT_LOCAL_SPILLOVER_THRESHOLD: Option<usize> = None;
+const DEFAULT_MAX_CACHED_HAZARDS: usize = HAZARD_CACHE;
+const DEFAULT_SCAN_CACHE_FIXED_CAPACITY: Option<usize> = None;
+const DEFAULT_COMPACT_HAZARDS_INTERVAL: Option<u64> = None;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // ConfigBuilder
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-#[derive(Copy, Clone, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct ConfigBuilder {
     initial_scan_cache_size: Option<usize>,
     max_reserved_hazard_pointers: Option<u32>,
     ops_count_threshold: Option<u32>,
-    count_strategy: Option<Operation>,
+    count_strategy: Option<CountStrategy>,
+    initial_reserved_hazards: Option<usize>,
+    fence_mode: Option<FenceMode>,
+    numa_node: Option<usize>,
+    count_clones: Option<bool>,
+    total_ops_budget: Option<u32>,
+    max_hazard_pointers: Option<usize>,
+    can_reclaim: Option<fn(usize) -> bool>,
+    retire_ring_capacity: Option<usize>,
+    reclaim_grace_scans: Option<usize>,
+    local_spillover_threshold: Option<usize>,
+    max_cached_hazards: Option<usize>,
+    scan_cache_fixed_capacity: Option<usize>,
+    compact_hazards_interval: Option<u64>,
 }
 
 /********** impl inherent *************************************************************************/
@@ -23,15 +52,200 @@ impl ConfigBuilder {
         Self::default()
     }
 
+    /// Creates a new [`ConfigBuilder`] seeded with all values of `config`, so
+    /// that only the overridden values differ from it when building.
+    #[inline]
+    pub fn from_config(config: Config) -> Self {
+        Self {
+            initial_scan_cache_size: Some(config.initial_scan_cache_size),
+            max_reserved_hazard_pointers: Some(config.max_reserved_hazard_pointers),
+            ops_count_threshold: Some(config.ops_count_threshold),
+            count_strategy: Some(config.count_strategy),
+            initial_reserved_hazards: Some(config.initial_reserved_hazards),
+            fence_mode: Some(config.fence_mode),
+            numa_node: config.numa_node,
+            count_clones: Some(config.count_clones),
+            total_ops_budget: config.total_ops_budget,
+            max_hazard_pointers: config.max_hazard_pointers,
+            can_reclaim: config.can_reclaim,
+            retire_ring_capacity: config.retire_ring_capacity,
+            reclaim_grace_scans: config.reclaim_grace_scans,
+            local_spillover_threshold: config.local_spillover_threshold,
+            max_cached_hazards: Some(config.max_cached_hazards),
+            scan_cache_fixed_capacity: config.scan_cache_fixed_capacity,
+            compact_hazards_interval: config.compact_hazards_interval,
+        }
+    }
+
     #[inline]
     pub fn initial_scan_cache_size(mut self, val: usize) -> Self {
         self.initial_scan_cache_size = Some(val);
         self
     }
 
+    /// Sets the number of counted operations between scans, see
+    /// [`Config::ops_count_threshold`].
+    #[inline]
+    pub fn ops_count_threshold(mut self, val: u32) -> Self {
+        self.ops_count_threshold = Some(val);
+        self
+    }
+
+    /// Sets the number of hazard pointers each new [`Local`][crate::Local]
+    /// reserves into its cache right away (bounded by the cache's capacity).
+    #[inline]
+    pub fn initial_reserved_hazards(mut self, val: usize) -> Self {
+        self.initial_reserved_hazards = Some(val);
+        self
+    }
+
+    /// Sets the [`FenceMode`], see [`Config::fence_mode`].
+    #[inline]
+    pub fn fence_mode(mut self, val: FenceMode) -> Self {
+        self.fence_mode = Some(val);
+        self
+    }
+
+    /// Sets the NUMA node hint, see [`Config::numa_node`].
+    #[inline]
+    pub fn numa_node(mut self, val: usize) -> Self {
+        self.numa_node = Some(val);
+        self
+    }
+
+    /// Sets whether cloning a [`Guard`][crate::Guard] counts as an operation,
+    /// see [`Config::count_clones`].
+    #[inline]
+    pub fn count_clones(mut self, val: bool) -> Self {
+        self.count_clones = Some(val);
+        self
+    }
+
+    /// Sets the budget of total operations between scans, see
+    /// [`Config::total_ops_budget`].
+    #[inline]
+    pub fn total_ops_budget(mut self, val: u32) -> Self {
+        self.total_ops_budget = Some(val);
+        self
+    }
+
+    /// Sets the maximum number of hazard pointers, see
+    /// [`Config::max_hazard_pointers`].
+    #[inline]
+    pub fn max_hazard_pointers(mut self, val: usize) -> Self {
+        self.max_hazard_pointers = Some(val);
+        self
+    }
+
+    /// Sets the reclamation veto callback, see [`Config::can_reclaim`].
+    #[inline]
+    pub fn can_reclaim(mut self, val: fn(usize) -> bool) -> Self {
+        self.can_reclaim = Some(val);
+        self
+    }
+
+    /// Sets the fixed capacity of the retire buffer, see
+    /// [`Config::retire_ring_capacity`].
+    #[inline]
+    pub fn retire_ring_capacity(mut self, val: usize) -> Self {
+        self.retire_ring_capacity = Some(val);
+        self
+    }
+
+    /// Sets the number of grace scans, see [`Config::reclaim_grace_scans`].
+    #[inline]
+    pub fn reclaim_grace_scans(mut self, val: usize) -> Self {
+        self.reclaim_grace_scans = Some(val);
+        self
+    }
+
+    /// Sets the spillover threshold, see
+    /// [`Config::local_spillover_threshold`].
+    #[inline]
+    pub fn local_spillover_threshold(mut self, val: usize) -> Self {
+        self.local_spillover_threshold = Some(val);
+        self
+    }
+
+    /// Sets the maximum number of cached hazard pointers, see
+    /// [`Config::max_cached_hazards`].
+    #[inline]
+    pub fn max_cached_hazards(mut self, val: usize) -> Self {
+        self.max_cached_hazards = Some(val);
+        self
+    }
+
+    /// Sets the fixed capacity of the scan cache, see
+    /// [`Config::scan_cache_fixed_capacity`].
+    #[inline]
+    pub fn scan_cache_fixed_capacity(mut self, val: usize) -> Self {
+        self.scan_cache_fixed_capacity = Some(val);
+        self
+    }
+
+    /// Sets the number of scans between compactions of the cached hazard
+    /// pointers, see [`Config::compact_hazards_interval`].
+    #[inline]
+    pub fn compact_hazards_interval(mut self, val: u64) -> Self {
+        self.compact_hazards_interval = Some(val);
+        self
+    }
+
+    /// Builds the [`Config`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is invalid, see
+    /// [`try_build`][ConfigBuilder::try_build].
     #[inline]
     pub fn build(self) -> Config {
-        Config {
+        match self.try_build() {
+            Ok(config) => config,
+            Err(err) => panic!("invalid configuration: {}", err),
+        }
+    }
+
+    /// Builds the [`Config`] after validating it.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the configuration can never trigger a reclamation, i.e. if
+    /// the [`ops_count_threshold`][Config::ops_count_threshold] is 0 and
+    /// neither a [`total_ops_budget`][Config::total_ops_budget] nor a
+    /// [`retire_ring_capacity`][Config::retire_ring_capacity] forces scans
+    /// instead, which would silently let retired records grow without bound.
+    /// Also fails, if the
+    /// [`scan_cache_fixed_capacity`][Config::scan_cache_fixed_capacity] or the
+    /// [`compact_hazards_interval`][Config::compact_hazards_interval] is 0 or
+    /// if the [`max_cached_hazards`][Config::max_cached_hazards] exceed the
+    /// capacity of each thread's hazard cache.
+    #[inline]
+    pub fn try_build(self) -> Result<Config, ConfigError> {
+        let has_other_trigger =
+            self.total_ops_budget.is_some() || self.retire_ring_capacity.is_some();
+        if self.ops_count_threshold == Some(0) && !has_other_trigger {
+            return Err(ConfigError::ZeroThreshold);
+        }
+
+        if self.scan_cache_fixed_capacity == Some(0) {
+            return Err(ConfigError::ZeroScanCacheCapacity);
+        }
+
+        if self.compact_hazards_interval == Some(0) {
+            return Err(ConfigError::ZeroCompactionInterval);
+        }
+
+        match self.max_cached_hazards {
+            Some(requested) if requested > HAZARD_CACHE => {
+                return Err(ConfigError::CachedHazardsExceedCache {
+                    requested,
+                    capacity: HAZARD_CACHE,
+                })
+            }
+            _ => {}
+        }
+
+        Ok(Config {
             initial_scan_cache_size: self
                 .initial_scan_cache_size
                 .unwrap_or(DEFAULT_SCAN_CACHE_SIZE),
@@ -40,6 +254,75 @@ impl ConfigBuilder {
                 .unwrap_or(DEFAULT_MAX_RESERVED_HAZARD_POINTERS),
             ops_count_threshold: self.ops_count_threshold.unwrap_or(DEFAULT_OPS_COUNT_THRESHOLD),
             count_strategy: self.count_strategy.unwrap_or(DEFAULT_COUNT_STRATEGY),
+            initial_reserved_hazards: self
+                .initial_reserved_hazards
+                .unwrap_or(DEFAULT_INITIAL_RESERVED_HAZARDS),
+            fence_mode: self.fence_mode.unwrap_or(DEFAULT_FENCE_MODE),
+            numa_node: self.numa_node,
+            count_clones: self.count_clones.unwrap_or(DEFAULT_COUNT_CLONES),
+            total_ops_budget: self.total_ops_budget.or(DEFAULT_TOTAL_OPS_BUDGET),
+            max_hazard_pointers: self.max_hazard_pointers.or(DEFAULT_MAX_HAZARD_POINTERS),
+            can_reclaim: self.can_reclaim.or(DEFAULT_CAN_RECLAIM),
+            retire_ring_capacity: self.retire_ring_capacity.or(DEFAULT_RETIRE_RING_CAPACITY),
+            reclaim_grace_scans: self.reclaim_grace_scans.or(DEFAULT_RECLAIM_GRACE_SCANS),
+            local_spillover_threshold: self
+                .local_spillover_threshold
+                .or(DEFAULT_LOCAL_SPILLOVER_THRESHOLD),
+            max_cached_hazards: self.max_cached_hazards.unwrap_or(DEFAULT_MAX_CACHED_HAZARDS),
+            scan_cache_fixed_capacity: self
+                .scan_cache_fixed_capacity
+                .or(DEFAULT_SCAN_CACHE_FIXED_CAPACITY),
+            compact_hazards_interval: self
+                .compact_hazards_interval
+                .or(DEFAULT_COMPACT_HAZARDS_INTERVAL),
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// ConfigError
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An error for an invalid configuration, see
+/// [`ConfigBuilder::try_build`].
+#[derive(Copy, Clone, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// The [`ops_count_threshold`][Config::ops_count_threshold] is 0, which the
+    /// ops count (starting at 1) never reaches, and there is no other trigger
+    /// for reclamation.
+    ZeroThreshold,
+    /// The [`scan_cache_fixed_capacity`][Config::scan_cache_fixed_capacity] is
+    /// 0, so no protected pointer could ever be collected.
+    ZeroScanCacheCapacity,
+    /// The [`compact_hazards_interval`][Config::compact_hazards_interval] is
+    /// 0.
+    ZeroCompactionInterval,
+    /// The [`max_cached_hazards`][Config::max_cached_hazards] (`requested`)
+    /// exceed the (fixed) capacity of each thread's hazard cache.
+    CachedHazardsExceedCache { requested: usize, capacity: usize },
+}
+
+/********** impl Display **************************************************************************/
+
+impl fmt::Display for ConfigError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::ZeroThreshold => {
+                write!(f, "the ops count threshold must not be 0 without any other reclaim trigger")
+            }
+            ConfigError::ZeroScanCacheCapacity => {
+                write!(f, "the fixed scan cache capacity must not be 0")
+            }
+            ConfigError::ZeroCompactionInterval => {
+                write!(f, "the hazard compaction interval must not be 0")
+            }
+            ConfigError::CachedHazardsExceedCache { requested, capacity } => write!(
+                f,
+                "at most {} hazard pointers can be cached, but {} were requested",
+                capacity, requested
+            ),
         }
     }
 }
@@ -48,13 +331,135 @@ impl ConfigBuilder {
 // Config
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-#[derive(Copy, Clone, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[non_exhaustive]
 pub struct Config {
     pub initial_scan_cache_size: usize,
     pub max_reserved_hazard_pointers: u32,
     pub ops_count_threshold: u32,
-    pub count_strategy: Operation,
+    pub count_strategy: CountStrategy,
+    /// The number of hazard pointers reserved by each new thread local state
+    /// when it is created, which is bounded by the capacity of its cache.
+    pub initial_reserved_hazards: usize,
+    /// The kind of memory fences used for synchronizing protecting and
+    /// scanning threads.
+    ///
+    /// Since all threads must agree on the same mode, only the mode of the
+    /// [`Config`] the [`Hp`][crate::Hp] is created with is relevant, the mode
+    /// of any configuration passed when building a [`Local`][crate::Local] is
+    /// ignored.
+    pub fence_mode: FenceMode,
+    /// The NUMA node a thread local state is (mostly) running on, which
+    /// determines the queue it retires records into, if the [`Hp`][crate::Hp]
+    /// has been created with per-node queues (see
+    /// [`Hp::global_retire_numa`][crate::Hp::global_retire_numa]) and is
+    /// ignored otherwise.
+    ///
+    /// If no hint is given, the node is queried from the platform if the
+    /// `numa` feature is enabled, otherwise queues are assigned in round-robin
+    /// order.
+    pub numa_node: Option<usize>,
+    /// If set, each clone of a [`Guard`][crate::Guard] counts as an operation
+    /// towards the [`ops_count_threshold`][Config::ops_count_threshold] in
+    /// addition to the operations determined by the
+    /// [`count_strategy`][Config::count_strategy].
+    ///
+    /// This prevents starving reclamation in workloads cloning guards much
+    /// more frequently than releasing or retiring.
+    pub count_clones: bool,
+    /// If set, a scan is forced after this many operations of *any* kind (see
+    /// [`Operation`]) since the last scan, regardless of the
+    /// [`count_strategy`][Config::count_strategy].
+    ///
+    /// This guarantees regular scans in workloads, in which the counted kind
+    /// of operation rarely occurs, e.g. mostly protecting and rarely retiring.
+    pub total_ops_budget: Option<u32>,
+    /// The maximum number of hazard pointers the application expects to be
+    /// allocated in total.
+    ///
+    /// The number of hazard pointers is not actually limited, this value is
+    /// only checked by [`Hp::validate_runtime`][crate::Hp::validate_runtime].
+    pub max_hazard_pointers: Option<usize>,
+    /// If set, this callback is consulted during each scan for every retired
+    /// record (by its address) that is not protected by any hazard pointer,
+    /// and the record is only reclaimed if the callback returns `true`.
+    ///
+    /// This allows a second ownership system (e.g. external reference counts)
+    /// to gate the reclamation of records, vetoed records are simply retained
+    /// until a later scan.
+    /// The callback of the scanning thread local state's configuration (or the
+    /// [`Hp`][crate::Hp]'s own configuration for
+    /// [`reclaim_until_quiescent`][crate::Hp::reclaim_until_quiescent]) is
+    /// used, so all configurations should agree on it.
+    /// Records are reclaimed regardless of the callback when the
+    /// [`Hp`][crate::Hp] itself is dropped or reset.
+    ///
+    /// Comparing configurations compares the addresses of their callbacks,
+    /// which are not guaranteed to be unique (or identical for the same
+    /// function), so configurations with callbacks should not be compared.
+    /// For the same reason, [`Config`] implements neither [`Hash`] nor
+    /// [`Ord`].
+    pub can_reclaim: Option<fn(usize) -> bool>,
+    /// If set, each thread local state stores its retired records in a buffer
+    /// of this fixed capacity, which is allocated once when the state is
+    /// created and never grows (local retire strategy only).
+    ///
+    /// Retiring a record into a full buffer forces a synchronous scan and, if
+    /// all buffered records are still protected, retries a bounded number of
+    /// times until enough of them can be reclaimed, which bounds the memory
+    /// used for retired records, e.g. for real-time or embedded applications.
+    /// If the records remain protected (e.g. by the thread's own guards), they
+    /// are abandoned like those of an exited thread rather than blocking
+    /// forever.
+    /// Records abandoned by exited threads are reclaimed in place instead of
+    /// being adopted into the buffer.
+    pub retire_ring_capacity: Option<usize>,
+    /// If set, a retired record only becomes eligible for reclamation once
+    /// the scanning thread local state has performed this many scans after
+    /// the record was retired, even if it is no longer protected.
+    ///
+    /// This is a defense-in-depth measure giving straggling threads, which
+    /// have not yet published their hazard pointers, more time.
+    /// With the local retire strategy, the scans are counted by each thread
+    /// local state for its own records (adopted records keep their age), so
+    /// scans by other threads do not shorten the grace period.
+    /// With the global retire strategy, all scans with this option are counted
+    /// for the shared global queue.
+    /// In neither case is the [`generation`][crate::Hp::generation] advanced.
+    pub reclaim_grace_scans: Option<usize>,
+    /// If set, a thread local state, which still holds more than this many
+    /// retired records after a scan, shares half of them with all other
+    /// threads (local retire strategy only).
+    ///
+    /// The shared records are adopted by the next thread scanning, like those
+    /// abandoned by exited threads, so records protected for a long time do
+    /// not pile up with a single thread, which effectively turns the local
+    /// into a hybrid local/global retire strategy.
+    pub local_spillover_threshold: Option<usize>,
+    /// The maximum number of released hazard pointers each thread local state
+    /// caches for re-use, which must not exceed the (fixed) capacity of its
+    /// cache.
+    ///
+    /// Hazard pointers released beyond this limit are freed right away, so
+    /// they can be acquired by other threads again.
+    pub max_cached_hazards: usize,
+    /// If set, each thread local state's scan cache of protected pointers is
+    /// allocated once with this fixed capacity and never grows.
+    ///
+    /// If more pointers are protected than fit into the cache, they are
+    /// collected and checked in multiple passes, so scans never allocate,
+    /// e.g. for bounded-memory or allocation-free environments.
+    pub scan_cache_fixed_capacity: Option<usize>,
+    /// If set, every time a thread local state has performed this many
+    /// scans, it moves the hazard pointers reserved in its cache to free ones
+    /// closer to the head of the global hazard list, if there are any.
+    ///
+    /// Over time, the hazard pointers in use by long-lived threads become
+    /// scattered across the list, which this keeps compacted towards its
+    /// head.
+    /// Only cached hazard pointers, which are reserved exclusively by their
+    /// thread, are moved, hazard pointers in use by guards never are.
+    pub compact_hazards_interval: Option<u64>,
 }
 
 /********* impl inherent **************************************************************************/
@@ -62,12 +467,26 @@ pub struct Config {
 impl Config {
     #[inline]
     pub fn is_count_release(&self) -> bool {
-        self.count_strategy == Operation::Release
+        self.counts(Operation::Release)
     }
 
     #[inline]
     pub fn is_count_retire(&self) -> bool {
-        self.count_strategy == Operation::Retire
+        self.counts(Operation::Retire)
+    }
+
+    /// Returns `true` if `op` is counted towards the
+    /// [`ops_count_threshold`][Config::ops_count_threshold] according to the
+    /// [`count_strategy`][Config::count_strategy].
+    #[inline]
+    pub(crate) fn counts(&self, op: Operation) -> bool {
+        match (self.count_strategy, op) {
+            (CountStrategy::Release, Operation::Release) => true,
+            (CountStrategy::Retire, Operation::Retire) => true,
+            (CountStrategy::Both, Operation::Release)
+            | (CountStrategy::Both, Operation::Retire) => true,
+            _ => false,
+        }
     }
 }
 
@@ -81,26 +500,202 @@ impl Default for Config {
             max_reserved_hazard_pointers: DEFAULT_MAX_RESERVED_HAZARD_POINTERS,
             ops_count_threshold: DEFAULT_OPS_COUNT_THRESHOLD,
             count_strategy: Default::default(),
+            initial_reserved_hazards: DEFAULT_INITIAL_RESERVED_HAZARDS,
+            fence_mode: DEFAULT_FENCE_MODE,
+            numa_node: None,
+            count_clones: DEFAULT_COUNT_CLONES,
+            total_ops_budget: DEFAULT_TOTAL_OPS_BUDGET,
+            max_hazard_pointers: DEFAULT_MAX_HAZARD_POINTERS,
+            can_reclaim: DEFAULT_CAN_RECLAIM,
+            retire_ring_capacity: DEFAULT_RETIRE_RING_CAPACITY,
+            reclaim_grace_scans: DEFAULT_RECLAIM_GRACE_SCANS,
+            local_spillover_threshold: DEFAULT_LOCAL_SPILLOVER_THRESHOLD,
+            max_cached_hazards: DEFAULT_MAX_CACHED_HAZARDS,
+            scan_cache_fixed_capacity: DEFAULT_SCAN_CACHE_FIXED_CAPACITY,
+            compact_hazards_interval: DEFAULT_COMPACT_HAZARDS_INTERVAL,
         }
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// CountStrategy
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The kind of [`Operation`]s counted towards the
+/// [`ops_count_threshold`][Config::ops_count_threshold].
+#[derive(Copy, Clone, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum CountStrategy {
+    Release,
+    Retire,
+    /// Counting both releases and retires, e.g. for workloads that both retire
+    /// records and release guards frequently.
+    Both,
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for CountStrategy {
+    #[inline]
+    fn default() -> Self {
+        DEFAULT_COUNT_STRATEGY
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // Operation
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// The kinds of operations performed through a thread local state.
 #[derive(Copy, Clone, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
 #[non_exhaustive]
 pub enum Operation {
     Release,
     Retire,
+    /// Protecting a value with a [`Guard`][crate::Guard], which is only
+    /// counted towards the [`total_ops_budget`][Config::total_ops_budget] and
+    /// not at all, if no budget is configured, keeping the protect path free
+    /// of any counting by default.
+    Protect,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// FenceMode
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The kind of memory fences used for synchronizing threads protecting values
+/// with threads scanning for protected values.
+#[derive(Copy, Clone, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum FenceMode {
+    /// Both protecting and scanning threads use `SeqCst` fences/stores.
+    SeqCst,
+    /// Protecting threads use only compiler fences, while scanning threads
+    /// issue a process-wide memory barrier with the `membarrier` system call.
+    ///
+    /// This requires Linux and the `membarrier` feature, otherwise (or if the
+    /// system call is not supported by the kernel), the [`SeqCst`][FenceMode::SeqCst]
+    /// mode is used instead.
+    AsymmetricMembarrier,
 }
 
 /********** impl Default **************************************************************************/
 
-impl Default for Operation {
+impl Default for FenceMode {
     #[inline]
     fn default() -> Self {
-        DEFAULT_COUNT_STRATEGY
+        DEFAULT_FENCE_MODE
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// RuntimeWarning
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A warning about a configuration that is likely to cause problems at
+/// runtime, see [`Hp::validate_runtime`][crate::Hp::validate_runtime].
+#[derive(Copy, Clone, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum RuntimeWarning {
+    /// The hazard pointers that may be cached by all currently live thread
+    /// local states (`required`) exceed the configured
+    /// [`max_hazard_pointers`][Config::max_hazard_pointers] (`max`), which
+    /// can starve threads of hazard pointers.
+    HazardOversubscription { required: usize, max: usize },
+    /// The configured
+    /// [`initial_reserved_hazards`][Config::initial_reserved_hazards]
+    /// (`requested`) exceed the capacity of each thread's hazard cache and are
+    /// hence silently truncated.
+    InitialReservationExceedsCache { requested: usize, capacity: usize },
+}
+
+/********** impl Display **************************************************************************/
+
+impl fmt::Display for RuntimeWarning {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuntimeWarning::HazardOversubscription { required, max } => write!(
+                f,
+                "up to {} hazard pointers may be cached, but at most {} are expected",
+                required, max
+            ),
+            RuntimeWarning::InitialReservationExceedsCache { requested, capacity } => write!(
+                f,
+                "{} initially reserved hazard pointers exceed the cache capacity of {}",
+                requested, capacity
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigBuilder, ConfigError, CountStrategy, FenceMode, HAZARD_CACHE};
+
+    #[test]
+    fn from_config() {
+        let preset = ConfigBuilder::new()
+            .fence_mode(FenceMode::AsymmetricMembarrier)
+            .count_clones(true)
+            .total_ops_budget(64)
+            .retire_ring_capacity(256)
+            .build();
+
+        let config = ConfigBuilder::from_config(preset).ops_count_threshold(16).build();
+        assert_eq!(config.ops_count_threshold, 16);
+        assert_ne!(preset.ops_count_threshold, 16);
+
+        // all other values of the preset survive
+        assert_eq!(config.fence_mode, FenceMode::AsymmetricMembarrier);
+        assert!(config.count_clones);
+        assert_eq!(config.total_ops_budget, Some(64));
+        assert_eq!(config.retire_ring_capacity, Some(256));
+        assert_eq!(config.count_strategy, CountStrategy::Retire);
+        assert_eq!(ConfigBuilder::from_config(preset).build(), preset);
+    }
+
+    #[test]
+    fn zero_threshold() {
+        let res = ConfigBuilder::new().ops_count_threshold(0).try_build();
+        assert_eq!(res, Err(ConfigError::ZeroThreshold));
+        assert!(ConfigBuilder::new().ops_count_threshold(1).try_build().is_ok());
+    }
+
+    #[test]
+    fn reclaim_triggers() {
+        let dead = ConfigBuilder::new().ops_count_threshold(0).count_clones(true);
+        assert_eq!(dead.try_build(), Err(ConfigError::ZeroThreshold));
+
+        // a zero threshold only disables the ops count trigger, but not the others
+        assert!(dead.total_ops_budget(64).try_build().is_ok());
+        assert!(dead.retire_ring_capacity(64).try_build().is_ok());
+    }
+
+    #[test]
+    fn zero_scan_cache_capacity() {
+        let res = ConfigBuilder::new().scan_cache_fixed_capacity(0).try_build();
+        assert_eq!(res, Err(ConfigError::ZeroScanCacheCapacity));
+        let config = ConfigBuilder::new().scan_cache_fixed_capacity(1).build();
+        assert_eq!(ConfigBuilder::from_config(config).build(), config);
+    }
+
+    #[test]
+    fn zero_compaction_interval() {
+        let res = ConfigBuilder::new().compact_hazards_interval(0).try_build();
+        assert_eq!(res, Err(ConfigError::ZeroCompactionInterval));
+        let config = ConfigBuilder::new().compact_hazards_interval(4).build();
+        assert_eq!(ConfigBuilder::from_config(config).build(), config);
+    }
+
+    #[test]
+    fn max_cached_hazards() {
+        let res = ConfigBuilder::new().max_cached_hazards(HAZARD_CACHE + 1).try_build();
+        let err = ConfigError::CachedHazardsExceedCache {
+            requested: HAZARD_CACHE + 1,
+            capacity: HAZARD_CACHE,
+        };
+        assert_eq!(res, Err(err));
+        assert!(ConfigBuilder::new().max_cached_hazards(HAZARD_CACHE).try_build().is_ok());
     }
 }
@@ -1,8 +1,11 @@
+#[cfg(feature = "track-retire-location")]
+use std::panic::Location;
 use std::rc::Rc;
 use std::sync::RwLock;
 
 use conquer_once::Lazy;
-use conquer_reclaim::{BuildReclaimRef, GlobalReclaim, Reclaim, ReclaimRef, Retired};
+use conquer_reclaim::typenum::Unsigned;
+use conquer_reclaim::{BuildReclaimRef, GlobalReclaim, Reclaim, ReclaimRef, Retired, Unlinked};
 
 use crate::config::Config;
 use crate::global::GlobalRef;
@@ -26,6 +29,55 @@ thread_local!(static LOCAL: Rc<Local> = {
     Rc::new(Local::new(config, GlobalRef::from_ref(&HP.state)))
 });
 
+/********** free functions ************************************************************************/
+
+/// Retires `unlinked` through the calling thread's ambient thread local state
+/// of the [`GlobalHp`] reclaimer, so no explicit handle is required.
+///
+/// # Safety
+///
+/// The same invariants as for [`ReclaimRef::retire`] apply, i.e. the record
+/// must no longer be reachable by other threads (except through already
+/// protected pointers) and must not be retired more than once.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::thread;
+///
+/// use conquer_reclaim::typenum::U0;
+/// use conquer_reclaim::Owned;
+/// use hazptr_rewrite::GlobalHp;
+///
+/// type Atomic<T> = conquer_reclaim::Atomic<T, GlobalHp, U0>;
+///
+/// static COUNT: AtomicUsize = AtomicUsize::new(0);
+///
+/// struct DropCount;
+/// impl Drop for DropCount {
+///     fn drop(&mut self) {
+///         COUNT.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// thread::spawn(|| {
+///     let atomic = Atomic::new(DropCount);
+///     let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+///     unsafe { hazptr_rewrite::retire(unlinked) };
+/// })
+/// .join()
+/// .unwrap();
+///
+/// // the record is reclaimed at the latest when the retiring thread exits
+/// assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+/// ```
+#[inline]
+#[cfg_attr(feature = "track-retire-location", track_caller)]
+pub unsafe fn retire<T: 'static, N: Unsigned + 'static>(unlinked: Unlinked<T, GlobalHp, N>) {
+    GlobalHpRef.retire(Unlinked::into_retired(unlinked));
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // GlobalHP
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -88,8 +140,16 @@ unsafe impl ReclaimRef for GlobalHpRef {
     }
 
     #[inline]
+    #[cfg_attr(feature = "track-retire-location", track_caller)]
     unsafe fn retire(self, record: Retired<Self::Reclaimer>) {
+        // the caller's location is not propagated into the closure
+        #[cfg(feature = "track-retire-location")]
+        let location = Location::caller();
+
         LOCAL.with(move |local| {
+            #[cfg(feature = "track-retire-location")]
+            local.retire_at(record.into_raw(), location);
+            #[cfg(not(feature = "track-retire-location"))]
             local.retire(record.into_raw());
         });
     }
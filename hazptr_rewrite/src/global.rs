@@ -1,7 +1,17 @@
+use core::cmp;
 use core::convert::AsRef;
-use core::sync::atomic::{self, Ordering};
+use core::ops::{Deref, DerefMut};
+use core::ptr;
+use core::sync::atomic::{self, AtomicPtr, AtomicUsize, Ordering};
 
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::config::FenceMode;
 use crate::hazard::{HazardList, HazardPtr, ProtectStrategy, ProtectedPtr, ProtectedResult};
+use crate::membarrier;
+use crate::registry::{PendingRegistry, PendingSlot};
+use crate::retire::global_retire::Taken;
 use crate::retire::GlobalRetireState;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -49,6 +59,12 @@ impl<'global> AsRef<Global> for GlobalRef<'global> {
     }
 }
 
+/// The minimum pressure factor (see
+/// [`Hp::raise_reclaim_pressure`][crate::Hp::raise_reclaim_pressure]), at
+/// which a chunked reclamation ignores all weak protections (see
+/// `Guard::downgrade`).
+pub const WEAK_DROP_PRESSURE: usize = 8;
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // Global
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -57,6 +73,42 @@ impl<'global> AsRef<Global> for GlobalRef<'global> {
 pub(crate) struct Global {
     pub(crate) retire_state: GlobalRetireState,
     hazards: HazardList,
+    /// The (effective) fence mode, which all threads must agree on.
+    fence_mode: FenceMode,
+    /// The registry of the pending record counts of all live thread local
+    /// states (local retire strategy only).
+    pending: PendingRegistry,
+    /// The number of currently live thread local states.
+    live_locals: AtomicUsize,
+    /// The number of thread local states currently retrying to protect a
+    /// value, which keeps changing while they do.
+    spinning: AtomicUsize,
+    /// The current generation, with which all retired records are tagged.
+    generation: AtomicUsize,
+    /// The number of scans of the global queue performed with a grace period
+    /// (see [`Config::reclaim_grace_scans`][crate::Config::reclaim_grace_scans]),
+    /// which is separate from the (user visible) generation.
+    scan_count: AtomicUsize,
+    /// The factor by which the next chunked reclamation raises its limit, as
+    /// requested by retiring threads (0, if none has been requested).
+    pressure: AtomicUsize,
+    /// The scan caches for ad-hoc reclamation calls.
+    scan_caches: ScanCachePool,
+    /// The number of thread local states that have been registered so far.
+    #[cfg(feature = "deterministic-hazards")]
+    local_count: AtomicUsize,
+    /// The highest number of protected hazards observed by any scan.
+    #[cfg(feature = "stats")]
+    peak_protected: AtomicUsize,
+    /// The total number of records retired by all threads.
+    #[cfg(feature = "stats")]
+    retired_total: AtomicUsize,
+    /// The total number of records reclaimed by all threads.
+    #[cfg(feature = "stats")]
+    reclaimed_total: AtomicUsize,
+    /// The total number of scans performed by all threads.
+    #[cfg(feature = "stats")]
+    scans_total: AtomicUsize,
 }
 
 /********** impl inherent *************************************************************************/
@@ -64,7 +116,216 @@ pub(crate) struct Global {
 impl Global {
     #[inline]
     pub const fn new(retire_state: GlobalRetireState) -> Self {
-        Self { retire_state, hazards: HazardList::new() }
+        Self {
+            retire_state,
+            hazards: HazardList::new(),
+            fence_mode: FenceMode::SeqCst,
+            pending: PendingRegistry::new(),
+            live_locals: AtomicUsize::new(0),
+            spinning: AtomicUsize::new(0),
+            generation: AtomicUsize::new(0),
+            scan_count: AtomicUsize::new(0),
+            pressure: AtomicUsize::new(0),
+            scan_caches: ScanCachePool::new(),
+            #[cfg(feature = "deterministic-hazards")]
+            local_count: AtomicUsize::new(0),
+            #[cfg(feature = "stats")]
+            peak_protected: AtomicUsize::new(0),
+            #[cfg(feature = "stats")]
+            retired_total: AtomicUsize::new(0),
+            #[cfg(feature = "stats")]
+            reclaimed_total: AtomicUsize::new(0),
+            #[cfg(feature = "stats")]
+            scans_total: AtomicUsize::new(0),
+        }
+    }
+
+    /// Creates a new [`Global`] using the fence `mode`, if it is supported on
+    /// the current platform, or the `SeqCst` mode otherwise.
+    #[inline]
+    pub fn with_fence_mode(retire_state: GlobalRetireState, mode: FenceMode) -> Self {
+        let fence_mode = match mode {
+            FenceMode::AsymmetricMembarrier if membarrier::register() => mode,
+            _ => FenceMode::SeqCst,
+        };
+
+        let mut global = Self::new(retire_state);
+        global.fence_mode = fence_mode;
+        global
+    }
+
+    /// Returns the effective fence mode.
+    #[inline]
+    pub fn fence_mode(&self) -> FenceMode {
+        self.fence_mode
+    }
+
+    /// Returns the number of currently live thread local states.
+    #[inline]
+    pub fn live_locals(&self) -> usize {
+        self.live_locals.load(Ordering::Relaxed)
+    }
+
+    /// Returns the current generation.
+    #[inline]
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// Requests that the next chunked reclamation raises its limit by (at
+    /// least) `factor`.
+    #[inline]
+    pub fn raise_pressure(&self, factor: usize) {
+        self.pressure.fetch_max(factor, Ordering::Relaxed);
+    }
+
+    /// Takes the currently requested pressure factor, which is at least 1.
+    #[inline]
+    fn take_pressure(&self) -> usize {
+        cmp::max(self.pressure.swap(0, Ordering::Relaxed), 1)
+    }
+
+    /// Advances the current generation and returns the new one.
+    #[inline]
+    pub fn advance_generation(&self) -> usize {
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Returns the number of scans of the global queue performed with a grace
+    /// period.
+    #[inline]
+    pub fn scan_count(&self) -> usize {
+        self.scan_count.load(Ordering::SeqCst)
+    }
+
+    /// Advances the number of scans of the global queue performed with a
+    /// grace period and returns the new one.
+    #[inline]
+    pub fn advance_scan_count(&self) -> usize {
+        self.scan_count.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Increments the number of live thread local states.
+    #[inline]
+    pub fn increment_live_locals(&self) {
+        self.live_locals.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Decrements the number of live thread local states.
+    #[inline]
+    pub fn decrement_live_locals(&self) {
+        self.live_locals.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of thread local states currently spinning in a
+    /// protect loop.
+    #[inline]
+    pub fn threads_spinning(&self) -> usize {
+        self.spinning.load(Ordering::Relaxed)
+    }
+
+    /// Counts a thread local state entering (`true`) or leaving (`false`) a
+    /// protect loop.
+    #[inline]
+    pub fn set_spinning(&self, spinning: bool) {
+        if spinning {
+            self.spinning.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.spinning.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Registers a new thread local state and returns its index.
+    #[cfg(feature = "deterministic-hazards")]
+    #[inline]
+    pub fn register_local(&self) -> usize {
+        self.local_count.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Attempts to acquire the hazard pointer at index `idx`, see
+    /// [`HazardList::try_reserve_at`].
+    #[cfg(feature = "deterministic-hazards")]
+    #[inline]
+    pub fn try_reserve_hazard_at(&self, idx: usize) -> Option<&HazardPtr> {
+        self.hazards.try_reserve_at(idx)
+    }
+
+    /// Acquires a slot for publishing a thread local state's number of pending
+    /// records.
+    #[inline]
+    pub fn acquire_pending_slot(&self) -> &PendingSlot {
+        self.pending.acquire()
+    }
+
+    /// Returns the number of pending records of all live thread local states.
+    #[inline]
+    pub fn pending(&self) -> Vec<usize> {
+        self.pending.pending()
+    }
+
+    /// Visits every currently protected pointer along with the thread owning
+    /// the protecting hazard pointer.
+    #[cfg(feature = "track-owner")]
+    #[inline]
+    pub fn visit_protected(&self, mut visit: impl FnMut(usize, Option<std::thread::ThreadId>)) {
+        for hazard in self.hazards.iter() {
+            match hazard.protected(Ordering::Acquire) {
+                ProtectedResult::Protected(protected)
+                | ProtectedResult::WeakProtected(protected) => {
+                    visit(protected.address(), crate::owner::owner(hazard))
+                }
+                ProtectedResult::Unprotected => {}
+            }
+        }
+    }
+
+    /// Returns a sorted snapshot of all currently protected pointers,
+    /// including weakly protected ones.
+    #[inline]
+    pub fn protected_snapshot(&self) -> Vec<ProtectedPtr> {
+        let mut vec = Vec::new();
+        let _ = self.collect_protected_hazards_from(&mut vec, 0, usize::MAX);
+        vec.sort_unstable();
+        vec
+    }
+
+    /// Attempts to acquire a thread-reserved hazard pointer located before
+    /// `hazard`, see [`HazardList::try_reserve_before`].
+    #[inline]
+    pub fn try_reserve_hazard_before(&self, hazard: &HazardPtr) -> Option<&HazardPtr> {
+        self.hazards.try_reserve_before(hazard)
+    }
+
+    /// Returns the index of `hazard` within the list of all hazard pointers.
+    #[cfg(test)]
+    pub fn hazard_index(&self, hazard: &HazardPtr) -> Option<usize> {
+        self.hazards.iter().position(|curr| ptr::eq(curr, hazard))
+    }
+
+    /// Returns the total number of currently allocated hazard pointers.
+    #[inline]
+    pub fn hazard_capacity(&self) -> usize {
+        self.hazards.capacity()
+    }
+
+    /// Returns an estimate of the number of bytes allocated for the hazard
+    /// list, the retire buffers of all live thread local states and the
+    /// abandoned queue.
+    #[inline]
+    pub fn overhead_bytes(&self) -> usize {
+        let abandoned = match &self.retire_state {
+            GlobalRetireState::LocalStrategy(abandoned) => abandoned.allocated_bytes(),
+            GlobalRetireState::GlobalStrategy(_) => 0,
+        };
+
+        self.hazards.allocated_bytes() + self.pending.buffer_bytes() + abandoned
+    }
+
+    /// Pre-allocates enough hazard pointers for at least `count` to be
+    /// acquired without any further allocations.
+    #[inline]
+    pub fn reserve_hazards(&self, count: usize) {
+        self.hazards.reserve(count);
     }
 
     #[inline]
@@ -80,20 +341,536 @@ impl Global {
     #[inline]
     pub fn collect_protected_hazards(&self, vec: &mut Vec<ProtectedPtr>, order: Ordering) {
         assert_eq!(order, Ordering::SeqCst, "this method must have `SeqCst` ordering");
+        self.collect_hazards(vec, false);
+    }
+
+    /// Collects at most `capacity` protected hazards into `vec`, skipping the
+    /// first `start` hazards, and returns the index of the first hazard that
+    /// has not been visited, if `vec` was filled up before all hazards could
+    /// be visited.
+    ///
+    /// Weak protections are collected like regular ones.
+    #[inline]
+    pub fn collect_protected_hazards_from(
+        &self,
+        vec: &mut Vec<ProtectedPtr>,
+        start: usize,
+        capacity: usize,
+    ) -> Option<usize> {
+        vec.clear();
+
+        // (GLO:3) each pass synchronizes with the protecting threads like (GLO:1)
+        if let FenceMode::AsymmetricMembarrier = self.fence_mode {
+            membarrier::barrier();
+        }
+
+        atomic::fence(Ordering::SeqCst);
+
+        for (idx, hazard) in self.hazards.iter().enumerate().skip(start) {
+            match hazard.protected(Ordering::Relaxed) {
+                ProtectedResult::Protected(protected)
+                | ProtectedResult::WeakProtected(protected) => {
+                    if vec.len() == capacity {
+                        return Some(idx);
+                    }
+
+                    vec.push(protected);
+                }
+                ProtectedResult::Unprotected => {}
+            }
+        }
+
+        None
+    }
+
+    /// Returns `true` if any hazard pointer currently protects `addr`, either
+    /// regularly or weakly.
+    #[inline]
+    pub fn is_protected(&self, addr: usize) -> bool {
+        // (GLO:2) synchronizes with the protecting threads like (GLO:1)
+        if let FenceMode::AsymmetricMembarrier = self.fence_mode {
+            membarrier::barrier();
+        }
+
+        atomic::fence(Ordering::SeqCst);
+
+        self.hazards.iter().any(|hazard| match hazard.protected(Ordering::Relaxed) {
+            ProtectedResult::Protected(protected) | ProtectedResult::WeakProtected(protected) => {
+                protected.address() == addr
+            }
+            ProtectedResult::Unprotected => false,
+        })
+    }
+
+    /// Collects all protected hazards into `vec`, invalidating and ignoring
+    /// all weak protections, if `drop_weak` is `true`.
+    #[inline]
+    fn collect_hazards(&self, vec: &mut Vec<ProtectedPtr>, drop_weak: bool) {
         vec.clear();
+        // every allocated hazard pointer may protect a value, so the cache is grown at most once
+        // up front rather than repeatedly during the iteration
+        vec.reserve(self.hazards.capacity());
+
+        // (GLO:1) in the asymmetric mode, this heavy barrier synchronizes with the compiler fence
+        // (HAZ:2) on every thread protecting a value
+        if let FenceMode::AsymmetricMembarrier = self.fence_mode {
+            membarrier::barrier();
+        }
 
         atomic::fence(Ordering::SeqCst);
 
         for hazard in self.hazards.iter() {
             match hazard.protected(Ordering::Relaxed) {
                 ProtectedResult::Protected(protected) => vec.push(protected),
-                ProtectedResult::Abort => return,
-                _ => {}
+                // a weak protection is only ignored if it can be invalidated before the holder
+                // upgrades (or releases) it
+                ProtectedResult::WeakProtected(protected) => {
+                    if !(drop_weak && hazard.try_invalidate_weak(protected)) {
+                        vec.push(protected);
+                    }
+                }
+                ProtectedResult::Unprotected => {}
+            }
+        }
+
+        #[cfg(feature = "stats")]
+        self.peak_protected.fetch_max(vec.len(), Ordering::Relaxed);
+    }
+
+    /// Returns the highest number of simultaneously protected pointers that
+    /// has been observed by any scan so far.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn peak_protected(&self) -> usize {
+        self.peak_protected.load(Ordering::Relaxed)
+    }
+
+    /// Adds `count` records to the total number of retired records.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn record_retired(&self, count: usize) {
+        self.retired_total.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Adds `count` records to the total number of reclaimed records.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn record_reclaimed(&self, count: usize) {
+        self.reclaimed_total.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Records a scan, which has reclaimed `reclaimed` records.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn record_scan(&self, reclaimed: usize) {
+        self.scans_total.fetch_add(1, Ordering::Relaxed);
+        self.record_reclaimed(reclaimed);
+    }
+
+    /// Returns the total numbers of retired and reclaimed records and of
+    /// performed scans.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn totals(&self) -> (usize, usize, usize) {
+        (
+            self.retired_total.load(Ordering::Relaxed),
+            self.reclaimed_total.load(Ordering::Relaxed),
+            self.scans_total.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Resets the global state for re-use by reclaiming all globally stored
+    /// retired records and freeing all hazard pointers.
+    ///
+    /// All allocated hazard pointers remain in place for later re-use.
+    #[inline]
+    pub fn reset(&mut self) {
+        for hazard in self.hazards.iter() {
+            hazard.set_free(Ordering::Relaxed);
+        }
+
+        #[cfg(feature = "deterministic-hazards")]
+        {
+            *self.local_count.get_mut() = 0;
+        }
+
+        match &mut self.retire_state {
+            GlobalRetireState::GlobalStrategy(queue) => queue.reset(),
+            // dropping the merged node reclaims all abandoned records
+            GlobalRetireState::LocalStrategy(abandoned) => drop(abandoned.take_all_and_merge()),
+        }
+    }
+
+    /// Reclaims all globally stored retired records that are no longer
+    /// protected and returns the number of reclaimed records.
+    ///
+    /// With the global retire strategy, these are the records in the global
+    /// queue, with the local strategy, these are all abandoned records.
+    /// The `scan_cache` is used for storing the collected protected hazards
+    /// and `can_reclaim` is the optional reclamation veto callback.
+    #[inline]
+    pub fn reclaim_all_unprotected(
+        &self,
+        scan_cache: &mut Vec<ProtectedPtr>,
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        match &self.retire_state {
+            GlobalRetireState::GlobalStrategy(queue) => {
+                if queue.is_empty() {
+                    return 0;
+                }
+
+                self.collect_protected_hazards(scan_cache, Ordering::SeqCst);
+                scan_cache.sort_unstable_by_key(|protected| protected.address());
+                unsafe { queue.reclaim_all_unprotected(scan_cache, can_reclaim) }
+            }
+            GlobalRetireState::LocalStrategy(abandoned) => match abandoned.take_all_and_merge() {
+                Some(mut node) => {
+                    self.collect_protected_hazards(scan_cache, Ordering::SeqCst);
+                    scan_cache.sort_unstable_by_key(|protected| protected.address());
+                    let reclaimed =
+                        unsafe { node.reclaim_all_unprotected(scan_cache, can_reclaim) };
+
+                    // any records that could not be reclaimed are abandoned again
+                    if !node.is_empty() {
+                        abandoned.push(node);
+                    }
+
+                    reclaimed
+                }
+                None => 0,
+            },
+        }
+    }
+
+    /// Reclaims all globally stored retired records, which were retired before
+    /// `generation` and are no longer protected, and returns the number of
+    /// reclaimed records.
+    ///
+    /// See [`reclaim_all_unprotected`][Global::reclaim_all_unprotected].
+    #[inline]
+    pub fn reclaim_older_than(
+        &self,
+        generation: usize,
+        scan_cache: &mut Vec<ProtectedPtr>,
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        self.collect_protected_hazards(scan_cache, Ordering::SeqCst);
+        scan_cache.sort_unstable_by_key(|protected| protected.address());
+
+        match &self.retire_state {
+            GlobalRetireState::GlobalStrategy(queue) => unsafe {
+                queue.reclaim_older_than(generation, scan_cache, can_reclaim)
+            },
+            GlobalRetireState::LocalStrategy(abandoned) => match abandoned.take_all_and_merge() {
+                Some(mut node) => {
+                    let reclaimed =
+                        unsafe { node.reclaim_older_than(generation, scan_cache, can_reclaim) };
+                    if !node.is_empty() {
+                        abandoned.push(node);
+                    }
+
+                    reclaimed
+                }
+                None => 0,
+            },
+        }
+    }
+
+    /// Reclaims at most `max` records from the global queue (global retire
+    /// strategy only), re-using the protected hazards collected in `set` as
+    /// far as possible, and returns the number of reclaimed records.
+    ///
+    /// The limit is raised by any pressure factor requested since the last
+    /// chunk (see [`raise_pressure`][Global::raise_pressure]) and a factor of
+    /// at least [`WEAK_DROP_PRESSURE`] ignores all weak protections.
+    #[inline]
+    pub fn reclaim_chunk(
+        &self,
+        set: &mut ProtectedSet,
+        max: usize,
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        let pressure = self.take_pressure();
+        let drop_weak = pressure >= WEAK_DROP_PRESSURE;
+        // the set must be collected anew, if it may contain weak protections or has been collected
+        // from the hazards of another global state
+        if drop_weak || !set.is_owned_by(self) {
+            set.generation = None;
+        }
+
+        let max = max.saturating_mul(pressure);
+        match &self.retire_state {
+            GlobalRetireState::GlobalStrategy(queue) => unsafe {
+                queue.reclaim_chunk(max, set, can_reclaim, &mut |set| {
+                    self.collect_set(set, drop_weak)
+                })
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Takes all records from the global queue (global retire strategy only).
+    #[inline]
+    pub fn take_retired(&self) -> Taken<'_> {
+        match &self.retire_state {
+            GlobalRetireState::GlobalStrategy(queue) => Taken::new(queue, |protected| {
+                self.collect_protected_hazards(protected, Ordering::SeqCst)
+            }),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Visits the address and generation of every record in the global queue
+    /// (global retire strategy only), see
+    /// [`RetiredQueue::iter`][crate::retire::global_retire::RetiredQueue::iter].
+    #[inline]
+    pub fn visit_retired(&self, mut visit: impl FnMut(usize, usize)) {
+        match &self.retire_state {
+            GlobalRetireState::GlobalStrategy(queue) => {
+                for idx in 0..queue.len() {
+                    for (address, generation) in queue.shard(idx).iter() {
+                        visit(address, generation);
+                    }
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Borrows a scan cache from the pool for an ad-hoc reclamation call, which
+    /// is returned to the pool once it is dropped.
+    #[inline]
+    pub fn borrow_scan_cache(&self) -> PooledScanCache<'_> {
+        self.scan_caches.borrow()
+    }
+
+    /// Returns a snapshot of the global queue's reclamation progress (global
+    /// retire strategy only).
+    #[inline]
+    pub fn reclaim_progress(&self) -> ReclaimProgress {
+        match &self.retire_state {
+            GlobalRetireState::GlobalStrategy(queue) => ReclaimProgress {
+                generation: self.generation(),
+                pending: queue.pending(),
+                last_reclaimed: queue.last_reclaimed(),
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Advances the generation and collects all currently protected hazards
+    /// into `set`, ignoring all weak protections, if `drop_weak` is `true`.
+    #[inline]
+    fn collect_set(&self, set: &mut ProtectedSet, drop_weak: bool) {
+        // all records retired before the generation is advanced are no longer reachable, so they
+        // can not be protected anew after the hazards have been collected
+        set.generation = Some(self.advance_generation());
+        set.owner = self as *const Self as usize;
+        self.collect_hazards(&mut set.protected, drop_weak);
+        set.protected.sort_unstable_by_key(|protected| protected.address());
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Global {
+    #[inline(never)]
+    fn drop(&mut self) {
+        // all remaining records are checked against the hazard pointers explicitly before any field
+        // is dropped, so no record protected by a leaked hazard pointer (e.g. of a forgotten guard)
+        // is reclaimed, regardless of the order in which the retire state and the hazard list are
+        // dropped afterwards; such records are leaked instead
+        let protected = self.protected_snapshot();
+        self.retire_state.reclaim_or_leak_all(&protected);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// ScanCachePool
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+const SCAN_CACHE_POOL_SIZE: usize = 4;
+
+/// A small pool of scan caches, which transient reclaimers (i.e. reclamation
+/// calls not made through a thread local state) borrow for the duration of a
+/// scan, instead of allocating a new cache for every call.
+#[derive(Debug)]
+struct ScanCachePool {
+    slots: [AtomicPtr<Vec<ProtectedPtr>>; SCAN_CACHE_POOL_SIZE],
+}
+
+/********** impl inherent *************************************************************************/
+
+impl ScanCachePool {
+    /// Creates a new empty [`ScanCachePool`].
+    #[inline]
+    const fn new() -> Self {
+        Self {
+            slots: [
+                AtomicPtr::new(ptr::null_mut()),
+                AtomicPtr::new(ptr::null_mut()),
+                AtomicPtr::new(ptr::null_mut()),
+                AtomicPtr::new(ptr::null_mut()),
+            ],
+        }
+    }
+
+    /// Borrows a pooled scan cache or allocates a new one, if all pooled ones
+    /// are currently borrowed.
+    #[inline]
+    fn borrow(&self) -> PooledScanCache<'_> {
+        let cache = self
+            .slots
+            .iter()
+            .map(|slot| slot.swap(ptr::null_mut(), Ordering::Acquire))
+            .find(|cache| !cache.is_null())
+            .map(|cache| unsafe { Box::from_raw(cache) })
+            .unwrap_or_default();
+
+        PooledScanCache { pool: self, cache: Some(cache) }
+    }
+
+    /// Returns `cache` to the pool or drops it, if the pool is full.
+    #[inline]
+    fn give_back(&self, cache: Box<Vec<ProtectedPtr>>) {
+        let cache = Box::into_raw(cache);
+        for slot in self.slots.iter() {
+            if slot
+                .compare_exchange(ptr::null_mut(), cache, Ordering::Release, Ordering::Relaxed)
+                .is_ok()
+            {
+                return;
+            }
+        }
+
+        drop(unsafe { Box::from_raw(cache) });
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for ScanCachePool {
+    #[inline(never)]
+    fn drop(&mut self) {
+        for slot in self.slots.iter_mut() {
+            let cache = *slot.get_mut();
+            if !cache.is_null() {
+                drop(unsafe { Box::from_raw(cache) });
             }
         }
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// PooledScanCache
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A scan cache borrowed from the [`ScanCachePool`], which is returned to the
+/// pool when it is dropped.
+#[derive(Debug)]
+pub(crate) struct PooledScanCache<'pool> {
+    pool: &'pool ScanCachePool,
+    cache: Option<Box<Vec<ProtectedPtr>>>,
+}
+
+/********** impl Deref ****************************************************************************/
+
+impl Deref for PooledScanCache<'_> {
+    type Target = Vec<ProtectedPtr>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.cache.as_ref().unwrap()
+    }
+}
+
+/********** impl DerefMut *************************************************************************/
+
+impl DerefMut for PooledScanCache<'_> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.cache.as_mut().unwrap()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for PooledScanCache<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.pool.give_back(self.cache.take().unwrap());
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// ReclaimProgress
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A snapshot of the reclamation progress of the global retire queue, see
+/// [`Hp::reclaim_progress`][crate::Hp::reclaim_progress].
+///
+/// Each value is read individually, so under concurrent retirement and
+/// reclamation the values may stem from slightly different points in time.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct ReclaimProgress {
+    /// The current generation.
+    pub generation: usize,
+    /// The number of retired records that have not yet been reclaimed.
+    pub pending: usize,
+    /// The number of records reclaimed by the most recent scan of the global
+    /// queue.
+    pub last_reclaimed: usize,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// ProtectedSet
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A set of protected pointers collected by a scan, which can be re-used by
+/// consecutive scans, see [`Hp::reclaim_chunk`][crate::Hp::reclaim_chunk].
+///
+/// A set collected in some generation remains complete for all records that
+/// were retired in earlier generations, since these were already unreachable
+/// when it was collected and hence can not have been protected since.
+#[derive(Debug, Default)]
+pub struct ProtectedSet {
+    protected: Vec<ProtectedPtr>,
+    /// The generation in which the set was collected, if it has been at all.
+    generation: Option<usize>,
+    /// The address of the global state from which the set was collected.
+    owner: usize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl ProtectedSet {
+    /// Creates a new empty [`ProtectedSet`], which is collected on first use.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if the set has been collected from `global`.
+    #[inline]
+    fn is_owned_by(&self, global: &Global) -> bool {
+        self.owner == global as *const Global as usize
+    }
+
+    /// Returns `true` if the set is complete for records retired in the given
+    /// `generation`.
+    #[inline]
+    pub(crate) fn covers(&self, generation: usize) -> bool {
+        self.generation.map_or(false, |collected| generation < collected)
+    }
+
+    /// Returns the sorted protected pointers.
+    #[inline]
+    pub(crate) fn protected(&self) -> &[ProtectedPtr] {
+        &self.protected
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // Ref
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -105,3 +882,133 @@ enum Ref<'a> {
     Ref(&'a Global),
     Raw(*const Global),
 }
+
+#[cfg(test)]
+mod tests {
+    use std::ptr::NonNull;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::hazard::{ProtectStrategy, ProtectedResult};
+    use crate::test_util::retired;
+    use crate::{GlobalRetire, Hp};
+
+    use super::ProtectedSet;
+
+    #[test]
+    fn collect_protected_after_not_yet_used() {
+        let hp = Hp::<GlobalRetire>::default();
+        let _ = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+
+        // simulates threads losing the race for the hazard at index 1 to another thread that has
+        // not yet completed its CAS and consequently protecting values through the hazards at
+        // index 2 and 3 of the same node, while the hazard at index 1 is still unused
+        let unused = hp.state.hazards.iter().nth(1).unwrap();
+        assert_eq!(unused.protected(Ordering::Relaxed), ProtectedResult::Unprotected);
+        let mut values = [1, 2];
+        let hazards: Vec<_> = hp.state.hazards.iter().skip(2).take(2).collect();
+        for (hazard, value) in hazards.iter().zip(values.iter_mut()) {
+            hazard.set_protected(NonNull::from(value).cast());
+        }
+
+        // the scan must not stop at the unused hazard
+        let mut protected = Vec::new();
+        hp.state.collect_protected_hazards(&mut protected, Ordering::SeqCst);
+        let mut expected: Vec<_> =
+            values.iter().map(|value| value as *const i32 as usize).collect();
+        let mut addresses: Vec<_> = protected.iter().map(|ptr| ptr.address()).collect();
+        expected.sort_unstable();
+        addresses.sort_unstable();
+        assert_eq!(addresses, expected);
+
+        hazards.iter().for_each(|hazard| hazard.set_free(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn scan_cache_pool() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let local = hp.build_local(None);
+
+        // the protected record is retained by each ad-hoc reclamation, so each call collects the
+        // protected hazards into a scan cache
+        let retired = retired(&COUNT);
+        let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+        hazard.set_protected(NonNull::new(retired.address() as *mut ()).unwrap());
+        local.retire(retired);
+
+        assert_eq!(hp.reclaim_until_quiescent(1), 0);
+        let cache = hp.state.scan_caches.slots[0].load(Ordering::Relaxed);
+        assert!(!cache.is_null());
+        let buffer = unsafe { (*cache).as_ptr() };
+
+        // the same cache (and its buffer) is re-used by all following calls
+        for _ in 0..100 {
+            assert_eq!(hp.reclaim_until_quiescent(1), 0);
+            assert_eq!(hp.reclaim_older_than(hp.generation()), 0);
+            assert_eq!(hp.state.scan_caches.slots[0].load(Ordering::Relaxed), cache);
+            assert_eq!(unsafe { (*cache).as_ptr() }, buffer);
+        }
+
+        hazard.set_free(Ordering::Relaxed);
+        assert_eq!(hp.reclaim_until_quiescent(1), 1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn reclaim_chunk() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let local = hp.build_local(None);
+        let retire = |records| {
+            for _ in 0..records {
+                local.retire(retired(&COUNT));
+            }
+        };
+
+        // a hazard protecting some unrelated value
+        let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+        hazard.set_protected(NonNull::from(&hp).cast());
+
+        retire(10);
+        let before = hp.generation();
+        let mut set = ProtectedSet::new();
+
+        // the backlog is drained in chunks, but the hazards are collected (which advances the
+        // generation) only once
+        assert_eq!(hp.reclaim_chunk(&mut set, 4), 4);
+        assert_eq!(hp.reclaim_chunk(&mut set, 4), 4);
+        assert_eq!(hp.reclaim_chunk(&mut set, 4), 2);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 10);
+        assert_eq!(hp.generation(), before + 1);
+
+        // records retired after the collection require collecting the hazards anew
+        retire(1);
+        assert_eq!(hp.reclaim_chunk(&mut set, 4), 1);
+        assert_eq!(hp.generation(), before + 2);
+    }
+
+    #[test]
+    fn reclaim_chunk_with_foreign_set() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let (hp, other) = (Hp::<GlobalRetire>::default(), Hp::<GlobalRetire>::default());
+        let (local, other_local) = (hp.build_local(None), other.build_local(None));
+        let protected = retired(&COUNT);
+        let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+        hazard.set_protected(NonNull::new(protected.address() as *mut ()).unwrap());
+        local.retire(protected);
+
+        // a set collected from another `Hp` (without any protected hazards) is not used as is
+        let mut set = ProtectedSet::new();
+        other_local.retire(retired(&COUNT));
+        assert_eq!(other.reclaim_chunk(&mut set, 4), 1);
+        assert_eq!(hp.reclaim_chunk(&mut set, 4), 0);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+
+        hazard.set_free(Ordering::Relaxed);
+        assert_eq!(hp.reclaim_chunk(&mut ProtectedSet::new(), 4), 1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 2);
+    }
+}
@@ -1,20 +1,32 @@
+use core::array;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, Index, IndexMut};
+use core::ptr::{self, NonNull};
 use core::sync::atomic::Ordering;
 
+#[cfg(not(feature = "std"))]
+use alloc::{rc::Rc, vec::Vec};
+#[cfg(feature = "std")]
+use std::rc::Rc;
+
 use conquer_reclaim::conquer_pointer::{
-    MarkedPtr,
+    MarkedNonNull, MarkedPtr,
     MaybeNull::{self, NotNull, Null},
 };
 use conquer_reclaim::typenum::Unsigned;
 use conquer_reclaim::{Atomic, NotEqualError, Protect, Reclaim, Shared};
 
 use crate::config::Operation;
-use crate::hazard::{HazardPtr, ProtectStrategy};
+use crate::global::Global;
+use crate::hazard::{HazardPtr, ProtectStrategy, ProtectedPtr, ProtectedResult};
 use crate::local::LocalHandle;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // Guard
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
+#[must_use = "a guard releases its hazard pointer right away, if it is dropped"]
 pub struct Guard<'local, 'global, R> {
     /// Hazards are borrowed through the local handle from global state, so they
     /// act like `'global` references.
@@ -30,6 +42,7 @@ impl<R> Clone for Guard<'_, '_, R> {
     #[inline]
     fn clone(&self) -> Self {
         let local = self.local.clone();
+        local.as_ref().try_increase_ops_count_on_clone();
         let hazard = match unsafe { (*self.hazard).protected(Ordering::Relaxed).protected() } {
             Some(protected) => local.as_ref().get_hazard(ProtectStrategy::Protect(protected)),
             None => local.as_ref().get_hazard(ProtectStrategy::ReserveOnly),
@@ -43,7 +56,7 @@ impl<R> Clone for Guard<'_, '_, R> {
         unsafe {
             // TODO: is relaxed enough?
             if let Some(protected) = (*source.hazard).protected(Ordering::Relaxed).protected() {
-                (*self.hazard).set_protected(protected.into_inner(), Ordering::SeqCst);
+                self.set_protected(protected.into_inner());
             }
         }
     }
@@ -57,13 +70,408 @@ impl<'local, 'global, R> Guard<'local, 'global, R> {
         let hazard = local.as_ref().get_hazard(ProtectStrategy::ReserveOnly);
         Self { hazard, local }
     }
+
+    /// Returns `true` if the guard's hazard pointer is merely reserved and does
+    /// not currently protect any value.
+    #[inline]
+    pub fn is_reserved(&self) -> bool {
+        unsafe { (*self.hazard).is_thread_reserved(Ordering::Relaxed) }
+    }
+
+    /// Returns `true` if the guard's hazard pointer currently protects a
+    /// value.
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        unsafe { (*self.hazard).protected(Ordering::Relaxed).protected().is_some() }
+    }
+
+    /// Returns the pointer currently protected by the guard without re-loading
+    /// its source or `None`, if the guard is merely reserved or its protection
+    /// is only weak (see [`downgrade`][Guard::downgrade]).
+    #[inline]
+    pub fn protected(&self) -> Option<ProtectedPtr> {
+        unsafe { (*self.hazard).protected(Ordering::Acquire).protected() }
+    }
+
+    /// Downgrades the guard's current protection to a weak one, which
+    /// reclaiming threads may ignore under high memory pressure (see
+    /// [`WEAK_DROP_PRESSURE`][crate::WEAK_DROP_PRESSURE]).
+    ///
+    /// This is useful e.g. for caches, which can simply re-fetch a value that
+    /// has been reclaimed in the meantime.
+    /// The weakly protected value must not be accessed before it has been
+    /// [`upgrade`][Guard::upgrade]d again.
+    /// Does nothing if the guard does not currently protect any value.
+    #[inline]
+    pub fn downgrade(&mut self) {
+        if let Some(protected) = unsafe { (*self.hazard).protected(Ordering::Relaxed).protected() }
+        {
+            unsafe { (*self.hazard).set_weak_protected(protected.into_inner()) };
+        }
+    }
+
+    /// Returns `true` if the guard's weak protection has been invalidated by a
+    /// reclaiming thread, meaning the value may already have been reclaimed.
+    #[inline]
+    pub fn is_invalidated(&self) -> bool {
+        unsafe { (*self.hazard).is_weak_invalidated(Ordering::Relaxed) }
+    }
+
+    /// Upgrades the guard's weak protection to a regular one and returns a
+    /// reference to the protected value, unless the protection has been
+    /// invalidated in the meantime.
+    ///
+    /// If the protection has been invalidated, the guard is released and
+    /// `None` is returned, so the value must be fetched anew.
+    /// `None` is also returned if the guard does not weakly protect any value.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure that the weakly protected value is of type
+    /// `T`.
+    #[inline]
+    pub unsafe fn upgrade<T>(&mut self) -> Option<&T> {
+        let hazard = &*self.hazard;
+        match hazard.protected(Ordering::Relaxed) {
+            ProtectedResult::WeakProtected(protected) if hazard.try_upgrade_weak(protected) => {
+                Some(&*(protected.into_inner().as_ptr() as *const T))
+            }
+            _ => {
+                if hazard.is_weak_invalidated(Ordering::Relaxed) {
+                    hazard.set_thread_reserved(Ordering::Relaxed);
+                }
+
+                None
+            }
+        }
+    }
+
+    /// Converts the guard into a [`RawGuardToken`], e.g. for storing it in a
+    /// plain (FFI) struct, without releasing its hazard pointer.
+    ///
+    /// The guard's hazard pointer remains acquired (and keeps protecting its
+    /// current value, if any) until the guard is reconstructed with
+    /// [`from_raw_token`][Guard::from_raw_token] and subsequently released or
+    /// dropped.
+    /// If the token is never converted back, the hazard pointer is leaked and
+    /// any value it protects can never be reclaimed.
+    /// The guard's local handle is dropped, so the caller has to ensure the
+    /// thread local state it refers to is kept alive by other means until the
+    /// guard is reconstructed.
+    #[inline]
+    pub fn into_raw_token(self) -> RawGuardToken {
+        let guard = ManuallyDrop::new(self);
+        let local = guard.local.as_ref() as *const _ as *const ();
+        // the local handle is dropped without dropping the guard itself, which would release its
+        // hazard pointer
+        drop(unsafe { ptr::read(&guard.local) });
+        RawGuardToken { hazard: guard.hazard, local }
+    }
+
+    /// Reconstructs a guard from a `token` previously returned by
+    /// [`into_raw_token`][Guard::into_raw_token] and the `local` handle it
+    /// will use from then on.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure that:
+    ///
+    /// - `token` is converted back into a guard at most once, since every
+    ///   reconstructed guard releases the same hazard pointer
+    /// - `local` refers to the same thread local state (i.e. the same
+    ///   [`Local`][crate::Local]) the original guard was created with, which
+    ///   must have remained alive since the token was created
+    /// - the guard is reconstructed on the thread owning this thread local
+    ///   state
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug builds, if `local` does not refer to the thread local
+    /// state the token was created with.
+    #[inline]
+    pub unsafe fn from_raw_token(
+        token: RawGuardToken,
+        local: LocalHandle<'local, 'global, R>,
+    ) -> Self {
+        debug_assert_eq!(
+            local.as_ref() as *const _ as *const (),
+            token.local,
+            "the raw guard token must be converted back with its original local state"
+        );
+
+        Self { hazard: token.hazard, local }
+    }
+
+    /// Parks all `guards` without releasing their hazard pointers, so their
+    /// protections can be resumed on another thread, e.g. when an async task
+    /// holding the guards migrates between threads.
+    ///
+    /// The guards' hazard pointers keep protecting their current values (if
+    /// any) until the [`ParkedGuards`] are either resumed or dropped, so the
+    /// protected values remain valid even after the thread local state the
+    /// guards were created with has been dropped.
+    #[inline]
+    pub fn park(guards: impl IntoIterator<Item = Self>) -> ParkedGuards<'global> {
+        let mut global = ptr::null();
+        let hazards = guards
+            .into_iter()
+            .map(|guard| {
+                let guard = ManuallyDrop::new(guard);
+                let owner = guard.local.as_ref().global_ptr();
+                assert!(
+                    global.is_null() || global == owner,
+                    "all parked guards must belong to the same `Hp`"
+                );
+                global = owner;
+                // the local handle is dropped without dropping the guard itself, which would
+                // release its hazard pointer
+                drop(unsafe { ptr::read(&guard.local) });
+                guard.hazard
+            })
+            .collect();
+
+        ParkedGuards { hazards, global, _marker: PhantomData }
+    }
+
+    /// Sets the guard's hazard pointer to protect `protected` according to the
+    /// global fence mode.
+    #[inline]
+    pub(crate) fn set_protected(&self, protected: NonNull<()>) {
+        let mode = self.local.as_ref().fence_mode();
+        unsafe { (*self.hazard).set_protected_with(protected, mode) };
+    }
+}
+
+impl<'local, 'global, R: Reclaim> Guard<'local, 'global, R> {
+    /// Protects the value loaded from `src` and returns a [`ProtectedScope`],
+    /// which releases the protection again once it goes out of scope.
+    ///
+    /// Unlike dropping the [`Guard`] itself, this only releases the protected
+    /// value, the guard's hazard pointer remains reserved and can be re-used
+    /// afterwards.
+    #[inline]
+    pub fn scoped_protect<T, N: Unsigned + 'static>(
+        &mut self,
+        src: &Atomic<T, R, N>,
+        order: Ordering,
+    ) -> ProtectedScope<'_, 'local, 'global, T, R, N> {
+        let protected = match self.protect(src, order) {
+            NotNull(shared) => NotNull(Shared::into_marked_non_null(shared)),
+            Null(tag) => Null(tag),
+        };
+
+        ProtectedScope { guard: self, protected }
+    }
+
+    /// Protects the value loaded from `src` and combines it with `self` into
+    /// a [`PinnedRef`], which can be moved around and e.g. returned from
+    /// functions, while keeping the value protected until it is dropped.
+    ///
+    /// Returns `None`, if `src` is null, in which case `self` is dropped.
+    #[inline]
+    pub fn pin<T, N: Unsigned + 'static>(
+        mut self,
+        src: &Atomic<T, R, N>,
+        order: Ordering,
+    ) -> Option<PinnedRef<'local, 'global, T, R, N>> {
+        let ptr = match self.protect(src, order) {
+            NotNull(shared) => Shared::into_marked_non_null(shared),
+            Null(_) => return None,
+        };
+
+        Some(PinnedRef { guard: self, ptr })
+    }
+
+    /// Protects the values loaded from each of the `atomics` with the
+    /// respective guard in `guards` and stores the protected values in `out`,
+    /// e.g. for taking a snapshot of an array of pointers.
+    ///
+    /// Since a guard can only protect a single value at a time, this requires
+    /// a separate guard for each atomic.
+    /// Each slot is protected and validated independently like with
+    /// [`protect`][Protect::protect], null pointers are stored as `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths of `guards`, `atomics` and `out` differ.
+    #[inline]
+    pub fn protect_slice<'g, T, N: Unsigned + 'static>(
+        guards: &'g mut [Self],
+        atomics: &[&Atomic<T, R, N>],
+        out: &mut [Option<Shared<'g, T, R, N>>],
+        order: Ordering,
+    ) {
+        assert_eq!(guards.len(), atomics.len(), "one guard is required for each atomic");
+        assert_eq!(atomics.len(), out.len(), "one output slot is required for each atomic");
+
+        for ((guard, atomic), slot) in guards.iter_mut().zip(atomics).zip(out.iter_mut()) {
+            *slot = match guard.protect(atomic, order) {
+                NotNull(shared) => Some(shared),
+                Null(_) => None,
+            };
+        }
+    }
+
+    /// Releases the currently protected value and protects the value loaded
+    /// from `src` instead, which is semantically equivalent to calling
+    /// [`release`][Protect::release] followed by [`protect`][Protect::protect].
+    ///
+    /// Unlike this sequence, the hazard pointer is directly overwritten with
+    /// the new value without being marked as reserved in between, which saves
+    /// a store in tight CAS-retry loops.
+    #[inline]
+    pub fn reprotect<T, N: Unsigned + 'static>(
+        &mut self,
+        src: &Atomic<T, R, N>,
+        order: Ordering,
+    ) -> MaybeNull<Shared<'_, T, R, N>> {
+        self.local.as_ref().try_increase_ops_count(Operation::Release);
+        self.protect(src, order)
+    }
+
+    /// Protects the value loaded from `src` and checks it with the `valid`
+    /// predicate, reloading and protecting `src` again if the check fails.
+    ///
+    /// This is useful e.g. for optimistic reads of nodes, which may have been
+    /// logically deleted (marked) in the meantime.
+    /// Returns `None`, if `src` is null or no valid value could be protected
+    /// after [`PROTECT_VALID_RETRIES`] attempts, in which case the protection
+    /// is released.
+    #[inline]
+    pub fn protect_valid<T, N: Unsigned + 'static>(
+        &mut self,
+        src: &Atomic<T, R, N>,
+        order: Ordering,
+        valid: impl Fn(&T) -> bool,
+    ) -> Option<Shared<'_, T, R, N>> {
+        for _ in 0..PROTECT_VALID_RETRIES {
+            let ptr = match self.protect(src, order) {
+                NotNull(shared) => Shared::into_marked_non_null(shared),
+                Null(_) => return None,
+            };
+
+            // the value remains protected until the next iteration, so it can be safely checked
+            let shared: Shared<T, R, N> = unsafe { Shared::from_marked_non_null(ptr) };
+            if valid(Shared::into_ref(shared)) {
+                return Some(unsafe { Shared::from_marked_non_null(ptr) });
+            }
+        }
+
+        self.release();
+        None
+    }
+
+    /// Protects the value loaded from the [`Atomic`] selected by `read` within
+    /// the node currently protected by `anchor`, e.g. for hand-over-hand
+    /// traversals, in which the child pointer is stored inside the parent.
+    ///
+    /// The anchor's protection keeps the parent node alive while its child
+    /// pointer is read and protected, which is the dependency that makes the
+    /// read safe in the first place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `anchor` does not currently protect any value.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure that the value protected by `anchor` is of
+    /// type `P`, i.e. that it was protected through an [`Atomic`] of `P`.
+    #[inline]
+    pub unsafe fn protect_via<'a, P: 'a, T, N: Unsigned + 'static>(
+        &mut self,
+        anchor: &'a Guard<'_, 'global, R>,
+        read: impl FnOnce(&'a P) -> &'a Atomic<T, R, N>,
+        order: Ordering,
+    ) -> MaybeNull<Shared<'_, T, R, N>> {
+        let parent = match (*anchor.hazard).protected(Ordering::Relaxed).protected() {
+            Some(protected) => &*(protected.into_inner().as_ptr() as *const P),
+            None => panic!("the anchor guard must protect a value"),
+        };
+
+        self.protect(read(parent), order)
+    }
+
+    /// Attempts to protect the value loaded from `src` exactly once, without
+    /// looping until the loaded value stabilizes like [`protect`][Protect::protect].
+    ///
+    /// This is intended for algorithms with their own retry loop, which can
+    /// then apply their own back-off strategy under heavy contention.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if `src` has changed between loading and protecting its value,
+    /// in which case no value remains protected.
+    #[inline]
+    pub fn try_protect<T, N: Unsigned + 'static>(
+        &mut self,
+        src: &Atomic<T, R, N>,
+        order: Ordering,
+    ) -> Result<MaybeNull<Shared<'_, T, R, N>>, NotEqualError> {
+        let expected = src.load_raw(Ordering::Relaxed);
+        self.protect_raw_if_equal(src, expected, order)
+    }
+
+    /// Protects the `expected` pointer, which the caller has previously loaded
+    /// from `src` (e.g. with `load_raw`), and validates that `src` still
+    /// contains the same value.
+    ///
+    /// Unlike [`protect_if_equal`][Protect::protect_if_equal], this does not
+    /// load `src` again before setting the protection.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if `src` no longer contains `expected`, in which case no value
+    /// remains protected.
+    #[inline]
+    pub fn protect_raw_if_equal<T, N: Unsigned + 'static>(
+        &mut self,
+        src: &Atomic<T, R, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> Result<MaybeNull<Shared<'_, T, R, N>>, NotEqualError> {
+        self.local.as_ref().try_count_protect();
+        match MaybeNull::from(expected) {
+            Null(tag) => {
+                self.release();
+                match src.load_raw(order) == expected {
+                    true => Ok(Null(tag)),
+                    false => Err(NotEqualError),
+                }
+            }
+            NotNull(ptr) => {
+                let protect = ptr.decompose_non_null().cast();
+                self.set_protected(protect);
+
+                if src.load_raw(order) == expected {
+                    Ok(NotNull(unsafe { Shared::from_marked_non_null(ptr) }))
+                } else {
+                    unsafe { (*self.hazard).set_thread_reserved(Ordering::Release) };
+                    Err(NotEqualError)
+                }
+            }
+        }
+    }
 }
 
+/// The maximum number of attempts made by [`Guard::protect_valid`].
+pub const PROTECT_VALID_RETRIES: usize = 16;
+
 /********** impl Drop *****************************************************************************/
 
 impl<'local, 'global, R> Drop for Guard<'local, 'global, R> {
     #[inline]
     fn drop(&mut self) {
+        // with strict releases, relying on dropping a guard for releasing its protected value is
+        // considered a bug, e.g. for guards that must not protect anything during blocking calls
+        #[cfg(feature = "strict-release")]
+        debug_assert!(
+            std::thread::panicking()
+                || unsafe { (*self.hazard).protected(Ordering::Relaxed) }
+                    == ProtectedResult::Unprotected,
+            "guard dropped without releasing its protected value"
+        );
+
         let local = self.local.as_ref();
         local.try_increase_ops_count(Operation::Release);
         let hazard = unsafe { &*self.hazard };
@@ -97,26 +505,40 @@ unsafe impl<R: Reclaim> Protect for Guard<'_, '_, R> {
         src: &Atomic<T, Self::Reclaimer, N>,
         order: Ordering,
     ) -> MaybeNull<Shared<T, Self::Reclaimer, N>> {
+        self.local.as_ref().try_count_protect();
         match MaybeNull::from(src.load_raw(Ordering::Relaxed)) {
             Null(tag) => release!(self, tag),
             NotNull(ptr) => {
                 let mut protect = ptr.decompose_non_null();
-                unsafe { (*self.hazard).set_protected(protect.cast(), Ordering::SeqCst) };
+                self.set_protected(protect.cast());
 
-                loop {
+                // the thread counts as spinning from its first retry until it leaves the loop
+                let mut spinning = false;
+                let res = loop {
                     match MaybeNull::from(src.load_raw(order)) {
-                        Null(tag) => return release!(self, tag),
+                        Null(tag) => break release!(self, tag),
                         NotNull(ptr) => {
                             let temp = ptr.decompose_non_null();
                             if protect == temp {
-                                return NotNull(unsafe { Shared::from_marked_non_null(ptr) });
+                                break NotNull(unsafe { Shared::from_marked_non_null(ptr) });
+                            }
+
+                            if !spinning {
+                                spinning = true;
+                                self.local.as_ref().set_spinning(true);
                             }
 
-                            unsafe { (*self.hazard).set_protected(temp.cast(), Ordering::SeqCst) };
+                            self.set_protected(temp.cast());
                             protect = temp;
                         }
                     }
+                };
+
+                if spinning {
+                    self.local.as_ref().set_spinning(false);
                 }
+
+                res
             }
         }
     }
@@ -128,8 +550,11 @@ unsafe impl<R: Reclaim> Protect for Guard<'_, '_, R> {
         expected: MarkedPtr<T, N>,
         order: Ordering,
     ) -> Result<MaybeNull<Shared<T, Self::Reclaimer, N>>, NotEqualError> {
+        self.local.as_ref().try_count_protect();
         let raw = src.load_raw(order);
         if raw != expected {
+            // on failure, the hazard must not remain protecting a previous value
+            unsafe { (*self.hazard).set_thread_reserved(Ordering::Release) };
             return Err(NotEqualError);
         }
 
@@ -137,7 +562,7 @@ unsafe impl<R: Reclaim> Protect for Guard<'_, '_, R> {
             Null(tag) => Ok(release!(self, tag)),
             NotNull(ptr) => {
                 let protect = ptr.decompose_non_null().cast();
-                unsafe { (*self.hazard).set_protected(protect, Ordering::SeqCst) };
+                self.set_protected(protect);
 
                 if src.load_raw(order) == ptr.into_marked_ptr() {
                     Ok(NotNull(unsafe { Shared::from_marked_non_null(ptr) }))
@@ -149,3 +574,955 @@ unsafe impl<R: Reclaim> Protect for Guard<'_, '_, R> {
         }
     }
 }
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// ProtectedScope
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A value protected by a [`Guard`] for the duration of a (lexical) scope.
+///
+/// When the [`ProtectedScope`] is dropped, the guard's protection is released,
+/// while the [`Guard`] itself remains usable.
+pub struct ProtectedScope<'g, 'local, 'global, T, R: Reclaim, N> {
+    guard: &'g mut Guard<'local, 'global, R>,
+    protected: MaybeNull<MarkedNonNull<T, N>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<T, R: Reclaim, N: Unsigned + 'static> ProtectedScope<'_, '_, '_, T, R, N> {
+    /// Returns the protected value.
+    #[inline]
+    pub fn get(&self) -> MaybeNull<Shared<'_, T, R, N>> {
+        match self.protected {
+            NotNull(ptr) => NotNull(unsafe { Shared::from_marked_non_null(ptr) }),
+            Null(tag) => Null(tag),
+        }
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl<T, R: Reclaim, N> Drop for ProtectedScope<'_, '_, '_, T, R, N> {
+    #[inline]
+    fn drop(&mut self) {
+        self.guard.release();
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// GuardCell
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A wrapper for a [`Guard`] that hands out reference-counted
+/// [`ProtectedHandle`]s, whose lifetimes are not tied to a (mutable) borrow of
+/// the guard.
+///
+/// This allows e.g. storing a protected value in a struct field.
+/// As long as any handle to a protected value exists, the guard can not be
+/// used to protect another value, so the guard's hazard pointer keeps
+/// protecting the value until all of its handles have been dropped.
+pub struct GuardCell<'local, 'global, R> {
+    guard: Rc<Guard<'local, 'global, R>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<'local, 'global, R: Reclaim> GuardCell<'local, 'global, R> {
+    /// Creates a new [`GuardCell`] wrapping `guard`.
+    #[inline]
+    pub fn new(guard: Guard<'local, 'global, R>) -> Self {
+        Self { guard: Rc::new(guard) }
+    }
+
+    /// Returns `true` if there are any live [`ProtectedHandle`]s derived from
+    /// this cell.
+    #[inline]
+    pub fn is_busy(&self) -> bool {
+        Rc::strong_count(&self.guard) > 1
+    }
+
+    /// Protects the value loaded from `src` and returns a
+    /// [`ProtectedHandle`] to it or `None`, if the loaded value is null.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if any handle to a previously protected value is still alive.
+    #[inline]
+    pub fn protect<T, N: Unsigned + 'static>(
+        &mut self,
+        src: &Atomic<T, R, N>,
+        order: Ordering,
+    ) -> Result<Option<ProtectedHandle<'local, 'global, T, R, N>>, GuardBusyError> {
+        let guard = Rc::get_mut(&mut self.guard).ok_or(GuardBusyError)?;
+        let ptr = match guard.protect(src, order) {
+            NotNull(shared) => Shared::into_marked_non_null(shared),
+            Null(_) => return Ok(None),
+        };
+
+        Ok(Some(ProtectedHandle { guard: Rc::clone(&self.guard), ptr }))
+    }
+
+    /// Releases any value currently protected by the cell's guard.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if any handle to a previously protected value is still alive.
+    #[inline]
+    pub fn release(&mut self) -> Result<(), GuardBusyError> {
+        Rc::get_mut(&mut self.guard).ok_or(GuardBusyError)?.release();
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// ProtectedHandle
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A reference-counted handle to a value protected by a [`GuardCell`].
+///
+/// The handle keeps the cell's guard alive and prevents it from protecting any
+/// other value, so the value remains protected for as long as the handle (or
+/// any of its clones) exists.
+pub struct ProtectedHandle<'local, 'global, T, R, N> {
+    guard: Rc<Guard<'local, 'global, R>>,
+    ptr: MarkedNonNull<T, N>,
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl<T, R, N> Clone for ProtectedHandle<'_, '_, T, R, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self { guard: Rc::clone(&self.guard), ptr: self.ptr }
+    }
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<T, R: Reclaim, N: Unsigned + 'static> ProtectedHandle<'_, '_, T, R, N> {
+    /// Returns the protected value.
+    #[inline]
+    pub fn get(&self) -> Shared<'_, T, R, N> {
+        // the value remains protected at least as long as `self` is borrowed
+        unsafe { Shared::from_marked_non_null(self.ptr) }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// PinnedRef
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An owned reference to a value protected by the [`Guard`] it contains.
+///
+/// Unlike a [`Shared`] reference, which borrows its guard, a [`PinnedRef`] can
+/// be freely moved, stored or returned from functions and keeps the value
+/// protected until it is dropped.
+pub struct PinnedRef<'local, 'global, T, R, N> {
+    guard: Guard<'local, 'global, R>,
+    ptr: MarkedNonNull<T, N>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<'local, 'global, T, R: Reclaim, N: Unsigned + 'static> PinnedRef<'local, 'global, T, R, N> {
+    /// Returns the protected value as a [`Shared`] reference.
+    #[inline]
+    pub fn shared(&self) -> Shared<'_, T, R, N> {
+        // the value remains protected at least as long as `self` is borrowed
+        unsafe { Shared::from_marked_non_null(self.ptr) }
+    }
+
+    /// Releases the protected value and returns the contained [`Guard`] for
+    /// re-use.
+    #[inline]
+    pub fn into_guard(self) -> Guard<'local, 'global, R> {
+        let mut guard = self.guard;
+        guard.release();
+        guard
+    }
+}
+
+/********** impl Deref ****************************************************************************/
+
+impl<T, R, N: Unsigned> Deref for PinnedRef<'_, '_, T, R, N> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // the guard is owned by `self` and can not protect any other value, so the value remains
+        // protected (and hence valid) for as long as `self` is alive
+        unsafe { &*self.ptr.decompose_non_null().as_ptr() }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// GuardArray
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A fixed number of [`Guard`]s of the same thread local state, e.g. for
+/// traversing a linked list with a `prev`, `curr` and `next` guard.
+pub struct GuardArray<'local, 'global, R, const N: usize> {
+    guards: [Guard<'local, 'global, R>; N],
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<'local, 'global, R, const N: usize> GuardArray<'local, 'global, R, N> {
+    /// Creates a new [`GuardArray`] of `N` guards, each of which reserves its
+    /// own hazard pointer from `local`.
+    #[inline]
+    pub fn with_handle(local: LocalHandle<'local, 'global, R>) -> Self {
+        Self { guards: array::from_fn(|_| Guard::with_handle(local.clone())) }
+    }
+
+    /// Swaps the guards at the indices `i` and `j`.
+    ///
+    /// Only the hazard pointers are exchanged between the two positions, their
+    /// protected values remain untouched, so this is cheaper than protecting
+    /// the values anew, e.g. when advancing `curr` to `prev` in a traversal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` are out of bounds.
+    #[inline]
+    pub fn swap(&mut self, i: usize, j: usize) {
+        self.guards.swap(i, j);
+    }
+}
+
+impl<R: Reclaim, const N: usize> GuardArray<'_, '_, R, N> {
+    /// Releases the values protected by all guards in a single pass.
+    ///
+    /// Releasing all guards counts as a single (logical) operation, i.e. the
+    /// ops count is increased only once, if releases are counted (see
+    /// [`Operation::Release`][crate::Operation::Release]).
+    #[inline]
+    pub fn release_all(&mut self) {
+        if let Some(guard) = self.guards.first() {
+            guard.local.as_ref().try_increase_ops_count(Operation::Release);
+        }
+
+        for guard in self.guards.iter() {
+            unsafe { (*guard.hazard).set_thread_reserved(Ordering::Release) };
+        }
+    }
+}
+
+/********** impl Index ****************************************************************************/
+
+impl<'local, 'global, R, const N: usize> Index<usize> for GuardArray<'local, 'global, R, N> {
+    type Output = Guard<'local, 'global, R>;
+
+    #[inline]
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.guards[idx]
+    }
+}
+
+/********** impl IndexMut *************************************************************************/
+
+impl<R, const N: usize> IndexMut<usize> for GuardArray<'_, '_, R, N> {
+    #[inline]
+    fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
+        &mut self.guards[idx]
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// RawGuardToken
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The raw essence of a [`Guard`], i.e. a pointer to its hazard pointer and an
+/// opaque pointer to its thread local state (see
+/// [`Guard::into_raw_token`]).
+///
+/// A token consists of two plain pointers, so it can be stored in structs
+/// shared with foreign code, but it must be converted back into a guard with
+/// [`Guard::from_raw_token`] in order to release its hazard pointer.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct RawGuardToken {
+    hazard: *const HazardPtr,
+    local: *const (),
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// ParkedGuards
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The hazard pointers of a set of parked [`Guard`]s (see [`Guard::park`]),
+/// which can be sent to another thread for resuming them there.
+///
+/// All hazard pointers are released when the parked guards are dropped
+/// without being resumed.
+#[derive(Debug)]
+#[must_use = "parked guards release their hazard pointers right away, if they are dropped"]
+pub struct ParkedGuards<'global> {
+    hazards: Vec<*const HazardPtr>,
+    /// The global state owning the hazards (`null`, if there are none).
+    global: *const Global,
+    _marker: PhantomData<&'global HazardPtr>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<'global> ParkedGuards<'global> {
+    /// Returns the number of parked guards.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.hazards.len()
+    }
+
+    /// Returns `true` if there are no parked guards.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.hazards.is_empty()
+    }
+
+    /// Returns the pointers currently protected by the parked guards.
+    #[inline]
+    pub fn protected(&self) -> Vec<ProtectedPtr> {
+        self.hazards
+            .iter()
+            .filter_map(|&hazard| unsafe { (*hazard).protected(Ordering::Acquire).protected() })
+            .collect()
+    }
+
+    /// Resumes the parked guards with the thread local state of `local` in
+    /// the same order in which they were parked.
+    ///
+    /// The resumed guards take over the parked hazard pointers, so their
+    /// protections never lapse.
+    ///
+    /// # Errors
+    ///
+    /// Fails and returns the parked guards, if `local` does not belong to the
+    /// same [`Hp`][crate::Hp] as the guards, since its scans would not be
+    /// aware of their hazard pointers.
+    #[inline]
+    pub fn resume<'local, R>(
+        self,
+        local: &LocalHandle<'local, 'global, R>,
+    ) -> Result<Vec<Guard<'local, 'global, R>>, Self> {
+        if !self.global.is_null() && self.global != local.as_ref().global_ptr() {
+            return Err(self);
+        }
+
+        let parked = ManuallyDrop::new(self);
+        let hazards = unsafe { ptr::read(&parked.hazards) };
+        let guards = hazards
+            .into_iter()
+            .map(|hazard| {
+                // the hazards are now owned by the resuming thread
+                #[cfg(feature = "track-owner")]
+                crate::owner::record(unsafe { &*hazard });
+
+                Guard { hazard, local: local.clone() }
+            })
+            .collect();
+
+        Ok(guards)
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for ParkedGuards<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        for &hazard in &self.hazards {
+            unsafe { (*hazard).set_free(Ordering::Release) };
+        }
+    }
+}
+
+/********** impl Send *****************************************************************************/
+
+// SAFETY: hazard pointers are allocated by the global state and may be accessed by any thread, the
+// global state itself is `Sync` and only compared by address, and the parked guards no longer
+// reference any (thread-bound) thread local state
+unsafe impl Send for ParkedGuards<'_> {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// GuardBusyError
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Error type for [`GuardCell`] operations that fail due to existing
+/// [`ProtectedHandle`]s.
+#[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct GuardBusyError;
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::rc::Rc;
+
+    use conquer_reclaim::conquer_pointer::{
+        MarkedPtr,
+        MaybeNull::{NotNull, Null},
+    };
+    use conquer_reclaim::typenum::U0;
+    use conquer_reclaim::{Protect, Shared, Unlinked};
+
+    use crate::hazard::ProtectedResult;
+    use crate::local::HAZARD_CACHE;
+    use crate::test_util::{retired, DropCount};
+    use crate::{
+        Config, ConfigBuilder, CountStrategy, Hp, Local, LocalHandle, LocalRetire, RetireVia,
+    };
+
+    use super::{
+        Guard, GuardArray, GuardBusyError, GuardCell, ParkedGuards, PinnedRef, ProtectedHandle,
+        RawGuardToken,
+    };
+
+    type Atomic<T> = conquer_reclaim::Atomic<T, Hp<LocalRetire>, U0>;
+    type Owned<T> = conquer_reclaim::Owned<T, Hp<LocalRetire>, U0>;
+
+    #[test]
+    fn scoped_protect() {
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+
+        let atomic = Atomic::new(1);
+        {
+            let scope = guard.scoped_protect(&atomic, Ordering::Acquire);
+            match scope.get() {
+                NotNull(shared) => assert_eq!(Shared::into_ref(shared), &1),
+                _ => panic!("protected value must not be null"),
+            }
+
+            let hazard = unsafe { &*scope.guard.hazard };
+            assert!(hazard.protected(Ordering::Relaxed).protected().is_some());
+        }
+
+        // the guard outlives the scope but no longer protects any value
+        let hazard = unsafe { &*guard.hazard };
+        assert_eq!(hazard.protected(Ordering::Relaxed), ProtectedResult::Unprotected);
+    }
+
+    #[test]
+    fn guard_array() {
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let mut guards = GuardArray::<_, 3>::with_handle(LocalHandle::from_ref(&local));
+        let hazards: Vec<_> = (0..3).map(|idx| guards[idx].hazard).collect();
+
+        let atomic = Atomic::new(1);
+        let _ = guards[0].protect(&atomic, Ordering::Acquire);
+        guards.swap(0, 2);
+
+        // the hazard pointers changed places along with their protected values
+        assert_eq!(guards[0].hazard, hazards[2]);
+        assert_eq!(guards[2].hazard, hazards[0]);
+        assert!(guards[0].is_reserved());
+        assert!(guards[2].is_active());
+
+        guards.release_all();
+        assert!((0..3).all(|idx| guards[idx].is_reserved()));
+    }
+
+    #[test]
+    fn guard_array_release_all() {
+        let mut config = Config::default();
+        config.count_strategy = CountStrategy::Release;
+
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(Some(config));
+        let mut guards = GuardArray::<_, 3>::with_handle(LocalHandle::from_ref(&local));
+
+        let atomics = [Atomic::new(1), Atomic::new(2), Atomic::new(3)];
+        for (idx, atomic) in atomics.iter().enumerate() {
+            let _ = guards[idx].protect(atomic, Ordering::Acquire);
+        }
+
+        // releasing the entire array is counted as a single operation
+        let ops_count = local.ops_count();
+        guards.release_all();
+        assert_eq!(local.ops_count(), ops_count + 1);
+        assert!((0..3).all(|idx| guards[idx].is_reserved()));
+    }
+
+    #[test]
+    fn protected() {
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+        assert_eq!(guard.protected(), None);
+
+        let atomic = Atomic::new(1);
+        let shared = match guard.protect(&atomic, Ordering::Acquire) {
+            NotNull(shared) => Shared::into_ref(shared) as *const i32 as usize,
+            _ => panic!("protected value must not be null"),
+        };
+        assert_eq!(guard.protected().map(|protected| protected.address()), Some(shared));
+
+        guard.downgrade();
+        assert_eq!(guard.protected(), None);
+        guard.release();
+        assert_eq!(guard.protected(), None);
+    }
+
+    #[test]
+    fn try_protect() {
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+
+        let atomic = Atomic::new(1);
+        match guard.try_protect(&atomic, Ordering::Acquire) {
+            Ok(NotNull(shared)) => assert_eq!(Shared::into_ref(shared), &1),
+            _ => panic!("uncontended protection must succeed"),
+        }
+        assert!(guard.is_active());
+
+        let null: Atomic<i32> = Atomic::null();
+        assert!(matches!(guard.try_protect(&null, Ordering::Acquire), Ok(Null(_))));
+        assert!(guard.is_reserved());
+    }
+
+    #[test]
+    fn raw_token() {
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+
+        let atomic = Atomic::new(1);
+        let addr = match guard.protect(&atomic, Ordering::Acquire) {
+            NotNull(shared) => Shared::into_ref(shared) as *const i32 as usize,
+            _ => panic!("protected value must not be null"),
+        };
+
+        // the value remains protected while only the token exists
+        let token: RawGuardToken = guard.into_raw_token();
+        let snapshot: Vec<_> = hp.protected_snapshot().iter().map(|p| p.address()).collect();
+        assert_eq!(snapshot, vec![addr]);
+
+        let mut guard = unsafe { Guard::from_raw_token(token, LocalHandle::from_ref(&local)) };
+        assert_eq!(guard.protected().map(|protected| protected.address()), Some(addr));
+        guard.release();
+        assert!(hp.protected_snapshot().is_empty());
+    }
+
+    #[test]
+    fn reserved_and_active() {
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+        assert!(guard.is_reserved());
+        assert!(!guard.is_active());
+
+        let atomic = Atomic::new(1);
+        let _ = guard.protect(&atomic, Ordering::Acquire);
+        assert!(!guard.is_reserved());
+        assert!(guard.is_active());
+
+        // releasing the protection keeps the hazard reserved for the guard
+        guard.release();
+        assert!(guard.is_reserved());
+        assert!(!guard.is_active());
+
+        // a null value is not actively protected
+        let null = Atomic::null();
+        let _ = guard.protect(&null, Ordering::Acquire);
+        assert!(guard.is_reserved());
+    }
+
+    #[test]
+    fn protect_if_equal_mismatch() {
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+
+        let atomic = Atomic::new(1);
+        let _ = guard.protect(&atomic, Ordering::Acquire);
+        let hazard = unsafe { &*guard.hazard };
+        assert!(hazard.protected(Ordering::Relaxed).protected().is_some());
+
+        // a mismatch must not leave the previously protected value protected
+        let res = guard.protect_if_equal(&atomic, MarkedPtr::null(), Ordering::Acquire);
+        assert!(res.is_err());
+        let hazard = unsafe { &*guard.hazard };
+        assert_eq!(hazard.protected(Ordering::Relaxed), ProtectedResult::Unprotected);
+    }
+
+    // the deterministic block reservation changes which hazards are acquired
+    #[cfg(not(feature = "deterministic-hazards"))]
+    #[test]
+    fn recycle_overflow_frees_hazard() {
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+
+        // one guard more than fits into the thread local hazard cache
+        let guards: Vec<_> =
+            (0..=HAZARD_CACHE).map(|_| Guard::with_handle(LocalHandle::from_ref(&local))).collect();
+        let overflow = guards[HAZARD_CACHE].hazard;
+        drop(guards);
+        assert_eq!(local.cached_hazard_count(), HAZARD_CACHE);
+
+        // the overflowing hazard is free and is hence acquired by another thread
+        let other = hp.build_local(None);
+        let guard = Guard::with_handle(LocalHandle::from_ref(&other));
+        assert_eq!(guard.hazard, overflow);
+    }
+
+    #[test]
+    fn protect_raw_if_equal() {
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+
+        let atomic = Atomic::new(1);
+        let raw = atomic.load_raw(Ordering::Relaxed);
+        match guard.protect_raw_if_equal(&atomic, raw, Ordering::Acquire) {
+            Ok(NotNull(shared)) => assert_eq!(Shared::into_ref(shared), &1),
+            _ => panic!("unchanged value must be protected"),
+        }
+
+        let hazard = unsafe { &*guard.hazard };
+        assert!(hazard.protected(Ordering::Relaxed).protected().is_some());
+
+        // the value changes after the raw load
+        let _unlinked = atomic.swap(Owned::new(2), Ordering::Relaxed);
+        assert!(guard.protect_raw_if_equal(&atomic, raw, Ordering::Acquire).is_err());
+        let hazard = unsafe { &*guard.hazard };
+        assert_eq!(hazard.protected(Ordering::Relaxed), ProtectedResult::Unprotected);
+    }
+
+    #[test]
+    fn reprotect() {
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+
+        let atomic = Atomic::new(1);
+        let _ = guard.protect(&atomic, Ordering::Acquire);
+        let _unlinked = atomic.swap(Owned::new(2), Ordering::Relaxed);
+
+        let expected = match guard.reprotect(&atomic, Ordering::Acquire) {
+            NotNull(shared) => {
+                assert_eq!(Shared::into_ref(shared), &2);
+                Shared::into_marked_non_null(shared).decompose_non_null().cast::<()>()
+            }
+            _ => panic!("protected value must not be null"),
+        };
+
+        let hazard = unsafe { &*guard.hazard };
+        let protected = hazard.protected(Ordering::Relaxed).protected().unwrap();
+        assert_eq!(protected.into_inner(), expected);
+    }
+
+    #[test]
+    fn count_clones() {
+        for &count_clones in &[false, true] {
+            let hp = Hp::<LocalRetire>::default();
+            let config = ConfigBuilder::new().count_clones(count_clones).build();
+            let local = hp.build_local(Some(config));
+            let guard = Guard::with_handle(LocalHandle::from_ref(&local));
+
+            let clones: Vec<_> = (0..3).map(|_| guard.clone()).collect();
+            assert_eq!(local.ops_count(), if count_clones { 3 } else { 0 });
+            drop(clones);
+        }
+    }
+
+    #[test]
+    fn protect_slice() {
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let mut guards: Vec<_> =
+            (0..3).map(|_| Guard::with_handle(LocalHandle::from_ref(&local))).collect();
+
+        let atomics = [Atomic::new(1), Atomic::null(), Atomic::new(3)];
+        let refs: Vec<_> = atomics.iter().collect();
+        let mut out = [None, None, None];
+        Guard::protect_slice(&mut guards, &refs, &mut out, Ordering::Acquire);
+
+        let values: Vec<_> = out.iter().map(|slot| slot.map(Shared::into_ref)).collect();
+        assert_eq!(values, [Some(&1), None, Some(&3)]);
+
+        // each non-null slot is protected by its own guard
+        let protected: Vec<_> = guards
+            .iter()
+            .map(|guard| unsafe { &*guard.hazard }.protected(Ordering::Relaxed).protected())
+            .collect();
+        assert!(protected[0].is_some() && protected[2].is_some());
+        assert_ne!(protected[0], protected[2]);
+        assert_eq!(protected[1], None);
+    }
+
+    #[test]
+    fn total_ops_budget() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<LocalRetire>::default();
+        let config = ConfigBuilder::new().total_ops_budget(8).build();
+        let local = hp.build_local(Some(config));
+        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+
+        local.retire(retired(&COUNT));
+
+        // the workload consists mostly of protects, which are not counted by the count strategy
+        let other = Atomic::new(DropCount(&COUNT));
+        for _ in 0..6 {
+            let _ = guard.protect(&other, Ordering::Acquire);
+        }
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+
+        // the eighth operation exhausts the budget and forces a scan
+        let _ = guard.protect(&other, Ordering::Acquire);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn pinned_ref() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        fn load<'local, 'global>(
+            local: &'local Local<'global>,
+            atomic: &Atomic<(u32, DropCount)>,
+        ) -> PinnedRef<'local, 'global, (u32, DropCount), Hp<LocalRetire>, U0> {
+            let guard = Guard::with_handle(LocalHandle::from_ref(local));
+            guard.pin(atomic, Ordering::Acquire).unwrap()
+        }
+
+        let hp = Hp::<LocalRetire>::default();
+        // every operation triggers a scan
+        let local = hp.build_local(Some(ConfigBuilder::new().total_ops_budget(1).build()));
+        let atomic = Atomic::new((7, DropCount(&COUNT)));
+        let pinned = load(&local, &atomic);
+
+        let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+        local.retire(Unlinked::into_retired(unlinked).into_raw());
+
+        // the retired value remains protected and valid after the function returned
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+        assert_eq!(pinned.0, 7);
+
+        drop(pinned);
+        drop(local);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn protect_valid() {
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+
+        // the node only becomes valid on the third load
+        let atomic = Atomic::new(1);
+        let loads = Cell::new(0);
+        let valid = |_: &i32| {
+            loads.set(loads.get() + 1);
+            loads.get() == 3
+        };
+
+        let shared = guard.protect_valid(&atomic, Ordering::Acquire, valid).unwrap();
+        assert_eq!(Shared::into_ref(shared), &1);
+        assert_eq!(loads.get(), 3);
+
+        // a node that never becomes valid is eventually given up on
+        assert!(guard.protect_valid(&atomic, Ordering::Acquire, |_| false).is_none());
+        let hazard = unsafe { &*guard.hazard };
+        assert_eq!(hazard.protected(Ordering::Relaxed), ProtectedResult::Unprotected);
+    }
+
+    #[cfg(all(feature = "strict-release", debug_assertions))]
+    #[test]
+    #[should_panic(expected = "guard dropped without releasing its protected value")]
+    fn strict_release() {
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let atomic = Atomic::new(1);
+
+        // dropping an explicitly released guard is fine
+        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+        let _ = guard.protect(&atomic, Ordering::Acquire);
+        guard.release();
+        drop(guard);
+
+        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+        let _ = guard.protect(&atomic, Ordering::Acquire);
+        drop(guard);
+    }
+
+    #[test]
+    fn downgrade_and_upgrade() {
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+
+        let atomic = Atomic::new(1u64);
+        let _ = guard.protect(&atomic, Ordering::Acquire);
+        guard.downgrade();
+        assert!(!guard.is_active());
+        assert!(!guard.is_invalidated());
+
+        // the weak protection is honored as long as it is not invalidated
+        assert_eq!(unsafe { guard.upgrade::<u64>() }, Some(&1));
+        assert!(guard.is_active());
+        assert_eq!(unsafe { guard.upgrade::<u64>() }, None);
+    }
+
+    #[test]
+    fn protect_via() {
+        struct Parent {
+            child: Atomic<i32>,
+        }
+
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let mut anchor = Guard::with_handle(LocalHandle::from_ref(&local));
+        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+
+        let root = Atomic::new(Parent { child: Atomic::new(2) });
+        let _ = anchor.protect(&root, Ordering::Acquire);
+
+        // the child is read through the parent, which remains protected by the anchor
+        let child = unsafe {
+            guard.protect_via(&anchor, |parent: &Parent| &parent.child, Ordering::Acquire)
+        };
+
+        match child {
+            NotNull(shared) => assert_eq!(Shared::into_ref(shared), &2),
+            _ => panic!("protected child must not be null"),
+        }
+
+        assert!(anchor.is_active());
+        assert!(guard.is_active());
+    }
+
+    #[test]
+    fn guard_cell_stored_handle() {
+        struct Holder<'local, 'global> {
+            handle: ProtectedHandle<'local, 'global, i32, Hp<LocalRetire>, U0>,
+        }
+
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let mut cell = GuardCell::new(Guard::with_handle(LocalHandle::from_ref(&local)));
+
+        let atomic = Atomic::new(1);
+        let holder = Holder { handle: cell.protect(&atomic, Ordering::Acquire).unwrap().unwrap() };
+
+        // the guard can not be re-used while the handle is stored
+        assert!(cell.is_busy());
+        assert_eq!(cell.protect(&atomic, Ordering::Acquire).err(), Some(GuardBusyError));
+        assert_eq!(cell.release(), Err(GuardBusyError));
+
+        let hazard = unsafe { &*cell.guard.hazard };
+        assert!(hazard.protected(Ordering::Relaxed).protected().is_some());
+        assert_eq!(Shared::into_ref(holder.handle.get()), &1);
+
+        drop(holder);
+        assert!(!cell.is_busy());
+        assert_eq!(cell.release(), Ok(()));
+        let hazard = unsafe { &*cell.guard.hazard };
+        assert_eq!(hazard.protected(Ordering::Relaxed), ProtectedResult::Unprotected);
+    }
+
+    #[test]
+    fn guards_from_rc_clones() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<LocalRetire>::default();
+        let handle = LocalHandle::<Hp<LocalRetire>>::from_owned(Rc::new(hp.build_local(None)));
+        let clone = handle.clone();
+
+        // each guard is created from a different clone of the same shared local
+        let (first, second) = (Atomic::new(DropCount(&COUNT)), Atomic::new(DropCount(&COUNT)));
+        let mut first_guard = Guard::with_handle(handle.clone());
+        let mut second_guard = Guard::with_handle(clone.clone());
+        let _ = first_guard.protect(&first, Ordering::SeqCst);
+        let _ = second_guard.protect(&second, Ordering::SeqCst);
+        assert_ne!(first_guard.hazard, second_guard.hazard);
+
+        for (atomic, handle) in [(&first, &handle), (&second, &clone)].iter() {
+            let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+            unsafe { unlinked.retire_via(handle) };
+        }
+
+        // the scan observes both protections, regardless of the clone it is run from
+        assert_eq!(clone.flush(), 0);
+        drop(first_guard);
+        assert_eq!(handle.flush(), 1);
+        drop(second_guard);
+        assert_eq!(clone.flush(), 1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn park_and_resume() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp: &'static Hp<LocalRetire> = Box::leak(Box::new(Hp::default()));
+        let atomics: &'static [Atomic<DropCount>] =
+            Box::leak((0..2).map(|_| Atomic::new(DropCount(&COUNT))).collect::<Box<[_]>>());
+
+        // the guards are parked on one thread, whose local state is dropped afterwards
+        let parked: ParkedGuards<'static> = std::thread::spawn(move || {
+            let local = hp.build_local(None);
+            let guards: Vec<_> = atomics
+                .iter()
+                .map(|atomic| {
+                    let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+                    let _ = guard.protect(atomic, Ordering::Acquire);
+                    guard
+                })
+                .collect();
+
+            Guard::park(guards)
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(parked.len(), 2);
+        assert_eq!(parked.protected().len(), 2);
+
+        let local = hp.build_local(None);
+        let handle = LocalHandle::from_ref(&local);
+        for atomic in atomics {
+            let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+            unsafe { unlinked.retire_via(&handle) };
+        }
+
+        // the parked protections are retained and taken over by the resumed guards
+        assert_eq!(handle.flush(), 0);
+        let mut guards = parked.resume(&handle).unwrap();
+        assert_eq!(guards.len(), 2);
+        assert!(guards.iter().all(Guard::is_active));
+        assert_eq!(handle.flush(), 0);
+
+        guards.iter_mut().for_each(|guard| guard.release());
+        assert_eq!(handle.flush(), 2);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn resume_with_foreign_local() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp: &'static Hp<LocalRetire> = Box::leak(Box::new(Hp::default()));
+        let other: &'static Hp<LocalRetire> = Box::leak(Box::new(Hp::default()));
+        let atomic = Atomic::new(DropCount(&COUNT));
+
+        let local = hp.build_local(None);
+        let handle = LocalHandle::from_ref(&local);
+        let mut guard = Guard::with_handle(handle.clone());
+        let _ = guard.protect(&atomic, Ordering::Acquire);
+        let parked = Guard::park(Some(guard));
+
+        // the hazards of one `Hp` must never be resumed with a local of another one
+        let foreign = other.build_local(None);
+        let parked = parked.resume(&LocalHandle::from_ref(&foreign)).unwrap_err();
+        assert_eq!(parked.len(), 1);
+
+        let mut guards = parked.resume(&handle).unwrap();
+        assert!(guards[0].is_active());
+        guards[0].release();
+    }
+}
@@ -1,9 +1,9 @@
 //! An iterable lock-free data structure for storing hazard pointers.
 
 use core::iter::FusedIterator;
-use core::mem::{self, MaybeUninit};
+use core::mem;
 use core::ptr::{self, NonNull};
-use core::sync::atomic::{AtomicPtr, Ordering};
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
 
 #[cfg(not(feature = "std"))]
 use alloc::boxed::Box;
@@ -12,9 +12,10 @@ use conquer_util::align::Aligned128 as CacheAligned;
 
 use crate::hazard::{HazardPtr, FREE, NOT_YET_USED, THREAD_RESERVED};
 
-/// The number of elements is chosen so that 31 hazards aligned to 128-byte and
-/// one likewise aligned next pointer fit into a 4096 byte memory page.
-const ELEMENTS: usize = 31;
+/// The default number of elements per node, which is chosen so that 31 hazards
+/// aligned to 128-byte and one likewise aligned next pointer fit into a 4096
+/// byte memory page.
+pub(crate) const ELEMENTS: usize = 31;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // HazardList
@@ -27,19 +28,44 @@ const ELEMENTS: usize = 31;
 /// If none can be found a new node is appended to the list's tail.
 /// In order to avoid having to deal with memory reclamation the list never
 /// shrinks and hence maintains its maximum extent at all times.
+///
+/// Each node contains `E` hazard pointers, which defaults to the page-fitting
+/// [`ELEMENTS`].
 #[derive(Debug, Default)]
-pub(crate) struct HazardList {
+pub(crate) struct HazardList<const E: usize = ELEMENTS> {
     /// Atomic pointer to the head of the linked list.
-    head: AtomicPtr<HazardArrayNode>,
+    head: AtomicPtr<HazardArrayNode<E>>,
+    /// The number of nodes that have been inserted into the list.
+    node_count: AtomicUsize,
 }
 
+/// A [`HazardList`] with the default (page-fitting) number of elements per node.
+pub(crate) type DefaultHazardList = HazardList<ELEMENTS>;
+
 /********** impl inherent *************************************************************************/
 
-impl HazardList {
+impl<const E: usize> HazardList<E> {
     /// Creates a new empty [`HazardList`].
     #[inline]
     pub const fn new() -> Self {
-        Self { head: AtomicPtr::new(ptr::null_mut()) }
+        Self { head: AtomicPtr::new(ptr::null_mut()), node_count: AtomicUsize::new(0) }
+    }
+
+    /// Returns the total number of hazard pointers in all currently allocated
+    /// nodes.
+    ///
+    /// Since nodes are never removed from the list, the returned value is a
+    /// lower bound for the number of hazard pointers that will be visited by
+    /// any subsequent iteration.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.node_count.load(Ordering::Relaxed) * E
+    }
+
+    /// Returns the number of bytes allocated for all nodes of the list.
+    #[inline]
+    pub fn allocated_bytes(&self) -> usize {
+        self.node_count.load(Ordering::Relaxed) * mem::size_of::<HazardArrayNode<E>>()
     }
 
     /// Acquires a thread-reserved hazard pointer.
@@ -47,7 +73,10 @@ impl HazardList {
     #[inline(never)]
     #[must_use = "discarding a reserved hazard pointer without freeing it renders it unusable"]
     pub fn get_or_insert_reserved_hazard(&self) -> &HazardPtr {
-        unsafe { self.get_or_insert_unchecked(THREAD_RESERVED, Ordering::Relaxed) }
+        let hazard = unsafe { self.get_or_insert_unchecked(THREAD_RESERVED, Ordering::Relaxed) };
+        #[cfg(feature = "track-owner")]
+        crate::owner::record(hazard);
+        hazard
     }
 
     /// Acquires a hazard pointer and sets it to point at `protected`.
@@ -55,18 +84,144 @@ impl HazardList {
     #[inline(never)]
     #[must_use = "discarding a reserved hazard pointer without freeing it renders it unusable"]
     pub fn get_or_insert_hazard(&self, protect: NonNull<()>) -> &HazardPtr {
-        unsafe { self.get_or_insert_unchecked(protect.as_ptr() as _, Ordering::SeqCst) }
+        let hazard =
+            unsafe { self.get_or_insert_unchecked(protect.as_ptr() as _, Ordering::SeqCst) };
+        #[cfg(feature = "track-owner")]
+        crate::owner::record(hazard);
+        hazard
+    }
+
+    /// Attempts to acquire the hazard pointer at the (global) index `idx` as
+    /// thread-reserved, allocating all nodes up to this index, if necessary.
+    ///
+    /// Returns `None`, if the hazard pointer is already in use.
+    #[cfg(feature = "deterministic-hazards")]
+    pub fn try_reserve_at(&self, idx: usize) -> Option<&HazardPtr> {
+        let mut remaining = idx;
+        let mut curr = &self.head;
+
+        loop {
+            let mut node = curr.load(Ordering::Acquire);
+            if node.is_null() {
+                let new = Box::into_raw(Box::new(HazardArrayNode::new(NOT_YET_USED)));
+                node = match curr.compare_exchange(
+                    ptr::null_mut(),
+                    new,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                ) {
+                    Ok(_) => {
+                        self.node_count.fetch_add(1, Ordering::Relaxed);
+                        new
+                    }
+                    Err(existing) => unsafe {
+                        Box::from_raw(new);
+                        existing
+                    },
+                };
+            }
+
+            let elements = unsafe { &(*node).elements };
+            if remaining < E {
+                let hazard = &elements[remaining].aligned;
+                let current = hazard.protected.load(Ordering::Relaxed);
+                let success = (current == FREE || current == NOT_YET_USED)
+                    && hazard
+                        .protected
+                        .compare_exchange(
+                            current,
+                            THREAD_RESERVED,
+                            Ordering::SeqCst,
+                            Ordering::Relaxed,
+                        )
+                        .is_ok();
+
+                return if success { Some(hazard) } else { None };
+            }
+
+            remaining -= E;
+            curr = unsafe { &(*node).next.aligned };
+        }
+    }
+
+    /// Attempts to acquire a thread-reserved hazard pointer located before
+    /// `hazard` in the list, e.g. for moving a reservation towards the head.
+    ///
+    /// Returns `None`, if all hazard pointers before `hazard` are in use.
+    #[inline]
+    pub fn try_reserve_before(&self, hazard: &HazardPtr) -> Option<&HazardPtr> {
+        let reserved = self.iter().take_while(|&curr| !ptr::eq(curr, hazard)).find(|curr| {
+            let current = curr.protected.load(Ordering::Relaxed);
+            (current == FREE || current == NOT_YET_USED)
+                && curr
+                    .protected
+                    .compare_exchange(
+                        current,
+                        THREAD_RESERVED,
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    )
+                    .is_ok()
+        });
+
+        #[cfg(feature = "track-owner")]
+        {
+            if let Some(hazard) = reserved {
+                crate::owner::record(hazard);
+            }
+        }
+
+        reserved
+    }
+
+    /// Pre-allocates enough nodes for the list to hold at least `count` hazard
+    /// pointers in total, so that acquiring up to this many hazard pointers
+    /// never allocates.
+    ///
+    /// All hazard pointers in the appended nodes are unused, so this can be
+    /// safely called concurrently to the acquisition of hazard pointers.
+    #[cold]
+    #[inline(never)]
+    pub fn reserve(&self, count: usize) {
+        let mut remaining = count;
+        let mut tail = &self.head;
+
+        while remaining > 0 {
+            let mut node = tail.load(Ordering::Acquire);
+            if node.is_null() {
+                let new = Box::into_raw(Box::new(HazardArrayNode::new(NOT_YET_USED)));
+                node = match tail.compare_exchange(
+                    ptr::null_mut(),
+                    new,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                ) {
+                    Ok(_) => {
+                        self.node_count.fetch_add(1, Ordering::Relaxed);
+                        new
+                    }
+                    // another thread has appended a node in the meantime, which is counted instead
+                    Err(existing) => unsafe {
+                        Box::from_raw(new);
+                        existing
+                    },
+                };
+            }
+
+            remaining = remaining.saturating_sub(E);
+            tail = unsafe { &(*node).next.aligned };
+        }
     }
 
     /// Returns an iterator over all currently allocated [`HazardPointers`].
     #[inline]
-    pub fn iter(&self) -> Iter {
+    pub fn iter(&self) -> Iter<E> {
         Iter { idx: 0, curr: unsafe { self.head.load(Ordering::Acquire).as_ref() } }
     }
 
     #[inline]
     unsafe fn get_or_insert_unchecked(&self, protect: *const (), order: Ordering) -> &HazardPtr {
-        let mut prev = &self.head as *const AtomicPtr<HazardArrayNode>;
+        let mut prev = &self.head as *const AtomicPtr<HazardArrayNode<E>>;
         let mut curr = (*prev).load(Ordering::Acquire);
 
         // iterate the linked list of hazard nodes
@@ -88,7 +243,7 @@ impl HazardList {
     #[inline]
     unsafe fn insert_back(
         &self,
-        mut tail: *const AtomicPtr<HazardArrayNode>,
+        mut tail: *const AtomicPtr<HazardArrayNode<E>>,
         protected: *const (),
         order: Ordering,
     ) -> &HazardPtr {
@@ -107,13 +262,14 @@ impl HazardList {
             tail = &(*tail_node).next.aligned;
         }
 
+        self.node_count.fetch_add(1, Ordering::Relaxed);
         &(*node).elements[0].aligned
     }
 
     #[inline]
     unsafe fn try_insert_in_node(
         &self,
-        node: *const HazardArrayNode,
+        node: *const HazardArrayNode<E>,
         protected: *const (),
         order: Ordering,
     ) -> Option<&HazardPtr> {
@@ -139,7 +295,7 @@ impl HazardList {
 
 /********** impl Drop *****************************************************************************/
 
-impl Drop for HazardList {
+impl<const E: usize> Drop for HazardList<E> {
     #[inline(never)]
     fn drop(&mut self) {
         let mut curr = self.head.load(Ordering::Relaxed);
@@ -154,21 +310,21 @@ impl Drop for HazardList {
 // Iter
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-pub(crate) struct Iter<'a> {
+pub(crate) struct Iter<'a, const E: usize = ELEMENTS> {
     idx: usize,
-    curr: Option<&'a HazardArrayNode>,
+    curr: Option<&'a HazardArrayNode<E>>,
 }
 
 /********** impl Iterator *************************************************************************/
 
-impl<'a> Iterator for Iter<'a> {
+impl<'a, const E: usize> Iterator for Iter<'a, E> {
     type Item = &'a HazardPtr;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         // this loop is executed at most twice
         while let Some(node) = self.curr {
-            if self.idx < ELEMENTS {
+            if self.idx < E {
                 let idx = self.idx;
                 self.idx += 1;
                 return Some(&node.elements[idx].aligned);
@@ -184,32 +340,28 @@ impl<'a> Iterator for Iter<'a> {
 
 /********** impl FusedIterator ********************************************************************/
 
-impl FusedIterator for Iter<'_> {}
+impl<const E: usize> FusedIterator for Iter<'_, E> {}
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // HazardArrayNode
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-struct HazardArrayNode {
-    elements: [CacheAligned<HazardPtr>; ELEMENTS],
-    next: CacheAligned<AtomicPtr<HazardArrayNode>>,
+struct HazardArrayNode<const E: usize> {
+    elements: [CacheAligned<HazardPtr>; E],
+    next: CacheAligned<AtomicPtr<HazardArrayNode<E>>>,
 }
 
 /********** impl inherent *************************************************************************/
 
-impl HazardArrayNode {
+impl<const E: usize> HazardArrayNode<E> {
     #[inline]
     fn new(protected: *const ()) -> Self {
-        let mut elements: [MaybeUninit<CacheAligned<HazardPtr>>; ELEMENTS] =
-            unsafe { MaybeUninit::uninit().assume_init() };
-
-        elements[0] = MaybeUninit::new(CacheAligned::new(HazardPtr::with_protected(protected)));
-        for elem in &mut elements[1..] {
-            *elem = MaybeUninit::new(CacheAligned::new(HazardPtr::new()));
-        }
-
+        assert!(E > 0, "hazard array nodes must contain at least one element");
         Self {
-            elements: unsafe { mem::transmute(elements) },
+            elements: core::array::from_fn(|idx| match idx {
+                0 => CacheAligned::new(HazardPtr::with_protected(protected)),
+                _ => CacheAligned::new(HazardPtr::new()),
+            }),
             next: CacheAligned::new(AtomicPtr::default()),
         }
     }
@@ -217,10 +369,11 @@ impl HazardArrayNode {
 
 #[cfg(test)]
 mod tests {
+    use core::mem;
     use core::ptr::NonNull;
     use core::sync::atomic::Ordering;
 
-    use super::{HazardList, ELEMENTS};
+    use super::{DefaultHazardList as HazardList, HazardArrayNode, ELEMENTS};
     use crate::hazard::ProtectedResult::Unprotected;
 
     #[test]
@@ -286,6 +439,55 @@ mod tests {
         assert_eq!(hazards.len(), ELEMENTS + 1);
     }
 
+    #[test]
+    fn reserve() {
+        let list = HazardList::new();
+        list.reserve(2 * ELEMENTS + 1);
+        assert_eq!(list.iter().count(), 3 * ELEMENTS);
+        assert!(list.iter().all(|hazard| hazard.protected(Ordering::Relaxed) == Unprotected));
+
+        // already allocated nodes are counted
+        list.reserve(ELEMENTS);
+        assert_eq!(list.iter().count(), 3 * ELEMENTS);
+
+        // acquiring the reserved hazard pointers does not allocate any further nodes
+        for _ in 0..3 * ELEMENTS {
+            let _ = list.get_or_insert_reserved_hazard();
+        }
+
+        assert_eq!(list.iter().count(), 3 * ELEMENTS);
+    }
+
+    #[test]
+    fn capacity() {
+        let list = HazardList::new();
+        assert_eq!(list.capacity(), 0);
+
+        let _ = list.get_or_insert_reserved_hazard();
+        assert_eq!(list.capacity(), ELEMENTS);
+
+        list.reserve(2 * ELEMENTS + 1);
+        assert_eq!(list.capacity(), 3 * ELEMENTS);
+        assert_eq!(list.capacity(), list.iter().count());
+        assert_eq!(list.allocated_bytes(), 3 * mem::size_of::<HazardArrayNode<ELEMENTS>>());
+    }
+
+    #[test]
+    fn try_reserve_before() {
+        let list = HazardList::new();
+        let hazards: Vec<_> = (0..4).map(|_| list.get_or_insert_reserved_hazard()).collect();
+        assert!(list.try_reserve_before(hazards[3]).is_none());
+
+        hazards[1].set_free(Ordering::Relaxed);
+        let reserved = list.try_reserve_before(hazards[3]).unwrap();
+        assert_eq!(reserved as *const _, hazards[1] as *const _);
+        assert!(reserved.is_thread_reserved(Ordering::Relaxed));
+
+        // hazards after the given one are never acquired
+        hazards[3].set_free(Ordering::Relaxed);
+        assert!(list.try_reserve_before(hazards[2]).is_none());
+    }
+
     #[test]
     fn reuse_hazard_from_list() {
         let list = HazardList::new();
@@ -302,4 +504,18 @@ mod tests {
         let acquired_hazard = list.get_or_insert_reserved_hazard();
         assert_eq!(inner_hazard as *const _, acquired_hazard as *const _);
     }
+
+    #[test]
+    fn small_nodes() {
+        let list = super::HazardList::<2>::new();
+        for _ in 0..5 {
+            let _ = list.get_or_insert_reserved_hazard();
+        }
+
+        // three nodes with two hazard pointers each have been allocated
+        assert_eq!(list.iter().count(), 6);
+
+        list.reserve(8);
+        assert_eq!(list.iter().count(), 8);
+    }
 }
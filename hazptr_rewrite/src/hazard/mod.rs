@@ -1,13 +1,21 @@
 mod list;
 
-use core::ptr::NonNull;
-use core::sync::atomic::{AtomicPtr, Ordering};
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{self, AtomicPtr, Ordering};
+
+use crate::config::FenceMode;
 
 pub(crate) use self::list::HazardList;
 
 const FREE: *mut () = 0 as *mut ();
 const THREAD_RESERVED: *mut () = 1 as *mut ();
 const NOT_YET_USED: *mut () = 2 as *mut ();
+/// The sentinel, with which a reclaiming thread replaces a weakly protected
+/// pointer, when it ignores the weak protection under high pressure.
+const WEAK_INVALIDATED: *mut () = 3 as *mut ();
+/// The sentinel marking a hazard pointer as weakly protecting the pointer
+/// stored in its separate `weak` word.
+const WEAK_PROTECTED: *mut () = 4 as *mut ();
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // HazardPtr
@@ -19,6 +27,13 @@ const NOT_YET_USED: *mut () = 2 as *mut ();
 #[derive(Debug)]
 pub(crate) struct HazardPtr {
     protected: AtomicPtr<()>,
+    /// The weakly protected pointer, which is only valid while `protected` is
+    /// set to the [`WEAK_PROTECTED`] sentinel.
+    ///
+    /// The weak state is kept out of the protected address itself, so that
+    /// every regularly protected address remains unambiguous regardless of its
+    /// alignment.
+    weak: AtomicPtr<()>,
 }
 
 /********** impl Hazard ***************************************************************************/
@@ -39,31 +54,117 @@ impl HazardPtr {
         self.protected.store(THREAD_RESERVED, order);
     }
 
+    /// Returns `true` if the [`HazardPtr`] is currently thread-reserved and
+    /// does not protect any value.
+    #[inline]
+    pub fn is_thread_reserved(&self, order: Ordering) -> bool {
+        self.protected.load(order) == THREAD_RESERVED
+    }
+
     #[inline]
     pub fn protected(&self, order: Ordering) -> ProtectedResult {
         match self.protected.load(order) {
-            FREE | THREAD_RESERVED => ProtectedResult::Unprotected,
-            NOT_YET_USED => ProtectedResult::Abort,
+            FREE | THREAD_RESERVED | NOT_YET_USED | WEAK_INVALIDATED => {
+                ProtectedResult::Unprotected
+            }
+            WEAK_PROTECTED => {
+                // (HAZ:5) this `Acquire` fence synchronizes-with the `Release` store (HAZ:3), so
+                // the weakly protected pointer stored before is visible
+                atomic::fence(Ordering::Acquire);
+                let weak = self.weak.load(Ordering::Relaxed);
+                ProtectedResult::WeakProtected(ProtectedPtr(NonNull::new(weak).unwrap()))
+            }
             ptr => ProtectedResult::Protected(ProtectedPtr(NonNull::new(ptr).unwrap())),
         }
     }
 
+    /// Sets the [`HazardPtr`] to weakly protect `protected`, which reclaiming
+    /// threads may invalidate under high pressure.
+    #[inline]
+    pub fn set_weak_protected(&self, protected: NonNull<()>) {
+        self.weak.store(protected.as_ptr(), Ordering::SeqCst);
+        // (HAZ:3) this `Release` store synchronizes-with the `Acquire` CAS (HAZ:4) of a thread
+        // invalidating the weak protection, so all prior accesses happen-before the reclamation,
+        // and with the `Acquire` fence (HAZ:5) of any thread reading the weak pointer
+        self.protected.store(WEAK_PROTECTED, Ordering::Release);
+    }
+
+    /// Returns `true` if the weak protection of the [`HazardPtr`] has been
+    /// invalidated by a reclaiming thread.
+    #[inline]
+    pub fn is_weak_invalidated(&self, order: Ordering) -> bool {
+        self.protected.load(order) == WEAK_INVALIDATED
+    }
+
+    /// Upgrades the weak protection of `protected` to a regular one and returns
+    /// `true` if it has not been invalidated in the meantime.
     #[inline]
-    pub fn set_protected(&self, protected: NonNull<()>, order: Ordering) {
-        assert_eq!(order, Ordering::SeqCst, "this method requires sequential consistency");
-        self.protected.store(protected.as_ptr(), order);
+    pub fn try_upgrade_weak(&self, protected: ProtectedPtr) -> bool {
+        debug_assert_eq!(self.weak.load(Ordering::Relaxed), protected.into_inner().as_ptr());
+        self.protected
+            .compare_exchange(
+                WEAK_PROTECTED,
+                protected.into_inner().as_ptr(),
+                Ordering::SeqCst,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+    }
+
+    /// Invalidates the current weak protection and returns `true`, if it has
+    /// neither been upgraded nor released in the meantime.
+    ///
+    /// If the holder has weakly protected another pointer since `protected`
+    /// was read, that protection is invalidated instead, which the holder
+    /// observes like any other invalidation, so it never accesses either one.
+    #[inline]
+    pub fn try_invalidate_weak(&self, _protected: ProtectedPtr) -> bool {
+        // (HAZ:4) this `Acquire` CAS synchronizes-with the `Release` store (HAZ:3)
+        self.protected
+            .compare_exchange(
+                WEAK_PROTECTED,
+                WEAK_INVALIDATED,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+    }
+
+    /// Sets the [`HazardPtr`] to protect `protected`, which always requires
+    /// sequential consistency.
+    #[inline]
+    pub fn set_protected(&self, protected: NonNull<()>) {
+        self.protected.store(protected.as_ptr(), Ordering::SeqCst);
+    }
+
+    /// Sets the [`HazardPtr`] to protect `protected` with the memory ordering
+    /// required by the (global) fence `mode`.
+    #[inline]
+    pub fn set_protected_with(&self, protected: NonNull<()>, mode: FenceMode) {
+        match mode {
+            FenceMode::AsymmetricMembarrier => {
+                // (HAZ:2) the heavy barrier issued by scanning threads (GLO:1) orders this store
+                // before any subsequent loads, so a compiler fence suffices
+                self.protected.store(protected.as_ptr(), Ordering::Relaxed);
+                atomic::compiler_fence(Ordering::SeqCst);
+            }
+            _ => self.set_protected(protected),
+        }
     }
 
     /// Creates a new [`HazardPointer`].
     #[inline]
     const fn new() -> Self {
-        Self { protected: AtomicPtr::new(NOT_YET_USED) }
+        Self { protected: AtomicPtr::new(NOT_YET_USED), weak: AtomicPtr::new(ptr::null_mut()) }
     }
 
     /// Creates a new [`HazardPointer`] set to initially set to `protected`.
     #[inline]
     const fn with_protected(protected: *const ()) -> Self {
-        Self { protected: AtomicPtr::new(protected as *mut _) }
+        Self {
+            protected: AtomicPtr::new(protected as *mut _),
+            weak: AtomicPtr::new(ptr::null_mut()),
+        }
     }
 }
 
@@ -76,19 +177,24 @@ impl HazardPtr {
 pub(crate) enum ProtectedResult {
     /// Indicates that the hazard pointer currently protects some value.
     Protected(ProtectedPtr),
+    /// Indicates that the hazard pointer currently protects some value weakly,
+    /// i.e. the protection may be ignored by reclaiming threads under high
+    /// pressure.
+    WeakProtected(ProtectedPtr),
     /// Indicates that the hazard pointer currently does not protect any value.
-    Unprotected,
-    /// Indicates that hazard pointer has never been used before.
     ///
-    /// Since hazard pointers are acquired in order this means that any
-    /// iteration of all hazard pointers can abort early, since no subsequent
-    /// hazards pointers could be in use either.
-    Abort,
+    /// This includes hazard pointers that have never been used before.
+    /// Although hazard pointers are generally acquired in order, an iteration
+    /// must not abort early on encountering one, since a thread losing the
+    /// race for such a hazard may already protect a value through any
+    /// subsequent one.
+    Unprotected,
 }
 
 /********** impl inherent *************************************************************************/
 
 impl ProtectedResult {
+    /// Returns the (regularly) protected pointer, if there is any.
     #[inline]
     pub fn protected(self) -> Option<ProtectedPtr> {
         match self {
@@ -144,12 +250,53 @@ mod tests {
     #[test]
     fn hazard_ptr() {
         let hazard = HazardPtr::new();
-        assert_eq!(hazard.protected(Ordering::Relaxed), ProtectedResult::Abort);
-        hazard.set_protected(NonNull::from(&mut 1).cast(), Ordering::Relaxed);
+        assert_eq!(hazard.protected(Ordering::Relaxed), ProtectedResult::Unprotected);
+        hazard.set_protected(NonNull::from(&mut 1).cast());
         assert!(hazard.protected(Ordering::Relaxed).protected().is_some());
         hazard.set_thread_reserved(Ordering::Relaxed);
         assert_eq!(hazard.protected(Ordering::Relaxed), ProtectedResult::Unprotected);
+        assert!(hazard.is_thread_reserved(Ordering::Relaxed));
         hazard.set_free(Ordering::Relaxed);
         assert_eq!(hazard.protected(Ordering::Relaxed), ProtectedResult::Unprotected);
     }
+
+    #[test]
+    fn weak_protected() {
+        let value = Box::new(1u64);
+        let protected = NonNull::from(&*value).cast();
+
+        let hazard = HazardPtr::new();
+        hazard.set_weak_protected(protected);
+        let weak = match hazard.protected(Ordering::Relaxed) {
+            ProtectedResult::WeakProtected(weak) => weak,
+            _ => panic!("hazard must be weakly protected"),
+        };
+
+        assert_eq!(weak.into_inner(), protected);
+        assert!(hazard.try_upgrade_weak(weak));
+        assert!(hazard.protected(Ordering::Relaxed).protected().is_some());
+
+        // an upgraded protection can no longer be invalidated
+        assert!(!hazard.try_invalidate_weak(weak));
+        hazard.set_weak_protected(protected);
+        assert!(hazard.try_invalidate_weak(weak));
+        assert!(hazard.is_weak_invalidated(Ordering::Relaxed));
+        assert_eq!(hazard.protected(Ordering::Relaxed), ProtectedResult::Unprotected);
+        assert!(!hazard.try_upgrade_weak(weak));
+    }
+
+    #[test]
+    fn set_protected() {
+        let mut value = 1;
+        let protected = NonNull::from(&mut value).cast();
+
+        let hazard = HazardPtr::new();
+        hazard.set_protected(protected);
+        match hazard.protected(Ordering::Relaxed) {
+            ProtectedResult::Protected(ptr) => {
+                assert_eq!(ptr.address(), protected.as_ptr() as usize)
+            }
+            _ => panic!("hazard must be protected"),
+        }
+    }
 }
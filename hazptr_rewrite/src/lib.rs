@@ -3,25 +3,66 @@
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
+#[cfg(not(feature = "std"))]
+use alloc::{sync::Arc, vec::Vec};
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
 #[cfg(feature = "std")]
 mod default;
 
+#[cfg(feature = "track-retire-location")]
+mod location;
+#[cfg(feature = "metrics-export")]
+mod metrics;
+#[cfg(feature = "track-owner")]
+mod owner;
+
 mod config;
 mod global;
 mod guard;
 mod hazard;
 mod local;
+mod membarrier;
+mod numa;
 mod queue;
+mod registry;
 mod retire;
+mod schedule;
+#[cfg(test)]
+mod test_util;
+
+use core::cmp;
+
+use conquer_reclaim::{RawRetired, Reclaim};
+
+#[cfg(feature = "std")]
+pub use crate::default::{retire, GlobalHp, GlobalHpRef};
 
-use conquer_reclaim::Reclaim;
+pub use crate::config::{
+    Config, ConfigBuilder, ConfigError, CountStrategy, FenceMode, Operation, RuntimeWarning,
+};
+pub use crate::global::{ProtectedSet, ReclaimProgress, WEAK_DROP_PRESSURE};
+pub use crate::guard::{
+    GuardArray, GuardBusyError, GuardCell, ParkedGuards, PinnedRef, ProtectedHandle, RawGuardToken,
+    PROTECT_VALID_RETRIES,
+};
+pub use crate::hazard::ProtectedPtr;
+#[cfg(feature = "std")]
+pub use crate::local::LocalReport;
+pub use crate::local::{Local, LocalHandle, LocalStats, PendingLocal, RetireVia};
+pub use crate::retire::channel::{RetireBatch, RetireChannel};
+pub use crate::retire::global_retire::{Header, Taken, TakenRecord};
+pub use crate::retire::{GlobalRetire, GlobalRetireExternal, LocalRetire, StrategyKind};
+#[cfg(feature = "std")]
+pub use crate::schedule::SystemClock;
+pub use crate::schedule::{Clock, InlineExecutor, ManualClock, ReclaimExecutor, ReclaimSchedule};
 
-pub use crate::config::{Config, ConfigBuilder, Operation};
-pub use crate::local::{Local, LocalHandle};
-pub use crate::retire::{GlobalRetire, LocalRetire};
+#[cfg(feature = "track-retire-location")]
+pub use crate::location::retire_location;
 
 use crate::global::{Global, GlobalRef};
-use crate::retire::global_retire::Header;
+use crate::local::HAZARD_CACHE;
 use crate::retire::{GlobalRetireState, RetireStrategy};
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -32,62 +73,597 @@ use crate::retire::{GlobalRetireState, RetireStrategy};
 #[derive(Debug)]
 pub struct Hp<S> {
     state: Global,
+    config: Config,
     retire_strategy: S,
 }
 
 /********** impl inherent *************************************************************************/
 
 impl<S: RetireStrategy> Hp<S> {
+    /// Returns the default [`Config`] that is applied to all [`Local`]s built
+    /// without an explicit configuration.
+    #[inline]
+    pub fn config(&self) -> Config {
+        self.config
+    }
+
+    /// Pre-allocates enough hazard pointers for at least `count` of them to be
+    /// in use at once, without any thread having to allocate them when
+    /// acquiring them.
+    ///
+    /// This avoids contention when many threads acquire their first hazard
+    /// pointers at the same time, e.g. at start-up, if the number of threads
+    /// and the number of hazard pointers each of them requires is known.
+    #[inline]
+    pub fn reserve_hazards(&self, count: usize) {
+        self.state.reserve_hazards(count);
+    }
+
+    /// Returns the total number of hazard pointers that have been allocated so
+    /// far, e.g. for reporting metrics.
+    ///
+    /// Hazard pointers are allocated in nodes of fixed size and never
+    /// de-allocated before `self` is dropped, so this is an upper bound for
+    /// the number of hazard pointers that have ever been in use at once.
+    #[inline]
+    pub fn hazard_capacity(&self) -> usize {
+        self.state.hazard_capacity()
+    }
+
+    /// Returns an estimate of the memory in bytes used by the reclaimer
+    /// itself, e.g. for capacity reports.
+    ///
+    /// The estimate comprises the nodes of the hazard list, the retire
+    /// buffers of all live [`Local`]s and the abandoned records of exited
+    /// ones (local retire strategy only), but not the retired records
+    /// themselves.
+    /// Like [`hazard_capacity`][Hp::hazard_capacity], it never decreases
+    /// due to the hazard list, but retire buffers may be de-allocated.
+    #[inline]
+    pub fn overhead_bytes(&self) -> usize {
+        self.state.overhead_bytes()
+    }
+
+    /// Visits every currently protected pointer along with the thread owning
+    /// the protecting hazard pointer, if it is known.
+    ///
+    /// This allows attributing records, which can not be reclaimed, to the
+    /// threads protecting them.
+    #[cfg(feature = "track-owner")]
+    #[inline]
+    pub fn visit_protected(&self, visit: impl FnMut(usize, Option<std::thread::ThreadId>)) {
+        self.state.visit_protected(visit);
+    }
+
+    /// Returns a snapshot of all pointers that are currently protected by any
+    /// thread, sorted by their addresses.
+    ///
+    /// The snapshot is inherently racy, since threads may protect or release
+    /// pointers at any time while or after it is taken, so it must not be
+    /// used for deciding whether a record can be reclaimed.
+    /// It is, however, useful for post-mortem diagnostics (e.g. of records
+    /// that are never reclaimed) and for assertions in tests.
+    #[inline]
+    pub fn protected_snapshot(&self) -> Vec<ProtectedPtr> {
+        self.state.protected_snapshot()
+    }
+
+    /// Returns the kind of the retire strategy, e.g. for generic code that
+    /// calls APIs only available with one of the strategies.
+    #[inline]
+    pub fn strategy_kind(&self) -> StrategyKind {
+        S::KIND
+    }
+
     /// Builds a new instance of a [`Local`] that stores a reference (i.e.
     /// borrows) the internal global state of `self`.
     ///
     /// If `config` wraps a [`Config`] instance this instance is used to
-    /// supply the [`Local`]'s internal configuration, otherwise the default
-    /// configuration is applied.
+    /// supply the [`Local`]'s internal configuration, otherwise the
+    /// configuration `self` was constructed with is applied (see e.g.
+    /// [`with_config`][Hp::with_config]).
     #[inline]
     pub fn build_local(&self, config: Option<Config>) -> Local {
-        Local::new(config.unwrap_or_default(), GlobalRef::from_ref(&self.state))
+        Local::new(config.unwrap_or(self.config), GlobalRef::from_ref(&self.state))
     }
 
     /// Builds a new instance of a [`Local`] that stores a pointer (i.e. without
     /// borrowing) the internal global state of `self`.
     ///
     /// If `config` wraps a [`Config`] instance this instance is used to
-    /// supply the [`Local`]'s internal configuration, otherwise the default
-    /// configuration is applied.
+    /// supply the [`Local`]'s internal configuration, otherwise the
+    /// configuration `self` was constructed with is applied (see e.g.
+    /// [`with_config`][Hp::with_config]).
     ///
     /// # Safety
     ///
     /// The resulting [`Local`] is not lifetime-dependent on the [`Hp`] instance
     /// it is derived from, which allows e.g. self-referential types.
     /// The caller is required, however, to ensure that the [`Local`] instance
-    /// does not outlive `self`.
+    /// does not outlive `self` and is not alive when [`reset`][Hp::reset] is
+    /// called.
     #[inline]
     pub unsafe fn build_local_unchecked(&self, config: Option<Config>) -> Local<'_> {
-        Local::new(config.unwrap_or_default(), GlobalRef::from_raw(&self.state))
+        Local::new(config.unwrap_or(self.config), GlobalRef::from_raw(&self.state))
+    }
+
+    /// Prepares `n` new instances of [`Local`], e.g. for a fixed size pool of
+    /// worker threads, which can each be sent to their respective thread and
+    /// built there with [`PendingLocal::into_local`].
+    ///
+    /// Each [`Local`] is pre-warmed with at least one reserved hazard pointer
+    /// and the global hazard list is sized for the pool's typical footprint
+    /// up front, so the worker threads need not allocate any hazards.
+    #[inline]
+    pub fn build_locals(&self, n: usize) -> Vec<PendingLocal> {
+        let mut config = self.config;
+        config.initial_reserved_hazards = cmp::max(config.initial_reserved_hazards, 1);
+        self.reserve_hazards(n.saturating_mul(config.initial_reserved_hazards));
+        (0..n).map(|_| PendingLocal::new(config, &self.state)).collect()
+    }
+
+    /// Returns the highest number of simultaneously protected pointers that
+    /// has been observed by any scan so far.
+    ///
+    /// This can serve as a guideline for sizing the number of hazard pointers
+    /// required by an application.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn peak_protected(&self) -> usize {
+        self.state.peak_protected()
+    }
+
+    /// Returns the number of threads currently retrying to protect a value,
+    /// because it keeps changing while they attempt to protect it.
+    ///
+    /// A persistently high count hints at a livelock, e.g. a hot pointer being
+    /// swapped too frequently for readers to ever protect it.
+    /// The count is only a best-effort snapshot.
+    #[inline]
+    pub fn threads_spinning(&self) -> usize {
+        self.state.threads_spinning()
+    }
+
+    /// Checks the default configuration against the current runtime state
+    /// and returns a warning for configurations that are likely to cause
+    /// problems, e.g. as a sanity check at startup.
+    ///
+    /// # Errors
+    ///
+    /// Fails with a [`RuntimeWarning`], if e.g. the hazard pointers that may
+    /// be cached by all currently live [`Local`]s exceed the configured
+    /// [`max_hazard_pointers`][Config::max_hazard_pointers].
+    #[inline]
+    pub fn validate_runtime(&self) -> Result<(), RuntimeWarning> {
+        if self.config.initial_reserved_hazards > HAZARD_CACHE {
+            return Err(RuntimeWarning::InitialReservationExceedsCache {
+                requested: self.config.initial_reserved_hazards,
+                capacity: HAZARD_CACHE,
+            });
+        }
+
+        if let Some(max) = self.config.max_hazard_pointers {
+            let cached = self.config.max_cached_hazards.min(HAZARD_CACHE);
+            let required = cached * self.state.live_locals();
+            if required > max {
+                return Err(RuntimeWarning::HazardOversubscription { required, max });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the effective [`FenceMode`], which may differ from the one that
+    /// was configured, if it is not supported on the current platform.
+    #[inline]
+    pub fn fence_mode(&self) -> FenceMode {
+        self.state.fence_mode()
+    }
+
+    /// Resets `self` to a clean state for re-use, e.g. between iterations of a
+    /// fuzzing harness.
+    ///
+    /// All globally stored retired records are reclaimed, all hazard pointers
+    /// are freed (including any leaked by e.g. forgotten guards) and all
+    /// internal counters are reset, but the allocated hazard pointers remain
+    /// in place for re-use.
+    /// Exclusive access guarantees that no [`Local`] built with
+    /// [`build_local`][Hp::build_local] can be alive at this point, so no
+    /// record can be protected any longer.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.state.reset();
+    }
+
+    /// Repeatedly attempts to reclaim all globally stored retired records
+    /// until either a round reclaims no records at all or `max_rounds` rounds
+    /// have been executed and returns the total number of reclaimed records.
+    ///
+    /// This is useful e.g. during shutdown, when the `Drop` implementations of
+    /// reclaimed records themselves retire further records, which can only be
+    /// reclaimed in a subsequent round.
+    /// With the local retire strategy, only records that have been abandoned
+    /// by exited threads are stored globally.
+    #[inline]
+    pub fn reclaim_until_quiescent(&self, max_rounds: usize) -> usize {
+        let mut scan_cache = self.state.borrow_scan_cache();
+        let mut total = 0;
+
+        for _ in 0..max_rounds {
+            let reclaimed =
+                self.state.reclaim_all_unprotected(&mut scan_cache, self.config.can_reclaim);
+            #[cfg(feature = "stats")]
+            self.state.record_scan(reclaimed);
+
+            match reclaimed {
+                0 => break,
+                reclaimed => total += reclaimed,
+            }
+        }
+
+        total
+    }
+
+    /// Reclaims all remaining globally stored records, which are not protected
+    /// by any hazard pointer, and returns the number of reclaimed records.
+    ///
+    /// Like dropping `self`, this ignores the
+    /// [`can_reclaim`][Config::can_reclaim] callback, so it allows e.g. leak
+    /// tests to deterministically assert that all records have been reclaimed
+    /// before `self` is dropped.
+    /// With the local retire strategy, only records that have been abandoned
+    /// by exited threads are stored globally.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any [`Local`] built from `self` is still alive.
+    #[inline]
+    pub fn reclaim_remaining(&self) -> usize {
+        assert_eq!(self.state.live_locals(), 0, "all thread local states must have been dropped");
+        let mut scan_cache = self.state.borrow_scan_cache();
+        let mut total = 0;
+
+        // reclaimed records may retire further records when being dropped
+        loop {
+            let reclaimed = self.state.reclaim_all_unprotected(&mut scan_cache, None);
+            #[cfg(feature = "stats")]
+            self.state.record_scan(reclaimed);
+
+            match reclaimed {
+                0 => return total,
+                reclaimed => total += reclaimed,
+            }
+        }
+    }
+
+    /// Reclaims the single record `retired` right away, if it is currently
+    /// neither protected by any hazard pointer nor vetoed by the
+    /// [`can_reclaim`][Config::can_reclaim] callback.
+    ///
+    /// This allows "free now if safe, otherwise defer" policies without
+    /// queuing the record first.
+    ///
+    /// # Errors
+    ///
+    /// Fails and returns `retired`, if it can not be reclaimed yet, in which
+    /// case it should be retired regularly instead.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure `retired` satisfies the usual invariants for
+    /// retiring, i.e. it must no longer be reachable by other threads (except
+    /// through already protected pointers) and must not be retired elsewhere.
+    #[inline]
+    pub unsafe fn try_reclaim_one(&self, retired: RawRetired) -> Result<(), RawRetired> {
+        let addr = retired.address();
+        if self.state.is_protected(addr)
+            || !self.config.can_reclaim.map_or(true, |can_reclaim| can_reclaim(addr))
+        {
+            return Err(retired);
+        }
+
+        retired.reclaim();
+        #[cfg(feature = "stats")]
+        self.state.record_reclaimed(1);
+        Ok(())
+    }
+
+    /// Polls the given `schedule` and performs a single reclamation attempt
+    /// (see [`reclaim_until_quiescent`][Hp::reclaim_until_quiescent]) if one
+    /// is due.
+    ///
+    /// Returns the number of reclaimed records, if an attempt was made.
+    #[inline]
+    pub fn poll_reclaim<C: Clock>(&self, schedule: &mut ReclaimSchedule<C>) -> Option<usize> {
+        if schedule.poll() {
+            Some(self.reclaim_until_quiescent(1))
+        } else {
+            None
+        }
     }
 }
 
-/********** impl Default **************************************************************************/
+impl<S: RetireStrategy + 'static> Hp<S>
+where
+    Self: Send + Sync,
+{
+    /// Returns the current generation, with which all records are tagged when
+    /// they are retired.
+    #[inline]
+    pub fn generation(&self) -> usize {
+        self.state.generation()
+    }
 
-impl Default for Hp<GlobalRetire> {
+    /// Advances the current generation and returns the new one.
+    ///
+    /// Records retired before the call can then be distinguished from those
+    /// retired afterwards, see [`reclaim_older_than`][Hp::reclaim_older_than].
     #[inline]
-    fn default() -> Self {
+    pub fn advance_generation(&self) -> usize {
+        self.state.advance_generation()
+    }
+
+    /// Reclaims all globally stored records that were retired before the
+    /// given `generation` and are no longer protected and returns the number
+    /// of reclaimed records.
+    ///
+    /// Records retired in `generation` or later are left in place, even if
+    /// they are unprotected, which allows giving recently retired records a
+    /// grace period.
+    /// As with [`reclaim_until_quiescent`][Hp::reclaim_until_quiescent], only
+    /// globally stored records are considered.
+    #[inline]
+    pub fn reclaim_older_than(&self, generation: usize) -> usize {
+        let mut scan_cache = self.state.borrow_scan_cache();
+        let reclaimed =
+            self.state.reclaim_older_than(generation, &mut scan_cache, self.config.can_reclaim);
+        #[cfg(feature = "stats")]
+        self.state.record_scan(reclaimed);
+        reclaimed
+    }
+
+    /// Dispatches a single reclamation attempt (see
+    /// [`reclaim_until_quiescent`][Hp::reclaim_until_quiescent]) to the given
+    /// `executor` rather than running it on the calling thread.
+    ///
+    /// The scheduled job keeps the [`Hp`] instance alive until it has run.
+    #[inline]
+    pub fn schedule_reclaim(self: &Arc<Self>, executor: &impl ReclaimExecutor) {
+        let hp = Arc::clone(self);
+        executor.schedule(move || {
+            hp.reclaim_until_quiescent(1);
+        });
+    }
+}
+
+impl Hp<GlobalRetire> {
+    /// Creates a new [`Hp`] with the global retire strategy, which applies
+    /// the given `config` to all [`Local`]s that are built without an explicit
+    /// configuration.
+    #[inline]
+    pub fn with_config(config: Config) -> Self {
+        Self::global_retire_sharded(config, 1)
+    }
+
+    /// Retires an entire chain of records, e.g. a truncated segment of a
+    /// linked list, from `head` to `tail` at once.
+    ///
+    /// The records must already be linked through their headers (see
+    /// [`Header::from_retired`] and [`Header::link`]), so the chain is spliced
+    /// into the global queue as a whole, which is considerably cheaper than
+    /// retiring each record individually.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure that all headers in the chain have been
+    /// initialized and linked from `head` to `tail` and that all records
+    /// satisfy the usual invariants for retiring.
+    #[inline]
+    pub unsafe fn retire_linked_chain(&self, head: *mut Header, tail: *mut Header) {
+        match &self.state.retire_state {
+            GlobalRetireState::GlobalStrategy(queue) => {
+                let (generation, scan) = (self.state.generation(), self.state.scan_count());
+                queue.shard(queue.assign_shard()).retire_chain(head, tail, generation, scan)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Reclaims at most `max_records` unprotected records from the global
+    /// queue and returns the number of reclaimed records.
+    ///
+    /// The protected hazards collected into `set` are re-used by consecutive
+    /// calls for all records that were retired before the set was collected,
+    /// so draining a large backlog in bounded chunks usually requires
+    /// collecting the hazards only once.
+    /// The set is collected anew (which advances the
+    /// [`generation`][Hp::generation]) only when a more recently retired
+    /// record is encountered.
+    ///
+    /// If any thread has requested a higher reclamation pressure since the
+    /// last call (see [`raise_reclaim_pressure`][Hp::raise_reclaim_pressure]),
+    /// `max_records` is multiplied by the requested factor for this call only.
+    #[inline]
+    pub fn reclaim_chunk(&self, set: &mut ProtectedSet, max_records: usize) -> usize {
+        let reclaimed = self.state.reclaim_chunk(set, max_records, self.config.can_reclaim);
+        #[cfg(feature = "stats")]
+        self.state.record_scan(reclaimed);
+        reclaimed
+    }
+
+    /// Takes all records from the global queue at once for reclaiming them
+    /// according to a custom policy.
+    ///
+    /// Iterating the [`Taken`] records yields each record along with whether
+    /// it is protected, unprotected records can be reclaimed individually,
+    /// while all others are pushed back into the queue.
+    #[inline]
+    pub fn take_retired(&self) -> Taken<'_> {
+        self.state.take_retired()
+    }
+
+    /// Visits the address and generation of every record that is currently
+    /// pending in the global queue without removing it, e.g. for counting the
+    /// pending records per address in a monitoring thread.
+    ///
+    /// The visited records are only a best-effort snapshot: each shard of the
+    /// queue is taken while it is visited and pushed back afterwards, so
+    /// records retired meanwhile are not visited and the visited ones can not
+    /// be reclaimed by other threads until then.
+    #[inline]
+    pub fn visit_retired(&self, visit: impl FnMut(usize, usize)) {
+        self.state.visit_retired(visit);
+    }
+
+    /// Returns a snapshot of the global queue's reclamation progress, i.e. the
+    /// number of pending records, the number of records reclaimed by the most
+    /// recent scan and the current generation, e.g. for monitoring whether
+    /// reclamation keeps up with retirement.
+    #[inline]
+    pub fn reclaim_progress(&self) -> ReclaimProgress {
+        self.state.reclaim_progress()
+    }
+
+    /// Requests that the next thread reclaiming a chunk of records (see
+    /// [`reclaim_chunk`][Hp::reclaim_chunk]) raises its limit by `factor`.
+    ///
+    /// This allows e.g. a thread retiring many records, while other threads
+    /// do the reclaiming, to signal a growing backlog in the global queue.
+    /// Concurrent requests are not accumulated, the highest requested factor
+    /// applies.
+    /// A factor of at least [`WEAK_DROP_PRESSURE`] signals high memory
+    /// pressure, in which case the next chunk ignores (and invalidates) all
+    /// weak protections.
+    #[inline]
+    pub fn raise_reclaim_pressure(&self, factor: usize) {
+        self.state.raise_pressure(factor);
+    }
+
+    /// Creates a new [`Hp`] with the global retire strategy, which distributes
+    /// all retired records among `shards` separate global queues.
+    ///
+    /// Each thread retires its records into a single (assigned) shard, but all
+    /// threads reclaim the records from all shards.
+    /// The given `config` is applied to all [`Local`]s that are built without
+    /// an explicit configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is 0.
+    #[inline]
+    pub fn global_retire_sharded(config: Config, shards: usize) -> Self {
         Self {
-            state: Global::new(GlobalRetireState::global_strategy()),
+            state: Global::with_fence_mode(
+                GlobalRetireState::global_strategy(shards),
+                config.fence_mode,
+            ),
+            config,
+            retire_strategy: GlobalRetire,
+        }
+    }
+
+    /// Creates a new [`Hp`] with the global retire strategy, which maintains a
+    /// separate global queue for each of the `nodes` NUMA nodes.
+    ///
+    /// Each thread retires its records into the queue of the node it is
+    /// running on (see [`Config::numa_node`]) and prefers reclaiming the
+    /// records in that queue before those of all other nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nodes` is 0.
+    #[inline]
+    pub fn global_retire_numa(config: Config, nodes: usize) -> Self {
+        Self {
+            state: Global::with_fence_mode(
+                GlobalRetireState::global_strategy_per_node(nodes),
+                config.fence_mode,
+            ),
+            config,
             retire_strategy: GlobalRetire,
         }
     }
 }
 
-impl Default for Hp<LocalRetire> {
+impl Hp<GlobalRetireExternal> {
+    /// Creates a new [`Hp`] with the global retire strategy, which allocates
+    /// the header of each retired record separately, and applies the given
+    /// `config` to all [`Local`]s that are built without an explicit
+    /// configuration.
     #[inline]
-    fn default() -> Self {
+    pub fn with_config(config: Config) -> Self {
+        Self {
+            state: Global::with_fence_mode(
+                GlobalRetireState::global_strategy_with_external_headers(1),
+                config.fence_mode,
+            ),
+            config,
+            retire_strategy: GlobalRetireExternal,
+        }
+    }
+}
+
+impl Hp<LocalRetire> {
+    /// Creates a new [`Hp`] with the local retire strategy, which applies
+    /// the given `config` to all [`Local`]s that are built without an explicit
+    /// configuration.
+    #[inline]
+    pub fn with_config(config: Config) -> Self {
         Self {
-            state: Global::new(GlobalRetireState::local_strategy()),
+            state: Global::with_fence_mode(GlobalRetireState::local_strategy(), config.fence_mode),
+            config,
             retire_strategy: LocalRetire,
         }
     }
+
+    /// Returns the number of pending (i.e. retired but not yet reclaimed)
+    /// records of every currently live [`Local`].
+    ///
+    /// This is useful for identifying threads that are accumulating retired
+    /// records, e.g. because they rarely reach the reclamation threshold.
+    /// The order of the returned counts is unspecified.
+    #[inline]
+    pub fn per_thread_pending(&self) -> Vec<usize> {
+        self.state.pending()
+    }
+
+    /// Creates a new empty [`RetireChannel`] holding at most `capacity`
+    /// batches of records retired by worker threads.
+    ///
+    /// Any records remaining in the channel when it is dropped are reclaimed,
+    /// unless they are still protected, in which case they are abandoned like
+    /// the records of an exited thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    #[inline]
+    pub fn retire_channel(&self, capacity: usize) -> RetireChannel<'_> {
+        RetireChannel::new(&self.state, capacity)
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for Hp<GlobalRetire> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_config(Config::default())
+    }
+}
+
+impl Default for Hp<GlobalRetireExternal> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_config(Config::default())
+    }
+}
+
+impl Default for Hp<LocalRetire> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_config(Config::default())
+    }
 }
 
 /********** impl Reclaim **************************************************************************/
@@ -104,6 +680,17 @@ unsafe impl Reclaim for Hp<GlobalRetire> {
     }
 }
 
+unsafe impl Reclaim for Hp<GlobalRetireExternal> {
+    // the header is allocated separately when a record is retired
+    type Header = ();
+    type Ref = LocalHandle<'static, 'static, Self>;
+
+    #[inline]
+    fn new() -> Self {
+        Default::default()
+    }
+}
+
 unsafe impl Reclaim for Hp<LocalRetire> {
     type Header = ();
     type Ref = LocalHandle<'static, 'static, Self>;
@@ -113,3 +700,1009 @@ unsafe impl Reclaim for Hp<LocalRetire> {
         Default::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+    use std::ptr::NonNull;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    #[cfg(feature = "stats")]
+    use std::sync::Barrier;
+    use std::thread;
+
+    use conquer_reclaim::conquer_pointer::MaybeNull::NotNull;
+    use conquer_reclaim::typenum::U0;
+    use conquer_reclaim::{Protect, Shared, Unlinked};
+
+    use crate::guard::Guard;
+    use crate::hazard::ProtectStrategy;
+    use crate::local::HAZARD_CACHE;
+    use crate::retire::{self, GlobalRetireState};
+    use crate::test_util::{retired, DropCount};
+    use crate::{
+        Config, ConfigBuilder, FenceMode, GlobalRetire, GlobalRetireExternal, Header, Hp, Local,
+        LocalHandle, LocalRetire, ProtectedSet, ReclaimProgress, RuntimeWarning, StrategyKind,
+        WEAK_DROP_PRESSURE,
+    };
+
+    type Atomic<T> = conquer_reclaim::Atomic<T, Hp<GlobalRetire>, U0>;
+    type Owned<T> = conquer_reclaim::Owned<T, Hp<GlobalRetire>, U0>;
+
+    struct Node {
+        children: Vec<Atomic<Node>>,
+        local: &'static Local<'static>,
+        count: &'static AtomicUsize,
+    }
+
+    impl Node {
+        fn tree(depth: usize, local: &'static Local, count: &'static AtomicUsize) -> Self {
+            let children = match depth {
+                0 => Vec::new(),
+                _ => (0..2).map(|_| Atomic::new(Node::tree(depth - 1, local, count))).collect(),
+            };
+
+            Self { children, local, count }
+        }
+    }
+
+    impl Drop for Node {
+        fn drop(&mut self) {
+            self.count.fetch_add(1, Ordering::Relaxed);
+            // dropping a node retires all of its children
+            for child in &self.children {
+                if let Some(unlinked) = child.swap(Owned::none(), Ordering::Relaxed) {
+                    self.local.retire(Unlinked::into_retired(unlinked).into_raw());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn config() {
+        let config = ConfigBuilder::new().initial_scan_cache_size(1).build();
+        let hp = Hp::global_retire_sharded(config, 1);
+        assert_eq!(hp.config(), config);
+        assert_eq!(Hp::<LocalRetire>::default().config(), Config::default());
+        assert_eq!(Hp::<GlobalRetire>::with_config(config).config(), config);
+        assert_eq!(Hp::<LocalRetire>::with_config(config).config(), config);
+    }
+
+    #[test]
+    fn with_config() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        // a local built without an explicit config scans after every retired record
+        let hp =
+            Hp::<GlobalRetire>::with_config(ConfigBuilder::new().ops_count_threshold(1).build());
+        let local = hp.build_local(None);
+        for expected in 1..=4 {
+            local.retire(retired(&COUNT));
+            assert_eq!(COUNT.load(Ordering::Relaxed), expected);
+        }
+    }
+
+    #[cfg(feature = "track-owner")]
+    #[test]
+    fn visit_protected() {
+        use std::sync::mpsc;
+
+        static VALUE: u64 = 1;
+
+        let hp: &'static Hp<LocalRetire> = Box::leak(Box::new(Hp::default()));
+        let (id_tx, id_rx) = mpsc::channel();
+        let (done_tx, done_rx) = mpsc::channel::<()>();
+
+        let handle = thread::Builder::new()
+            .name(String::from("protector"))
+            .spawn(move || {
+                let local = hp.build_local(None);
+                let hazard = local.get_hazard(ProtectStrategy::ReserveOnly);
+                hazard.set_protected(NonNull::from(&VALUE).cast());
+                id_tx.send(thread::current().id()).unwrap();
+                // the value remains protected until the main thread is done visiting
+                done_rx.recv().unwrap();
+                hazard.set_free(Ordering::Relaxed);
+            })
+            .unwrap();
+
+        let id = id_rx.recv().unwrap();
+        assert_eq!(handle.thread().name(), Some("protector"));
+
+        let mut owners = Vec::new();
+        hp.visit_protected(|addr, owner| {
+            if addr == &VALUE as *const u64 as usize {
+                owners.push(owner);
+            }
+        });
+
+        assert_eq!(owners, vec![Some(id)]);
+        done_tx.send(()).unwrap();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn strategy_kind() {
+        assert_eq!(Hp::<GlobalRetire>::default().strategy_kind(), StrategyKind::Global);
+        assert_eq!(Hp::<LocalRetire>::default().strategy_kind(), StrategyKind::Local);
+        assert_eq!(
+            Hp::global_retire_sharded(Config::default(), 4).strategy_kind(),
+            StrategyKind::Global
+        );
+    }
+
+    #[test]
+    fn validate_runtime() {
+        let config = ConfigBuilder::new().max_hazard_pointers(HAZARD_CACHE + 4).build();
+        let hp = Hp::global_retire_sharded(config, 1);
+
+        let first = hp.build_local(None);
+        assert_eq!(hp.validate_runtime(), Ok(()));
+
+        // the caches of two threads could exceed the maximum number of hazards
+        let second = hp.build_local(None);
+        assert_eq!(
+            hp.validate_runtime(),
+            Err(RuntimeWarning::HazardOversubscription {
+                required: 2 * HAZARD_CACHE,
+                max: HAZARD_CACHE + 4
+            })
+        );
+
+        drop((first, second));
+        assert_eq!(hp.validate_runtime(), Ok(()));
+
+        // only the hazards each thread may actually cache count towards the maximum
+        let config =
+            ConfigBuilder::new().max_cached_hazards(4).max_hazard_pointers(HAZARD_CACHE).build();
+        let hp = Hp::global_retire_sharded(config, 1);
+        let locals: Vec<_> = (0..HAZARD_CACHE / 4).map(|_| hp.build_local(None)).collect();
+        assert_eq!(hp.validate_runtime(), Ok(()));
+        drop(locals);
+
+        let config = ConfigBuilder::new().initial_reserved_hazards(HAZARD_CACHE + 1).build();
+        let hp = Hp::global_retire_sharded(config, 1);
+        assert!(matches!(
+            hp.validate_runtime(),
+            Err(RuntimeWarning::InitialReservationExceedsCache { .. })
+        ));
+    }
+
+    #[test]
+    fn retire_linked_chain() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let headers: Vec<_> =
+            (0..50).map(|_| unsafe { Header::from_retired(retired(&COUNT)) }).collect();
+
+        for pair in headers.windows(2) {
+            unsafe { Header::link(pair[0], pair[1]) };
+        }
+
+        unsafe { hp.retire_linked_chain(headers[0], headers[49]) };
+        assert_eq!(hp.reclaim_until_quiescent(1), 50);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 50);
+    }
+
+    #[test]
+    fn retire_many() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let local = hp.build_local(None);
+        let handle = LocalHandle::from_ref(&local);
+
+        let retired = (0..50).map(|_| {
+            let atomic = Atomic::new(DropCount(&COUNT));
+            let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+            Unlinked::into_retired(unlinked)
+        });
+
+        // the entire chain is spliced into the queue below the ops count threshold
+        unsafe { handle.retire_many(retired) };
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+        assert_eq!(hp.reclaim_until_quiescent(1), 50);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 50);
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn peak_protected() {
+        const THREADS: usize = 4;
+
+        let hp = Arc::new(Hp::<GlobalRetire>::default());
+        let (protected, release) =
+            (Arc::new(Barrier::new(THREADS + 1)), Arc::new(Barrier::new(THREADS + 1)));
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let (hp, protected, release) =
+                    (Arc::clone(&hp), Arc::clone(&protected), Arc::clone(&release));
+                thread::spawn(move || {
+                    let local = hp.build_local(None);
+                    let atomic = Atomic::new(1);
+                    let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+                    let _ = guard.protect(&atomic, Ordering::Acquire);
+
+                    protected.wait();
+                    release.wait();
+                })
+            })
+            .collect();
+
+        // scan while all threads hold their protection
+        protected.wait();
+        let mut scan_cache = Vec::new();
+        hp.state.collect_protected_hazards(&mut scan_cache, Ordering::SeqCst);
+        release.wait();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert!(hp.peak_protected() >= THREADS);
+    }
+
+    #[test]
+    fn fence_modes() {
+        const THREADS: usize = 4;
+        const ITERATIONS: usize = 256;
+        const MAGIC: usize = 0xDEAD_BEEF;
+        static COUNTS: [AtomicUsize; 2] = [AtomicUsize::new(0), AtomicUsize::new(0)];
+
+        struct Record(&'static AtomicUsize, usize);
+        impl Drop for Record {
+            fn drop(&mut self) {
+                // invalidate the record to detect any use after reclamation
+                self.1 = 0;
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        for (mode, count) in
+            [FenceMode::SeqCst, FenceMode::AsymmetricMembarrier].iter().zip(&COUNTS)
+        {
+            let config = ConfigBuilder::new().fence_mode(*mode).build();
+            let hp = Arc::new(Hp::global_retire_sharded(config, 1));
+            assert!(hp.fence_mode() == *mode || hp.fence_mode() == FenceMode::SeqCst);
+
+            let shared = Arc::new(Atomic::new(Record(count, MAGIC)));
+            let handles: Vec<_> = (0..THREADS)
+                .map(|_| {
+                    let (hp, shared) = (Arc::clone(&hp), Arc::clone(&shared));
+                    thread::spawn(move || {
+                        let local = hp.build_local(None);
+                        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+                        for _ in 0..ITERATIONS {
+                            if let NotNull(record) = guard.protect(&*shared, Ordering::Acquire) {
+                                assert_eq!(Shared::into_ref(record).1, MAGIC);
+                            }
+
+                            let new = Owned::new(Record(count, MAGIC));
+                            let unlinked = shared.swap(new, Ordering::AcqRel).unwrap();
+                            local.retire(Unlinked::into_retired(unlinked).into_raw());
+                        }
+                    })
+                })
+                .collect();
+
+            for handle in handles {
+                handle.join().unwrap();
+            }
+
+            hp.reclaim_until_quiescent(1);
+            assert_eq!(count.load(Ordering::Relaxed), THREADS * ITERATIONS);
+        }
+    }
+
+    #[test]
+    fn build_locals() {
+        const THREADS: usize = 4;
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp: &'static _ = Box::leak(Box::new(Hp::global_retire_sharded(Config::default(), 1)));
+        let locals = hp.build_locals(THREADS);
+        assert_eq!(locals.len(), THREADS);
+        assert!(hp.hazard_capacity() >= THREADS);
+
+        let shared = Arc::new(Atomic::new(DropCount(&COUNT)));
+        let handles: Vec<_> = locals
+            .into_iter()
+            .map(|local| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || {
+                    let local = local.into_local();
+                    assert!(local.cached_hazard_count() >= 1);
+                    let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+                    let _ = guard.protect(&*shared, Ordering::Acquire);
+                    let unlinked = shared.swap(Owned::new(DropCount(&COUNT)), Ordering::AcqRel);
+                    let retired = Unlinked::into_retired(unlinked.unwrap()).into_raw();
+                    local.retire(retired);
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        hp.reclaim_until_quiescent(1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), THREADS);
+    }
+
+    #[test]
+    fn numa_node_shards() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let pending = |hp: &Hp<GlobalRetire>| match &hp.state.retire_state {
+            GlobalRetireState::GlobalStrategy(queue) => {
+                (0..queue.len()).map(|idx| queue.shard(idx).pending()).collect::<Vec<_>>()
+            }
+            _ => panic!("invalid retire state"),
+        };
+
+        // the node hint is only respected for per-node queues, where node 3 maps to shard 1
+        let numa = Hp::global_retire_numa(Config::default(), 2);
+        let sharded = Hp::global_retire_sharded(Config::default(), 2);
+        let locals: Vec<_> = [&numa, &sharded]
+            .iter()
+            .map(|hp| {
+                let local = hp.build_local(Some(ConfigBuilder::new().numa_node(3).build()));
+                for _ in 0..4 {
+                    local.retire(retired(&COUNT));
+                }
+                local
+            })
+            .collect();
+
+        assert_eq!(pending(&numa), [0, 4]);
+        assert_eq!(pending(&sharded), [4, 0]);
+
+        mem::drop(locals);
+        numa.reclaim_until_quiescent(1);
+        sharded.reclaim_until_quiescent(1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 8);
+    }
+
+    #[test]
+    fn reset() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let mut hp = Hp::global_retire_sharded(Config::default(), 2);
+        for iteration in 1..=2 {
+            let local = hp.build_local(None);
+            let atomics: Vec<_> = (0..4).map(|_| Atomic::new(DropCount(&COUNT))).collect();
+
+            // a leaked guard keeps protecting the first record beyond the iteration
+            let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+            let _ = guard.protect(&atomics[0], Ordering::Acquire);
+            mem::forget(guard);
+
+            for atomic in &atomics {
+                let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+                local.retire(Unlinked::into_retired(unlinked).into_raw());
+            }
+
+            drop(local);
+            assert_eq!(COUNT.load(Ordering::Relaxed), iteration * 4 - 1);
+
+            // the reset frees the leaked hazard and reclaims the remaining record
+            hp.reset();
+            assert_eq!(COUNT.load(Ordering::Relaxed), iteration * 4);
+            let mut protected = Vec::new();
+            hp.state.collect_protected_hazards(&mut protected, Ordering::SeqCst);
+            assert!(protected.is_empty());
+            match &hp.state.retire_state {
+                GlobalRetireState::GlobalStrategy(queue) => {
+                    assert!(queue.is_empty());
+                    assert_eq!(queue.assign_shard(), 0);
+                }
+                _ => panic!("invalid retire state"),
+            }
+        }
+    }
+
+    #[test]
+    fn drop_reclaims_abandoned() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let records: Vec<_> = (0..4).map(|_| retired(&COUNT)).collect();
+
+        // the hazards keep protecting all records, so they must be abandoned
+        let hazards: Vec<_> = records
+            .iter()
+            .map(|retired| {
+                let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+                hazard.set_protected(NonNull::new(retired.address() as *mut ()).unwrap());
+                hazard
+            })
+            .collect();
+
+        for retired in records {
+            local.retire(retired);
+        }
+
+        drop(local);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+        match &hp.state.retire_state {
+            GlobalRetireState::LocalStrategy(abandoned) => assert!(!abandoned.is_empty()),
+            _ => panic!("invalid retire state"),
+        }
+
+        // dropping the global state reclaims all abandoned records, once they are unprotected
+        for hazard in hazards {
+            hazard.set_free(Ordering::Relaxed);
+        }
+
+        drop(hp);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn per_thread_pending() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<LocalRetire>::default();
+        let (first, second) = (hp.build_local(None), hp.build_local(None));
+        assert_eq!(hp.per_thread_pending(), vec![0, 0]);
+
+        for (local, records) in [(&first, 2), (&second, 5)].iter() {
+            for _ in 0..*records {
+                local.retire(retired(&COUNT));
+            }
+        }
+
+        let mut pending = hp.per_thread_pending();
+        pending.sort();
+        assert_eq!(pending, vec![2, 5]);
+
+        // the slot of a dropped local is no longer reported
+        drop(first);
+        assert_eq!(hp.per_thread_pending(), vec![5]);
+    }
+
+    #[test]
+    fn reclaim_until_quiescent() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp: &'static Hp<GlobalRetire> = Box::leak(Box::new(Hp::default()));
+        let local: &'static Local = Box::leak(Box::new(hp.build_local(None)));
+
+        // a complete binary tree with 15 nodes
+        let root = Atomic::new(Node::tree(3, local, &COUNT));
+        let unlinked = root.swap(Owned::none(), Ordering::Relaxed).unwrap();
+        local.retire(Unlinked::into_retired(unlinked).into_raw());
+
+        // each level of the tree requires a separate round
+        assert_eq!(hp.reclaim_until_quiescent(2), 3);
+        assert_eq!(hp.reclaim_until_quiescent(8), 12);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 15);
+        assert_eq!(hp.reclaim_until_quiescent(8), 0);
+    }
+
+    #[test]
+    fn can_reclaim_veto() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        static VETOED: AtomicUsize = AtomicUsize::new(0);
+
+        fn can_reclaim(addr: usize) -> bool {
+            addr != VETOED.load(Ordering::Relaxed)
+        }
+
+        // every retire triggers a scan
+        let hp = Hp::<LocalRetire>::default();
+        let config = ConfigBuilder::new().total_ops_budget(1).can_reclaim(can_reclaim).build();
+        let local = hp.build_local(Some(config));
+
+        let vetoed = retired(&COUNT);
+        VETOED.store(vetoed.address(), Ordering::Relaxed);
+        local.retire(vetoed);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+
+        // the vetoed record is retained across all following scans
+        for _ in 0..3 {
+            local.retire(retired(&COUNT));
+        }
+        assert_eq!(COUNT.load(Ordering::Relaxed), 3);
+
+        // once the veto is lifted, the next scan reclaims the record
+        VETOED.store(0, Ordering::Relaxed);
+        local.retire(retired(&COUNT));
+        assert_eq!(COUNT.load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn take_retired() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let local = hp.build_local(None);
+        let records: Vec<_> = (0..6).map(|_| retired(&COUNT)).collect();
+
+        let protected = records[0].address();
+        let kept = records[1].address();
+        let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+        hazard.set_protected(NonNull::new(protected as *mut ()).unwrap());
+        records.into_iter().for_each(|retired| local.retire(retired));
+
+        // all records except the protected one and the one kept by the custom policy are reclaimed
+        let mut reclaimed = 0;
+        for record in hp.take_retired() {
+            if record.address() == kept {
+                record.requeue();
+                continue;
+            }
+
+            match record.reclaim() {
+                Ok(_) => reclaimed += 1,
+                Err(record) => assert_eq!(record.address(), protected),
+            }
+        }
+
+        assert_eq!(reclaimed, 4);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 4);
+        assert_eq!(hp.reclaim_progress().pending, 2);
+
+        // the requeued records remain in the global queue
+        hazard.set_free(Ordering::Relaxed);
+        assert_eq!(hp.reclaim_until_quiescent(1), 2);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 6);
+    }
+
+    #[test]
+    fn reclaim_progress() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let local = hp.build_local(None);
+        let records: Vec<_> = (0..4).map(|_| retired(&COUNT)).collect();
+
+        // one of the records remains protected
+        let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+        hazard.set_protected(NonNull::new(records[0].address() as *mut ()).unwrap());
+        records.into_iter().for_each(|retired| local.retire(retired));
+
+        let progress = hp.reclaim_progress();
+        assert_eq!(progress, ReclaimProgress { generation: 0, pending: 4, last_reclaimed: 0 });
+
+        // collecting the protected set for the chunk advances the generation
+        assert_eq!(hp.reclaim_chunk(&mut ProtectedSet::new(), 4), 3);
+        let progress = hp.reclaim_progress();
+        assert_eq!(progress, ReclaimProgress { generation: 1, pending: 1, last_reclaimed: 3 });
+
+        hazard.set_free(Ordering::Relaxed);
+        assert_eq!(hp.reclaim_until_quiescent(1), 1);
+        assert_eq!(hp.reclaim_progress().pending, 0);
+        assert_eq!(hp.reclaim_progress().last_reclaimed, 1);
+    }
+
+    #[test]
+    fn reclaim_pressure() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let local = hp.build_local(None);
+        for _ in 0..16 {
+            local.retire(retired(&COUNT));
+        }
+
+        let mut set = ProtectedSet::new();
+        assert_eq!(hp.reclaim_chunk(&mut set, 2), 2);
+
+        // the retiring thread signals a high backlog, which only raises the next chunk's limit
+        hp.raise_reclaim_pressure(4);
+        assert_eq!(hp.reclaim_chunk(&mut set, 2), 8);
+        assert_eq!(hp.reclaim_chunk(&mut set, 2), 2);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 12);
+    }
+
+    #[test]
+    fn weak_protection() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let local = hp.build_local(None);
+        let retired = retired(&COUNT);
+
+        let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+        hazard.set_weak_protected(NonNull::new(retired.address() as *mut ()).unwrap());
+        local.retire(retired);
+
+        // the weak protection is honored by regular reclamation
+        let mut set = ProtectedSet::new();
+        assert_eq!(hp.reclaim_chunk(&mut set, 4), 0);
+        hp.raise_reclaim_pressure(WEAK_DROP_PRESSURE - 1);
+        assert_eq!(hp.reclaim_chunk(&mut set, 4), 0);
+        assert!(!hazard.is_weak_invalidated(Ordering::Relaxed));
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+
+        // ...but dropped under high pressure, which the holder observes
+        hp.raise_reclaim_pressure(WEAK_DROP_PRESSURE);
+        assert_eq!(hp.reclaim_chunk(&mut set, 4), 1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+        assert!(hazard.is_weak_invalidated(Ordering::Relaxed));
+        hazard.set_free(Ordering::Relaxed);
+    }
+
+    #[test]
+    fn strong_protection_of_odd_address_under_pressure() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        unsafe fn free(ptr: NonNull<u8>) {
+            drop(Box::from_raw(ptr.as_ptr().sub(1) as *mut [u8; 2]));
+            COUNT.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let hp = Hp::<GlobalRetire>::default();
+        let local = hp.build_local(None);
+        let handle = LocalHandle::from_ref(&local);
+
+        // a record at an odd address, e.g. an align-1 field
+        let bytes = Box::into_raw(Box::new([0u8; 2])) as *mut u8;
+        let ptr = unsafe { NonNull::new_unchecked(bytes.add(1)) };
+        assert_eq!(ptr.as_ptr() as usize % 2, 1);
+
+        let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+        hazard.set_protected(ptr.cast());
+        unsafe { handle.retire_with(ptr, free) };
+
+        // a regular protection is never mistaken for a weak one and invalidated
+        let mut set = ProtectedSet::new();
+        hp.raise_reclaim_pressure(WEAK_DROP_PRESSURE);
+        assert_eq!(hp.reclaim_chunk(&mut set, 4), 0);
+        assert!(!hazard.is_weak_invalidated(Ordering::Relaxed));
+        let protected = hazard.protected(Ordering::Relaxed).protected();
+        assert_eq!(protected.map(|protected| protected.address()), Some(ptr.as_ptr() as usize));
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+
+        hazard.set_free(Ordering::Relaxed);
+        assert_eq!(hp.reclaim_chunk(&mut ProtectedSet::new(), 4), 1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn zero_sized_records() {
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        type Atomic<T> = conquer_reclaim::Atomic<T, Hp<LocalRetire>, U0>;
+        type Owned<T> = conquer_reclaim::Owned<T, Hp<LocalRetire>, U0>;
+
+        struct ZeroSized;
+        impl Drop for ZeroSized {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let handle = LocalHandle::from_ref(&local);
+
+        // all zero-sized records share the same dangling address, so protecting one of them must
+        // not prevent the reclamation of the others
+        let protected = Atomic::new(ZeroSized);
+        let mut guard = Guard::with_handle(handle.clone());
+        let _ = guard.protect(&protected, Ordering::Acquire);
+
+        let atomic = Atomic::new(ZeroSized);
+        let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+        let retired = Unlinked::into_retired(unlinked).into_raw();
+        assert!(retire::is_dangling(retired.address()));
+        local.retire(retired);
+
+        assert_eq!(handle.flush(), 1);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+        assert_eq!(handle.flush(), 0);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+
+        drop(guard);
+        drop(protected);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn reclaim_older_than() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let local = hp.build_local(None);
+        let retire = |records| {
+            for _ in 0..records {
+                local.retire(retired(&COUNT));
+            }
+        };
+
+        assert_eq!(hp.generation(), 0);
+        retire(2);
+        assert_eq!(hp.advance_generation(), 1);
+        retire(3);
+
+        // only the records retired in the first generation are reclaimed
+        assert_eq!(hp.reclaim_older_than(1), 2);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 2);
+        assert_eq!(hp.reclaim_older_than(1), 0);
+
+        let generation = hp.advance_generation();
+        assert_eq!(hp.reclaim_older_than(generation), 3);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn try_reclaim_one() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let retire = |atomic: Atomic<DropCount>| {
+            let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+            Unlinked::into_retired(unlinked).into_raw()
+        };
+
+        let protected = retire(Atomic::new(DropCount(&COUNT)));
+        let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+        hazard.set_protected(NonNull::new(protected.address() as *mut ()).unwrap());
+
+        // the protected record is handed back, while the unprotected one is reclaimed right away
+        let protected = unsafe { hp.try_reclaim_one(protected) }.unwrap_err();
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+        let unprotected = retire(Atomic::new(DropCount(&COUNT)));
+        assert!(unsafe { hp.try_reclaim_one(unprotected) }.is_ok());
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+
+        hazard.set_free(Ordering::Relaxed);
+        assert!(unsafe { hp.try_reclaim_one(protected) }.is_ok());
+        assert_eq!(COUNT.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn protected_snapshot() {
+        static VALUES: [u64; 3] = [1, 2, 3];
+
+        let hp = Hp::<LocalRetire>::default();
+        assert!(hp.protected_snapshot().is_empty());
+
+        let hazards: Vec<_> = VALUES
+            .iter()
+            .rev()
+            .map(|value| {
+                let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+                hazard.set_protected(NonNull::from(value).cast());
+                hazard
+            })
+            .collect();
+
+        let snapshot: Vec<_> =
+            hp.protected_snapshot().into_iter().map(|protected| protected.address()).collect();
+        let expected: Vec<_> = VALUES.iter().map(|value| value as *const u64 as usize).collect();
+        assert_eq!(snapshot, expected);
+
+        hazards[0].set_free(Ordering::Relaxed);
+        assert_eq!(hp.protected_snapshot().len(), 2);
+        for hazard in &hazards[1..] {
+            hazard.set_free(Ordering::Relaxed);
+        }
+
+        assert!(hp.protected_snapshot().is_empty());
+    }
+
+    #[test]
+    fn drop_leaks_protected() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let records: Vec<_> = (0..4).map(|_| retired(&COUNT)).collect();
+
+        // the hazard is (erroneously) never freed, e.g. like that of a forgotten guard
+        let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+        hazard.set_protected(NonNull::new(records[0].address() as *mut ()).unwrap());
+
+        match &hp.state.retire_state {
+            GlobalRetireState::GlobalStrategy(queue) => {
+                for retired in records {
+                    unsafe { queue.shard(0).retire(retired, 0, 0) };
+                }
+            }
+            _ => panic!("invalid retire state"),
+        }
+
+        // the protected record is leaked rather than freed
+        drop(hp);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn threads_spinning() {
+        use std::sync::mpsc;
+
+        let hp = Arc::new(Hp::<GlobalRetire>::default());
+        let local = hp.build_local(None);
+        local.set_spinning(true);
+        local.set_spinning(true);
+        assert_eq!(hp.threads_spinning(), 1);
+        local.set_spinning(false);
+        assert_eq!(hp.threads_spinning(), 0);
+
+        // a local dropped while spinning is no longer counted
+        local.set_spinning(true);
+        drop(local);
+        assert_eq!(hp.threads_spinning(), 0);
+
+        // a spinning thread is counted exactly once by all other threads until it stops spinning
+        let (spinning_tx, spinning_rx) = mpsc::channel();
+        let (done_tx, done_rx) = mpsc::channel::<()>();
+        let spinner = {
+            let hp = Arc::clone(&hp);
+            thread::spawn(move || {
+                let local = hp.build_local(None);
+                local.set_spinning(true);
+                local.set_spinning(true);
+                spinning_tx.send(()).unwrap();
+                done_rx.recv().unwrap();
+                local.set_spinning(false);
+            })
+        };
+
+        spinning_rx.recv().unwrap();
+        assert_eq!(hp.threads_spinning(), 1);
+        done_tx.send(()).unwrap();
+        spinner.join().unwrap();
+        assert_eq!(hp.threads_spinning(), 0);
+
+        // a writer swapping the pointer continuously forces the reader to retry, until the writer
+        // has observed the reader spinning in its protect loop
+        let atomic = Arc::new(Atomic::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+        let reader = {
+            let (hp, atomic, stop) = (Arc::clone(&hp), Arc::clone(&atomic), Arc::clone(&stop));
+            thread::spawn(move || {
+                let local = hp.build_local(None);
+                let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+                while !stop.load(Ordering::Relaxed) {
+                    let _ = guard.protect(&atomic, Ordering::Acquire);
+                    guard.release();
+                }
+            })
+        };
+
+        let local = hp.build_local(None);
+        let mut i = 0;
+        while hp.threads_spinning() != 1 {
+            i += 1;
+            let unlinked = atomic.swap(Owned::new(i), Ordering::AcqRel).unwrap();
+            local.retire(Unlinked::into_retired(unlinked).into_raw());
+        }
+
+        stop.store(true, Ordering::Relaxed);
+        reader.join().unwrap();
+        assert_eq!(hp.threads_spinning(), 0);
+    }
+
+    #[test]
+    fn reclaim_remaining() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let local = hp.build_local(None);
+        let hazards: Vec<_> = (0..4)
+            .map(|_| {
+                let retired = retired(&COUNT);
+                let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+                hazard.set_protected(NonNull::new(retired.address() as *mut ()).unwrap());
+                local.retire(retired);
+                hazard
+            })
+            .collect();
+
+        // the protected records survive the local's final reclamation attempt
+        drop(local);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+        assert_eq!(hp.reclaim_remaining(), 0);
+
+        hazards[0].set_free(Ordering::Relaxed);
+        assert_eq!(hp.reclaim_remaining(), 1);
+        for hazard in &hazards[1..] {
+            hazard.set_free(Ordering::Relaxed);
+        }
+
+        assert_eq!(hp.reclaim_remaining(), 3);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn reclaim_remaining_with_live_local() {
+        let hp = Hp::<LocalRetire>::default();
+        let _local = hp.build_local(None);
+        let _ = hp.reclaim_remaining();
+    }
+
+    #[test]
+    fn external_headers() {
+        type Atomic<T> = conquer_reclaim::Atomic<T, Hp<GlobalRetireExternal>, U0>;
+        type Owned<T> = conquer_reclaim::Owned<T, Hp<GlobalRetireExternal>, U0>;
+
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetireExternal>::default();
+        assert_eq!(hp.strategy_kind(), StrategyKind::Global);
+        let local = hp.build_local(None);
+
+        // the records carry no header, so the hazard protects the record's own address
+        let atomics: Vec<_> = (0..4).map(|_| Atomic::new(DropCount(&COUNT))).collect();
+        let mut guard = Guard::with_handle(LocalHandle::from_ref(&local));
+        let _ = guard.protect(&atomics[0], Ordering::Acquire);
+
+        for atomic in &atomics {
+            let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+            local.retire(Unlinked::into_retired(unlinked).into_raw());
+        }
+
+        assert_eq!(local.flush(), 3);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 3);
+
+        guard.release();
+        assert_eq!(local.flush(), 1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn overhead_bytes() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        const RECORDS: usize = 256;
+
+        let hp = Hp::<LocalRetire>::default();
+        let empty = hp.overhead_bytes();
+        hp.reserve_hazards(hp.hazard_capacity() + 1);
+        let reserved = hp.overhead_bytes();
+        assert!(reserved > empty);
+
+        // all hazards are acquired up front, so only the retire buffer can grow once retiring
+        let (retired, hazards): (Vec<_>, Vec<_>) = (0..RECORDS)
+            .map(|_| {
+                let retired = retired(&COUNT);
+                let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+                hazard.set_protected(NonNull::new(retired.address() as *mut ()).unwrap());
+                (retired, hazard)
+            })
+            .unzip();
+
+        let protected = hp.overhead_bytes();
+        let local = hp.build_local(None);
+        let built = hp.overhead_bytes();
+        assert!(built > protected);
+
+        // protected records can not be reclaimed, so the retire buffer has to grow
+        retired.into_iter().for_each(|retired| local.retire(retired));
+        let grown = hp.overhead_bytes();
+        assert!(grown > built);
+
+        // the buffer of the dropped local is accounted for in the abandoned queue instead
+        drop(local);
+        assert_eq!(hp.overhead_bytes(), grown);
+
+        hazards.iter().for_each(|hazard| hazard.set_free(Ordering::Relaxed));
+        assert_eq!(hp.reclaim_remaining(), RECORDS);
+        assert_eq!(COUNT.load(Ordering::Relaxed), RECORDS);
+    }
+
+    #[test]
+    fn visit_retired() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let local = hp.build_local(None);
+        for _ in 0..3 {
+            local.retire(retired(&COUNT));
+        }
+
+        let mut visited = Vec::new();
+        hp.visit_retired(|addr, generation| visited.push((addr, generation)));
+        assert_eq!(visited.len(), 3);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+
+        assert_eq!(local.flush(), 3);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 3);
+    }
+}
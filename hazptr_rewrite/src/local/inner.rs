@@ -1,14 +1,24 @@
+use core::cmp;
+#[cfg(feature = "std")]
+use core::fmt;
 use core::mem::ManuallyDrop;
-use core::ptr;
+#[cfg(feature = "track-retire-location")]
+use core::panic::Location;
+use core::ptr::{self, NonNull};
 use core::sync::atomic::Ordering;
 
 use arrayvec::{ArrayVec, CapacityError};
 use conquer_reclaim::RawRetired;
+use conquer_util::BackOff;
 
-use crate::config::{Config, Operation};
-use crate::global::GlobalRef;
+use crate::config::{Config, FenceMode, Operation};
+use crate::global::{Global, GlobalRef};
 use crate::hazard::{HazardPtr, ProtectStrategy, ProtectedPtr};
-use crate::retire::{GlobalRetireState, LocalRetireState};
+use crate::registry::PendingSlot;
+use crate::retire::channel::RetireBatch;
+use crate::retire::global_retire::Header;
+use crate::retire::local_retire::{ReclaimOnDrop, RetireNode};
+use crate::retire::{GlobalRetireState, LocalRetireState, Reclaimable};
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // RecycleError
@@ -27,20 +37,152 @@ impl From<CapacityError<&'_ HazardPtr>> for RecycleError {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// LocalReport
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The lifetime reclamation statistics of a single thread local state.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct LocalStats {
+    /// The number of records retired by the thread.
+    pub retired: u64,
+    /// The number of records reclaimed by the thread, including records
+    /// retired by other threads, e.g. adopted abandoned records.
+    pub reclaimed: u64,
+    /// The number of scans performed by the thread.
+    pub scans: u64,
+}
+
+/// A snapshot of the diagnostics of a single thread local state.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct LocalReport {
+    /// The thread's name, if one has been set.
+    pub name: Option<String>,
+    /// The number of hazard pointers currently cached by the thread.
+    pub cached_hazards: usize,
+    /// The thread's current operations count.
+    pub ops_count: u32,
+    /// The number of records retired (but not yet reclaimed) by the thread,
+    /// which is always 0 with the global retire strategy.
+    pub retired_records: usize,
+}
+
+/********** impl Display **************************************************************************/
+
+#[cfg(feature = "std")]
+impl fmt::Display for LocalReport {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "local \"{}\": ", name)?,
+            None => write!(f, "local <unnamed>: ")?,
+        }
+
+        write!(
+            f,
+            "{} cached hazards, ops count {}, {} retired records",
+            self.cached_hazards, self.ops_count, self.retired_records
+        )
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// ThrashingDetector
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The number of alternations between hazard cache misses and overflows
+/// between two scans, beyond which a thread is considered to be thrashing its
+/// hazard cache.
+const THRASHING_THRESHOLD: u32 = 64;
+
+/// An event indicating the hazard cache is too small for the thread's
+/// workload.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum CacheEvent {
+    /// A hazard had to be acquired from the global state, because the cache
+    /// was empty.
+    Miss,
+    /// A hazard had to be freed, because the cache was full.
+    Overflow,
+}
+
+/// Detects threads that repeatedly alternate between missing and overflowing
+/// their hazard cache, i.e. threads that keep more guards alive at once than
+/// fit into the cache and hence constantly acquire and free hazards.
+#[derive(Debug, Default)]
+struct ThrashingDetector {
+    last: Option<CacheEvent>,
+    alternations: u32,
+    detected: bool,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl ThrashingDetector {
+    /// Records a cache `event` and returns `true` if thrashing is detected for
+    /// the first time.
+    #[inline]
+    fn record(&mut self, event: CacheEvent) -> bool {
+        if self.detected {
+            return false;
+        }
+
+        if self.last.map_or(false, |last| last != event) {
+            self.alternations += 1;
+        }
+
+        self.last = Some(event);
+        self.detected = self.alternations >= THRASHING_THRESHOLD;
+        self.detected
+    }
+
+    /// Resets the alternations counted since the last scan.
+    #[inline]
+    fn reset(&mut self) {
+        self.last = None;
+        self.alternations = 0;
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // LocalInner
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-const HAZARD_CACHE: usize = 16;
+pub(crate) const HAZARD_CACHE: usize = 16;
+
+/// The number of forced scans, after which all records in a full bounded
+/// buffer are abandoned, because they are still protected or vetoed.
+const MAKE_ROOM_ATTEMPTS: u32 = 16;
 
 #[derive(Debug)]
 pub(super) struct LocalInner<'global> {
     config: Config,
     global: GlobalRef<'global>,
     state: ManuallyDrop<LocalRetireState>,
+    /// The records that must only be reclaimed by the retiring thread, which
+    /// are never abandoned, spilled or adopted, regardless of the strategy.
+    local_only: RetireNode,
     ops_count: u32,
+    /// The number of operations of any kind since the last scan.
+    total_ops: u32,
+    /// The number of scans performed by this thread, with which its own
+    /// retired records are tagged for enforcing the grace period (see
+    /// [`Config::reclaim_grace_scans`]).
+    scan_count: usize,
     hazard_cache: ArrayVec<[&'global HazardPtr; HAZARD_CACHE]>,
     scan_cache: Vec<ProtectedPtr>,
+    /// The slot for publishing the number of pending records (local retire
+    /// strategy only).
+    pending: Option<&'global PendingSlot>,
+    thrashing: ThrashingDetector,
+    /// Set while the thread is retrying to protect a value (see
+    /// [`Hp::threads_spinning`][crate::Hp::threads_spinning]).
+    spinning: bool,
+    stats: LocalStats,
+    /// The optional name for identifying the thread in diagnostics.
+    #[cfg(feature = "std")]
+    name: Option<String>,
 }
 
 /********** impl inherent *************************************************************************/
@@ -48,77 +190,540 @@ pub(super) struct LocalInner<'global> {
 impl<'global> LocalInner<'global> {
     #[inline]
     pub fn new(config: Config, global: GlobalRef<'global>) -> Self {
-        let state = ManuallyDrop::new(LocalRetireState::from(&global.as_ref().retire_state));
+        let state = ManuallyDrop::new(LocalRetireState::new(
+            &global.as_ref().retire_state,
+            config.numa_node,
+            config.retire_ring_capacity,
+        ));
+
+        global.as_ref().increment_live_locals();
+        let mut hazard_cache = ArrayVec::new();
+
+        // each thread reserves a contiguous block of hazards determined by its registration index,
+        // which are acquired from the cache in ascending order
+        #[cfg(feature = "deterministic-hazards")]
+        {
+            let start = global.as_ref().register_local() * HAZARD_CACHE;
+            for idx in (start..start + HAZARD_CACHE).rev() {
+                if let Some(hazard) = global.as_ref().try_reserve_hazard_at(idx) {
+                    hazard_cache.push(unsafe { &*(hazard as *const HazardPtr) });
+                }
+            }
+        }
+
+        let capacity = cmp::min(config.max_cached_hazards, HAZARD_CACHE);
+        let reserve =
+            cmp::min(config.initial_reserved_hazards, capacity.saturating_sub(hazard_cache.len()));
+        for _ in 0..reserve {
+            let hazard = global.as_ref().get_hazard(ProtectStrategy::ReserveOnly);
+            // hazards are allocated by the global state and hence live as long as it does
+            hazard_cache.push(unsafe { &*(hazard as *const HazardPtr) });
+        }
+
+        let pending = match &*state {
+            LocalRetireState::LocalStrategy(node) => {
+                // slots are allocated by the global state and hence live as long as it does
+                let slot =
+                    unsafe { &*(global.as_ref().acquire_pending_slot() as *const PendingSlot) };
+                slot.set_pending(node.len(), node.allocated_bytes());
+                Some(slot)
+            }
+            _ => None,
+        };
+
+        // a fixed scan cache is allocated once and never grows
+        let scan_cache = match config.scan_cache_fixed_capacity {
+            Some(capacity) => Vec::with_capacity(capacity),
+            None => Vec::new(),
+        };
+
         Self {
             config,
             global,
             state,
+            local_only: Default::default(),
             ops_count: Default::default(),
-            hazard_cache: Default::default(),
-            scan_cache: Default::default(),
+            total_ops: Default::default(),
+            scan_count: Default::default(),
+            hazard_cache,
+            scan_cache,
+            pending,
+            thrashing: Default::default(),
+            spinning: false,
+            stats: Default::default(),
+            #[cfg(feature = "std")]
+            name: None,
+        }
+    }
+
+    /// Sets the name used for identifying the thread in diagnostics.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn set_name(&mut self, name: &str) {
+        self.name = Some(name.to_owned());
+    }
+
+    /// Returns the thread local state's lifetime reclamation statistics.
+    #[inline]
+    pub fn stats(&self) -> LocalStats {
+        self.stats
+    }
+
+    /// Returns a snapshot of the thread local state's diagnostics.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn report(&self) -> LocalReport {
+        LocalReport {
+            name: self.name.clone(),
+            cached_hazards: self.hazard_cache.len(),
+            ops_count: self.ops_count,
+            retired_records: match &*self.state {
+                LocalRetireState::LocalStrategy(node) => node.len(),
+                LocalRetireState::GlobalStrategy(_) => 0,
+            },
+        }
+    }
+
+    #[inline]
+    pub fn fence_mode(&self) -> FenceMode {
+        self.global.as_ref().fence_mode()
+    }
+
+    /// Returns the global state.
+    #[inline]
+    pub fn global(&self) -> &Global {
+        self.global.as_ref()
+    }
+
+    /// Marks the thread as spinning in (or having left) a protect loop.
+    #[inline]
+    pub fn set_spinning(&mut self, spinning: bool) {
+        if self.spinning != spinning {
+            self.spinning = spinning;
+            self.global.as_ref().set_spinning(spinning);
         }
     }
 
+    /// Adopts all abandoned records (local retire strategy) and reclaims all
+    /// unprotected records right away, regardless of the current ops count,
+    /// and returns the number of reclaimed records.
+    #[inline]
+    pub fn flush(&mut self) -> usize {
+        self.ops_count = 0;
+        self.total_ops = 0;
+        self.scan()
+    }
+
+    /// Absorbs `other`, merging all of its pending retired records into the
+    /// own ones, while `other`'s hazard pointers are freed when it is dropped.
+    #[inline]
+    pub fn absorb(&mut self, mut other: LocalInner<'global>) {
+        assert!(
+            ptr::eq(self.global.as_ref(), other.global.as_ref()),
+            "absorbed local must be derived from the same global state"
+        );
+
+        // the absorbed records keep their age in terms of scans, but counted by this thread
+        if let LocalRetireState::LocalStrategy(absorbed) = &mut *other.state {
+            absorbed.abandon_scans(other.scan_count);
+            absorbed.adopt_scans(self.scan_count);
+            self.merge_records(absorbed.take_records());
+        }
+
+        // the caller of `retire_local_only` guarantees that `other` is absorbed on its own thread
+        other.local_only.abandon_scans(other.scan_count);
+        other.local_only.adopt_scans(self.scan_count);
+        self.local_only.merge(other.local_only.take_records());
+
+        // with the global retire strategy all records are already stored globally, so only the
+        // (now empty) `other` has to be dropped, which frees its hazard pointers
+    }
+
+    /// Merges all records in `batch` into the own retired records (local
+    /// retire strategy only).
+    #[inline]
+    pub fn retire_batch(&mut self, batch: RetireBatch) {
+        let mut node = batch.into_node();
+        let len = node.len();
+        // the records are considered to be retired only once the batch is received
+        node.set_generation(self.global.as_ref().generation());
+        node.set_scan(self.scan_count);
+        self.merge_records(node.take_records());
+        self.count_retired(len);
+        self.count_retire_ops(len as u32);
+    }
+
     #[inline]
     pub fn try_increase_ops_count(&mut self, op: Operation) {
-        if op == self.config.count_strategy {
-            self.ops_count += 1;
+        // a scan resets the total ops count
+        if self.count_total_ops(1) {
+            self.try_reclaim();
+        }
 
-            if self.ops_count == self.config.ops_count_threshold {
-                self.ops_count = 0;
-                self.try_reclaim();
+        if self.config.counts(op) {
+            self.increase_ops_count();
+        }
+    }
+
+    /// Counts a protect towards the [`total_ops_budget`][budget], protects
+    /// are not counted at all unless a budget is configured.
+    ///
+    /// [budget]: crate::Config::total_ops_budget
+    #[inline]
+    pub fn try_count_protect(&mut self) {
+        if self.config.total_ops_budget.is_some() {
+            self.count_protect();
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn count_protect(&mut self) {
+        if self.count_total_ops(1) {
+            self.try_reclaim();
+        }
+    }
+
+    /// Adds `n` operations to the total ops count and returns `true`, if the
+    /// total ops budget is exhausted.
+    #[inline]
+    fn count_total_ops(&mut self, n: u32) -> bool {
+        match self.config.total_ops_budget {
+            Some(budget) => {
+                self.total_ops += n;
+                self.total_ops >= budget
             }
+            None => false,
+        }
+    }
+
+    /// Counts `n` retired records towards the ops count (if retires are
+    /// counted) and the total ops budget, triggering at most a single
+    /// reclamation.
+    #[inline]
+    fn count_retire_ops(&mut self, n: u32) {
+        let mut reclaim = self.count_total_ops(n);
+        if self.config.is_count_retire() {
+            // a zero threshold disables the ops count trigger (see `ConfigBuilder::try_build`)
+            let threshold = self.config.ops_count_threshold;
+            let prev = self.ops_count;
+            self.ops_count += n;
+            reclaim |= threshold != 0 && prev < threshold && self.ops_count >= threshold;
+        }
+
+        // a scan resets both counts
+        if reclaim {
+            self.try_reclaim();
+        }
+    }
+
+    /// Increases the ops count for a cloned guard, if this is enabled in the
+    /// configuration.
+    #[inline]
+    pub fn try_increase_ops_count_on_clone(&mut self) {
+        if self.config.count_clones {
+            self.increase_ops_count();
         }
     }
 
+    #[cfg(test)]
     #[inline]
+    pub fn ops_count(&self) -> u32 {
+        self.ops_count
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "track-retire-location", track_caller)]
     pub fn retire(&mut self, retired: RawRetired) {
-        unsafe { self.retire_inner(retired) };
+        #[cfg(feature = "track-retire-location")]
+        crate::location::record(retired.address(), Location::caller());
 
-        if self.config.is_count_retire() {
-            self.ops_count += 1;
+        self.retire_recorded(retired);
+    }
+
+    /// Retires `retired` like [`retire`][LocalInner::retire], but records the
+    /// given `location` as its retire site, e.g. because the caller's location
+    /// can not be propagated through a closure.
+    #[cfg(feature = "track-retire-location")]
+    #[inline]
+    pub fn retire_at(&mut self, retired: RawRetired, location: &'static Location<'static>) {
+        crate::location::record(retired.address(), location);
+        self.retire_recorded(retired);
+    }
+
+    /// Retires `retired`, whose retire site has already been recorded (if
+    /// retire locations are tracked).
+    #[inline]
+    fn retire_recorded(&mut self, retired: RawRetired) {
+        unsafe { self.retire_inner(Reclaimable::Retired(retired)) };
+        self.count_retired(1);
+        self.count_retire_ops(1);
+    }
+
+    /// Retires the record at `ptr`, which is reclaimed by calling `destructor`
+    /// instead of dropping it.
+    ///
+    /// # Safety
+    ///
+    /// See [`LocalHandle::retire_with`][crate::LocalHandle::retire_with].
+    #[inline]
+    #[cfg_attr(feature = "track-retire-location", track_caller)]
+    pub unsafe fn retire_with<T>(&mut self, ptr: NonNull<T>, destructor: unsafe fn(NonNull<T>)) {
+        #[cfg(feature = "track-retire-location")]
+        crate::location::record(ptr.as_ptr() as usize, Location::caller());
+
+        self.retire_inner(Reclaimable::custom(ptr, destructor));
+        self.count_retired(1);
+        self.count_retire_ops(1);
+    }
+
+    /// Retires all records in `retired` at once, increasing the ops count by
+    /// their number but triggering at most a single reclamation.
+    ///
+    /// With the global retire strategy, the records are linked through their
+    /// headers first and then spliced into the queue with a single CAS.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure all records satisfy the usual invariants for
+    /// retiring and, with the global retire strategy (without external
+    /// headers), were derived from a `Retired<Hp<GlobalRetire>>`.
+    #[inline]
+    #[cfg_attr(feature = "track-retire-location", track_caller)]
+    pub unsafe fn retire_many(&mut self, retired: impl IntoIterator<Item = RawRetired>) {
+        #[cfg(feature = "track-retire-location")]
+        let location = Location::caller();
+
+        let generation = self.global.as_ref().generation();
+        let mut len = 0;
+        if let LocalRetireState::GlobalStrategy(shard) = *self.state {
+            let queue = match &self.global.as_ref().retire_state {
+                GlobalRetireState::GlobalStrategy(queue) => queue.shard(shard),
+                _ => unreachable!(),
+            };
+
+            let (mut head, mut tail) = (ptr::null_mut(), ptr::null_mut());
+            for retired in retired {
+                #[cfg(feature = "track-retire-location")]
+                crate::location::record(retired.address(), location);
+
+                let header = queue.header_for(retired);
+                match tail.is_null() {
+                    true => head = header,
+                    false => Header::link(tail, header),
+                }
+
+                tail = header;
+                len += 1;
+            }
+
+            if !head.is_null() {
+                queue.retire_chain(head, tail, generation, self.global.as_ref().scan_count());
+            }
+        } else {
+            let scan = self.scan_count;
+            for retired in retired {
+                #[cfg(feature = "track-retire-location")]
+                crate::location::record(retired.address(), location);
+
+                self.make_room();
+                self.local_node().retire(retired, generation, scan);
+                len += 1;
+            }
+
+            let node = self.local_node();
+            let (pending, bytes) = (node.len(), node.allocated_bytes());
+            self.pending.unwrap().set_pending(pending, bytes);
         }
+
+        self.count_retired(len);
+        self.count_retire_ops(len as u32);
+    }
+
+    /// Retires `retired` such that it is only ever reclaimed by this thread
+    /// local state, i.e. it is neither abandoned nor shared with other threads.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure `retired` satisfies the usual invariants for
+    /// retiring.
+    #[inline]
+    pub unsafe fn retire_local_only(&mut self, retired: RawRetired) {
+        self.local_only.retire(retired, self.global.as_ref().generation(), self.scan_count);
+        self.count_retired(1);
+        self.count_retire_ops(1);
     }
 
     #[inline]
     pub fn get_hazard(&mut self, strategy: ProtectStrategy) -> &HazardPtr {
         match self.hazard_cache.pop() {
             Some(hazard) => {
+                // a thread local state may have been moved to another thread since caching it
+                #[cfg(feature = "track-owner")]
+                crate::owner::record(hazard);
+
                 if let ProtectStrategy::Protect(protected) = strategy {
-                    hazard.set_protected(protected.into_inner(), Ordering::SeqCst);
+                    hazard.set_protected_with(protected.into_inner(), self.fence_mode());
                 }
 
                 hazard
             }
-            None => self.global.as_ref().get_hazard(strategy),
+            None => {
+                self.record_cache_event(CacheEvent::Miss);
+                self.global.as_ref().get_hazard(strategy)
+            }
         }
     }
 
     #[inline]
     pub fn try_recycle_hazard(&mut self, hazard: &'global HazardPtr) -> Result<(), RecycleError> {
-        // todo: use small vec, incorporate config?
-        self.hazard_cache.try_push(hazard)?;
-        hazard.set_thread_reserved(Ordering::Release);
+        // the hazard's state must only be changed after it has been successfully cached, otherwise
+        // the caller frees it
+        if self.hazard_cache.len() >= self.config.max_cached_hazards {
+            self.record_cache_event(CacheEvent::Overflow);
+            return Err(RecycleError);
+        }
 
+        if let Err(err) = self.hazard_cache.try_push(hazard) {
+            self.record_cache_event(CacheEvent::Overflow);
+            return Err(err.into());
+        }
+
+        hazard.set_thread_reserved(Ordering::Release);
         Ok(())
     }
 
+    /// Returns the number of hazard pointers currently reserved in the cache.
     #[inline]
-    fn try_reclaim(&mut self) {
+    pub fn cached_hazard_count(&self) -> usize {
+        self.hazard_cache.len()
+    }
+
+    /// Frees all cached hazard pointers beyond the first `keep` ones, so they
+    /// can be acquired by other threads again.
+    #[inline]
+    pub fn shrink_hazard_cache(&mut self, keep: usize) {
+        while self.hazard_cache.len() > keep {
+            let hazard = self.hazard_cache.pop().unwrap();
+            hazard.set_free(Ordering::Release);
+        }
+    }
+
+    /// Moves all cached hazard pointers to free ones closer to the head of the
+    /// global hazard list, if there are any, and returns the number of moved
+    /// hazard pointers.
+    ///
+    /// Cached hazard pointers are reserved exclusively by this thread and not
+    /// in use by any guard, so they can be exchanged at any time without
+    /// synchronizing with other threads beyond acquiring the new ones.
+    #[inline]
+    pub fn compact_hazard_cache(&mut self) -> usize {
+        let global = self.global.as_ref();
+        let mut moved = 0;
+        for cached in self.hazard_cache.iter_mut() {
+            if let Some(hazard) = global.try_reserve_hazard_before(*cached) {
+                // the new hazard is already reserved, so the old one can be freed right away
+                cached.set_free(Ordering::Release);
+                // hazards are allocated by the global state and hence live as long as it does
+                *cached = unsafe { &*(hazard as *const HazardPtr) };
+                moved += 1;
+            }
+        }
+
+        moved
+    }
+
+    /// Records a hazard cache miss or overflow and warns once, if the thread is
+    /// found to be thrashing its cache.
+    #[inline]
+    fn record_cache_event(&mut self, event: CacheEvent) {
+        if self.thrashing.record(event) {
+            #[cfg(feature = "log")]
+            log::warn!(
+                "thread local hazard cache (capacity {}) is thrashing, consider keeping fewer \
+                 guards alive at once or re-using guards",
+                cmp::min(self.config.max_cached_hazards, HAZARD_CACHE)
+            );
+        }
+    }
+
+    /// Reclaims all unprotected records right away, if there are any, and
+    /// returns the number of reclaimed records.
+    #[inline]
+    pub fn try_reclaim(&mut self) -> usize {
+        self.ops_count = 0;
+        self.total_ops = 0;
         if !self.has_retired_records() {
-            return;
+            return 0;
         }
 
-        // collect into scan_cache
-        self.global.as_ref().collect_protected_hazards(&mut self.scan_cache, Ordering::SeqCst);
+        self.scan()
+    }
+
+    #[inline]
+    fn scan(&mut self) -> usize {
+        // the thrashing rate is measured per interval between two scans
+        self.thrashing.reset();
+
+        // collect into scan_cache and sort it by the same key used for the binary search, which
+        // is required regardless of the retire strategy
+        // with a grace period, only records retired at least the configured number of scans ago
+        // can be reclaimed, which are counted by each thread for its own records
+        self.scan_count += 1;
+        let grace = self.config.reclaim_grace_scans;
+        let older_than = grace.map(|grace| self.scan_count.saturating_sub(grace));
 
-        unsafe { self.reclaim_all_unprotected() };
+        // a fixed scan cache is instead filled separately for each reclaimed node, possibly in
+        // multiple passes
+        let fixed = self.config.scan_cache_fixed_capacity;
+        if fixed.is_none() {
+            self.global.as_ref().collect_protected_hazards(&mut self.scan_cache, Ordering::SeqCst);
+            self.scan_cache.sort_unstable_by_key(|protected| protected.address());
+        }
+
+        // adopted records are merged before being reclaimed, so they are attributed to this thread
+        let mut reclaimed = unsafe { self.reclaim_all_unprotected(grace) };
+        let scan = (self.global.as_ref(), &mut self.scan_cache, fixed);
+        let can_reclaim = self.config.can_reclaim;
+        reclaimed +=
+            unsafe { reclaim_local_node(&mut self.local_only, older_than, scan, can_reclaim) };
+        self.stats.scans += 1;
+        self.stats.reclaimed += reclaimed as u64;
+        if let Some(interval) = self.config.compact_hazards_interval {
+            if self.stats.scans % interval == 0 {
+                self.compact_hazard_cache();
+            }
+        }
+
+        #[cfg(feature = "stats")]
+        self.global.as_ref().record_scan(reclaimed);
+        reclaimed
+    }
+
+    #[inline]
+    fn count_retired(&mut self, count: usize) {
+        self.stats.retired += count as u64;
+        #[cfg(feature = "stats")]
+        self.global.as_ref().record_retired(count);
+    }
+
+    #[inline]
+    fn increase_ops_count(&mut self) {
+        self.ops_count += 1;
+
+        if self.ops_count == self.config.ops_count_threshold {
+            self.try_reclaim();
+        }
     }
 
     #[inline]
     fn has_retired_records(&self) -> bool {
+        if !self.local_only.is_empty() {
+            return true;
+        }
+
         match &*self.state {
-            LocalRetireState::GlobalStrategy => match &self.global.as_ref().retire_state {
+            LocalRetireState::GlobalStrategy(_) => match &self.global.as_ref().retire_state {
                 GlobalRetireState::GlobalStrategy(queue) => !queue.is_empty(),
                 _ => unreachable!(),
             },
@@ -126,34 +731,167 @@ impl<'global> LocalInner<'global> {
         }
     }
 
+    /// Returns the own retired records (local retire strategy only).
+    #[inline]
+    fn local_node(&mut self) -> &mut RetireNode {
+        match &mut *self.state {
+            LocalRetireState::LocalStrategy(node) => node,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Merges `records` into the own retired records (local retire strategy
+    /// only), forcing scans as required for keeping a bounded buffer within
+    /// its capacity.
+    #[inline]
+    fn merge_records(&mut self, records: Vec<ReclaimOnDrop>) {
+        if self.local_node().is_bounded() {
+            for record in records {
+                self.make_room();
+                self.local_node().push(record);
+            }
+        } else {
+            self.local_node().merge(records);
+        }
+
+        let node = self.local_node();
+        let (len, bytes) = (node.len(), node.allocated_bytes());
+        self.pending.unwrap().set_pending(len, bytes);
+    }
+
+    /// Forces synchronous scans until a bounded buffer of retired records has
+    /// room for at least one more record, backing off between repeated scans.
+    ///
+    /// If the buffer is still full after [`MAKE_ROOM_ATTEMPTS`] scans, e.g.
+    /// because all buffered records are protected by the thread's own guards,
+    /// all of them are abandoned instead of blocking forever.
     #[inline]
-    unsafe fn retire_inner(&mut self, retired: RawRetired) {
+    fn make_room(&mut self) {
+        let is_full = |state: &LocalRetireState| match state {
+            LocalRetireState::LocalStrategy(node) => node.is_full(),
+            _ => false,
+        };
+
+        if !is_full(&self.state) {
+            return;
+        }
+
+        let backoff = BackOff::new();
+        for _ in 0..MAKE_ROOM_ATTEMPTS {
+            self.scan();
+            if !is_full(&self.state) {
+                return;
+            }
+
+            backoff.spin();
+        }
+
+        let mut abandoned = self.local_node().spill_all();
+        abandoned.abandon_scans(self.scan_count);
+        match &self.global.as_ref().retire_state {
+            GlobalRetireState::LocalStrategy(queue) => queue.push(abandoned),
+            _ => unreachable!(),
+        }
+
+        self.pending.unwrap().set_pending(0, self.local_node().allocated_bytes());
+    }
+
+    #[inline]
+    unsafe fn retire_inner(&mut self, retired: Reclaimable) {
+        self.make_room();
+        let generation = self.global.as_ref().generation();
         match &mut *self.state {
-            LocalRetireState::GlobalStrategy => match &self.global.as_ref().retire_state {
-                GlobalRetireState::GlobalStrategy(queue) => queue.retire(retired),
+            LocalRetireState::GlobalStrategy(shard) => match &self.global.as_ref().retire_state {
+                GlobalRetireState::GlobalStrategy(queue) => {
+                    let (shard, scan) = (queue.shard(*shard), self.global.as_ref().scan_count());
+                    match retired {
+                        Reclaimable::Retired(retired) => shard.retire(retired, generation, scan),
+                        custom => shard.retire_custom(custom, generation, scan),
+                    }
+                }
                 _ => unreachable!(),
             },
-            LocalRetireState::LocalStrategy(node) => node.retire(retired),
+            LocalRetireState::LocalStrategy(node) => {
+                node.retire_reclaimable(retired, generation, self.scan_count);
+                self.pending.unwrap().set_pending(node.len(), node.allocated_bytes());
+            }
         }
     }
 
+    /// Reclaims all unprotected records, which were retired at least `grace`
+    /// scans ago, if given, and returns the number of reclaimed records.
     #[inline]
-    unsafe fn reclaim_all_unprotected(&mut self) {
+    unsafe fn reclaim_all_unprotected(&mut self, grace: Option<usize>) -> usize {
         match &mut *self.state {
-            LocalRetireState::GlobalStrategy => match &self.global.as_ref().retire_state {
+            LocalRetireState::GlobalStrategy(shard) => match &self.global.as_ref().retire_state {
+                // the records in the global queue are tagged with a separate global scan count,
+                // which only scans with a grace period advance
                 GlobalRetireState::GlobalStrategy(queue) => {
-                    queue.reclaim_all_unprotected(&self.scan_cache)
+                    let global = self.global.as_ref();
+                    let older_than =
+                        grace.map(|grace| global.advance_scan_count().saturating_sub(grace));
+                    let can_reclaim = self.config.can_reclaim;
+                    match (self.config.scan_cache_fixed_capacity, older_than) {
+                        // a fixed scan cache is filled in as many passes as required for each
+                        // shard instead
+                        (Some(capacity), _) => queue.reclaim_in_passes_from(
+                            *shard,
+                            (global, &mut self.scan_cache, capacity),
+                            older_than,
+                            can_reclaim,
+                        ),
+                        (None, Some(scan)) => {
+                            queue.reclaim_before_scan(scan, &self.scan_cache, can_reclaim)
+                        }
+                        // the thread's own shard (i.e. its NUMA node's queue) is reclaimed first
+                        (None, None) => queue.reclaim_all_unprotected_from(
+                            *shard,
+                            &self.scan_cache,
+                            can_reclaim,
+                        ),
+                    }
                 }
                 _ => unreachable!(),
             },
             LocalRetireState::LocalStrategy(local) => match &self.global.as_ref().retire_state {
                 GlobalRetireState::LocalStrategy(queue) => {
-                    if let Some(node) = queue.take_all_and_merge() {
-                        local.merge(node.into_inner())
+                    let fixed = self.config.scan_cache_fixed_capacity;
+                    let (global, scan_cache) = (self.global.as_ref(), &mut self.scan_cache);
+                    let can_reclaim = self.config.can_reclaim;
+                    let scan_count = self.scan_count;
+                    let older_than = grace.map(|grace| scan_count.saturating_sub(grace));
+                    let mut reclaimed = 0;
+                    if let Some(mut node) = queue.take_all_and_merge() {
+                        node.adopt_scans(scan_count);
+                        if local.is_bounded() {
+                            // a bounded buffer must never grow, so abandoned records are reclaimed
+                            // in place and all remaining ones are abandoned again
+                            let scan = (global, &mut *scan_cache, fixed);
+                            reclaimed +=
+                                reclaim_local_node(&mut node, older_than, scan, can_reclaim);
+                            if !node.is_empty() {
+                                node.abandon_scans(scan_count);
+                                queue.push(node);
+                            }
+                        } else {
+                            local.merge(node.into_inner());
+                        }
                     }
 
-                    self.scan_cache.sort_unstable();
-                    local.reclaim_all_unprotected(&self.scan_cache)
+                    let scan = (global, scan_cache, fixed);
+                    reclaimed += reclaim_local_node(local, older_than, scan, can_reclaim);
+                    // an excess of remaining records is shared with other threads, which adopt them
+                    // on their next scan
+                    let threshold = self.config.local_spillover_threshold;
+                    if let Some(mut spilled) =
+                        threshold.and_then(|threshold| local.spill(threshold))
+                    {
+                        spilled.abandon_scans(scan_count);
+                        queue.push(spilled);
+                    }
+
+                    self.pending.unwrap().set_pending(local.len(), local.allocated_bytes());
+                    reclaimed
                 }
                 _ => unreachable!(),
             },
@@ -174,21 +912,499 @@ impl Drop for LocalInner<'_> {
         // execute a final reclamation attempt
         self.try_reclaim();
 
+        // records retired with `retire_local_only` must not be dropped by any other thread, so they
+        // can neither be abandoned nor leaked, if they may e.g. hold thread-affine resources
+        if !self.local_only.is_empty() {
+            abort_local_only(self.local_only.len());
+        }
+
+        // any remaining records are no longer attributed to this thread
+        if let Some(slot) = self.pending {
+            slot.release();
+        }
+
+        // a thread panicking while spinning must not be counted forever
+        self.set_spinning(false);
+        self.global.as_ref().decrement_live_locals();
+
         // with the local retire strategy, any remaining retired records must
         // be abandoned, i.e. stored globally so that other threads can adopt
         // and eventually reclaim them
         let state = unsafe { ptr::read(&*self.state) };
-        if let LocalRetireState::LocalStrategy(node) = state {
-            // if there are no remaining records the node can be de-allocated
-            // right away
+        if let LocalRetireState::LocalStrategy(mut node) = state {
+            let queue = match &self.global.as_ref().retire_state {
+                GlobalRetireState::LocalStrategy(queue) => queue,
+                _ => unreachable!(),
+            };
+
+            // if there are no remaining records the node is kept for re-use by
+            // a later thread or de-allocated right away
             if node.is_empty() {
+                queue.push_spare(node);
                 return;
             }
 
-            match &self.global.as_ref().retire_state {
-                GlobalRetireState::LocalStrategy(queue) => queue.push(node),
-                _ => unreachable!(),
+            node.abandon_scans(self.scan_count);
+            // the push (QUE:1) releases all writes to the node and its records made by this
+            // thread, which the adopting thread acquires (QUE:3), so no additional fence is required
+            queue.push(node);
+        }
+    }
+}
+
+/// Aborts the process, because a thread local state is dropped while some of
+/// the records only it may reclaim are still protected.
+///
+/// Without `std` there is no stable way to abort, so a panic is raised while
+/// already panicking instead, which always aborts (and with `panic = "abort"`
+/// the first panic already does).
+#[cold]
+#[inline(never)]
+fn abort_local_only(remaining: usize) -> ! {
+    #[cfg(feature = "std")]
+    {
+        eprintln!("thread local state dropped with {} unreclaimed local-only records", remaining);
+        std::process::abort();
+    }
+
+    #[cfg(not(feature = "std"))]
+    {
+        struct DoublePanic;
+        impl Drop for DoublePanic {
+            fn drop(&mut self) {
+                panic!("aborting due to unreclaimed local-only records");
+            }
+        }
+
+        let _double_panic = DoublePanic;
+        panic!("thread local state dropped with {} unreclaimed local-only records", remaining);
+    }
+}
+
+/// Reclaims all unprotected records in `node`, which were retired before the
+/// owning thread's scan `older_than`, if given, and returns the number of
+/// reclaimed records.
+#[inline]
+unsafe fn reclaim_node(
+    node: &mut RetireNode,
+    older_than: Option<usize>,
+    protected: &[ProtectedPtr],
+    can_reclaim: Option<fn(usize) -> bool>,
+) -> usize {
+    match older_than {
+        Some(scan) => node.reclaim_before_scan(scan, protected, can_reclaim),
+        None => node.reclaim_all_unprotected(protected, can_reclaim),
+    }
+}
+
+/// Reclaims all unprotected records in `node` like [`reclaim_node`], but if the
+/// scan cache has a fixed capacity, the protected pointers are collected and
+/// checked in as many passes as required.
+#[inline]
+unsafe fn reclaim_local_node(
+    node: &mut RetireNode,
+    older_than: Option<usize>,
+    (global, scan_cache, fixed): (&Global, &mut Vec<ProtectedPtr>, Option<usize>),
+    can_reclaim: Option<fn(usize) -> bool>,
+) -> usize {
+    let capacity = match fixed {
+        Some(capacity) => capacity,
+        None => return reclaim_node(node, older_than, scan_cache, can_reclaim),
+    };
+
+    // records protected in any pass are moved to the front and only the remaining ones can be
+    // reclaimed once all hazards have been visited
+    let (mut start, mut retained) = (0, 0);
+    loop {
+        let next = global.collect_protected_hazards_from(scan_cache, start, capacity);
+        scan_cache.sort_unstable_by_key(|protected| protected.address());
+        retained = node.retain_protected(retained, scan_cache);
+        match next {
+            Some(idx) => start = idx,
+            None => break,
+        }
+    }
+
+    node.reclaim_unretained(retained, older_than, can_reclaim)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "deterministic-hazards")]
+    use std::mem;
+
+    use std::ptr::NonNull;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    use crate::config::{Config, ConfigBuilder};
+    use crate::global::{Global, GlobalRef};
+    use crate::hazard::{HazardPtr, ProtectStrategy};
+    use crate::retire::local_retire::RetireNode;
+    use crate::retire::GlobalRetireState;
+    use crate::test_util::retired;
+    use crate::{GlobalRetire, Hp, LocalRetire};
+
+    use super::{LocalInner, HAZARD_CACHE};
+
+    // the deterministic block reservation fills the entire cache
+    #[cfg(not(feature = "deterministic-hazards"))]
+    #[test]
+    fn max_cached_hazards() {
+        let hp = Hp::<LocalRetire>::default();
+        let config = ConfigBuilder::new().max_cached_hazards(2).initial_reserved_hazards(4).build();
+        let mut local = LocalInner::new(config, GlobalRef::from_ref(&hp.state));
+        assert_eq!(local.cached_hazard_count(), 2);
+
+        let hazards: Vec<_> = (0..4)
+            .map(|_| local.get_hazard(ProtectStrategy::ReserveOnly) as *const HazardPtr)
+            .collect();
+        assert_eq!(local.cached_hazard_count(), 0);
+
+        // recycling fails once the configured cap is reached
+        local.try_recycle_hazard(unsafe { &*hazards[0] }).unwrap();
+        local.try_recycle_hazard(unsafe { &*hazards[1] }).unwrap();
+        assert!(local.try_recycle_hazard(unsafe { &*hazards[2] }).is_err());
+        assert_eq!(local.cached_hazard_count(), 2);
+
+        for &hazard in &hazards[2..] {
+            unsafe { &*hazard }.set_free(Ordering::Relaxed);
+        }
+    }
+
+    // the deterministic block reservation fills the entire cache
+    #[cfg(not(feature = "deterministic-hazards"))]
+    #[test]
+    fn shrink_hazard_cache() {
+        let hp = Hp::<LocalRetire>::default();
+        let mut local = LocalInner::new(Config::default(), GlobalRef::from_ref(&hp.state));
+
+        let hazards: Vec<_> = (0..8)
+            .map(|_| local.get_hazard(ProtectStrategy::ReserveOnly) as *const HazardPtr)
+            .collect();
+        for &hazard in &hazards {
+            local.try_recycle_hazard(unsafe { &*hazard }).unwrap();
+        }
+
+        assert_eq!(local.cached_hazard_count(), 8);
+        local.shrink_hazard_cache(2);
+        assert_eq!(local.cached_hazard_count(), 2);
+
+        // the freed hazards can be acquired by other threads again
+        let mut other = LocalInner::new(Config::default(), GlobalRef::from_ref(&hp.state));
+        let freed = &hazards[2..];
+        for _ in 0..6 {
+            let hazard = other.get_hazard(ProtectStrategy::ReserveOnly) as *const HazardPtr;
+            assert!(freed.contains(&hazard));
+            assert!(!local.hazard_cache.iter().any(|&cached| cached as *const _ == hazard));
+        }
+    }
+
+    #[cfg(not(feature = "deterministic-hazards"))]
+    #[test]
+    fn compact_hazard_cache() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let mut other = LocalInner::new(Config::default(), GlobalRef::from_ref(&hp.state));
+        let head: Vec<_> = (0..20)
+            .map(|_| other.get_hazard(ProtectStrategy::ReserveOnly) as *const HazardPtr)
+            .collect();
+
+        let config =
+            ConfigBuilder::new().initial_reserved_hazards(4).compact_hazards_interval(1).build();
+        let mut local = LocalInner::new(config, GlobalRef::from_ref(&hp.state));
+        let mut indices: Vec<_> = local
+            .hazard_cache
+            .iter()
+            .map(|&cached| hp.state.hazard_index(cached).unwrap())
+            .collect();
+        indices.sort_unstable();
+        assert_eq!(indices, [20, 21, 22, 23]);
+
+        // the hazards at the head of the list are freed again, which fragments the list
+        for &hazard in &head {
+            unsafe { &*hazard }.set_free(Ordering::Relaxed);
+        }
+
+        // the scan moves all cached hazards towards the head
+        local.retire(retired(&COUNT));
+        assert_eq!(local.try_reclaim(), 1);
+        let mut indices: Vec<_> = local
+            .hazard_cache
+            .iter()
+            .map(|&cached| hp.state.hazard_index(cached).unwrap())
+            .collect();
+        indices.sort_unstable();
+        assert_eq!(indices, [0, 1, 2, 3]);
+
+        // acquiring a hazard only has to search past the compacted reservations
+        let hazard = other.get_hazard(ProtectStrategy::ReserveOnly);
+        assert_eq!(hp.state.hazard_index(hazard), Some(4));
+        assert_eq!(local.compact_hazard_cache(), 0);
+    }
+    #[cfg(feature = "deterministic-hazards")]
+    #[test]
+    fn deterministic_hazards() {
+        use conquer_util::align::Aligned128;
+
+        let hp = Hp::<LocalRetire>::default();
+        let mut first = LocalInner::new(Config::default(), GlobalRef::from_ref(&hp.state));
+        let mut second = LocalInner::new(Config::default(), GlobalRef::from_ref(&hp.state));
+
+        assert_eq!(first.cached_hazard_count(), HAZARD_CACHE);
+        assert_eq!(second.cached_hazard_count(), HAZARD_CACHE);
+
+        // hazards are handed out in ascending order from each thread's block, regardless of the
+        // order in which the threads acquire them
+        let b = second.get_hazard(ProtectStrategy::ReserveOnly) as *const HazardPtr as usize;
+        let a0 = first.get_hazard(ProtectStrategy::ReserveOnly) as *const HazardPtr as usize;
+        let a1 = first.get_hazard(ProtectStrategy::ReserveOnly) as *const HazardPtr as usize;
+
+        let size = mem::size_of::<Aligned128<HazardPtr>>();
+        assert_eq!(a1 - a0, size);
+        assert_eq!(b - a0, HAZARD_CACHE * size);
+    }
+
+    // the deterministic block reservation fills the entire cache
+    #[cfg(not(feature = "deterministic-hazards"))]
+    #[test]
+    fn cache_thrashing() {
+        // one hazard more than fits into the cache is acquired and released in each round, which
+        // misses the cache once on acquiring and overflows it once on releasing
+        fn round(local: &mut LocalInner) {
+            let hazards: Vec<_> = (0..=HAZARD_CACHE)
+                .map(|_| local.get_hazard(ProtectStrategy::ReserveOnly) as *const HazardPtr)
+                .collect();
+            for hazard in hazards {
+                let hazard = unsafe { &*hazard };
+                if local.try_recycle_hazard(hazard).is_err() {
+                    hazard.set_free(Ordering::Release);
+                }
             }
         }
+
+        let hp = Hp::<LocalRetire>::default();
+        let mut local = LocalInner::new(Config::default(), GlobalRef::from_ref(&hp.state));
+
+        // the first round only counts the overflow as an alternation
+        for _ in 0..super::THRASHING_THRESHOLD / 2 {
+            round(&mut local);
+        }
+
+        assert_eq!(local.thrashing.alternations, super::THRASHING_THRESHOLD - 1);
+        assert!(!local.thrashing.detected);
+        round(&mut local);
+        assert!(local.thrashing.detected);
+    }
+
+    #[test]
+    fn reclaim_grace_scans() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<LocalRetire>::default();
+        let config = ConfigBuilder::new().reclaim_grace_scans(2).build();
+        let mut local = LocalInner::new(config, GlobalRef::from_ref(&hp.state));
+
+        local.retire(retired(&COUNT));
+
+        // the unprotected record survives the configured number of scans
+        for _ in 0..2 {
+            assert_eq!(local.flush(), 0);
+            assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+        }
+
+        assert_eq!(local.flush(), 1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+        assert_eq!(hp.generation(), 0);
+    }
+
+    #[test]
+    fn reclaim_grace_scans_per_thread() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp: &'static Hp<LocalRetire> = Box::leak(Box::new(Hp::default()));
+        let config = ConfigBuilder::new().reclaim_grace_scans(2).build();
+        let mut local = LocalInner::new(config, GlobalRef::from_ref(&hp.state));
+
+        local.retire(retired(&COUNT));
+
+        // neither the scans of other threads nor advancing the generation shorten the grace period
+        thread::spawn(move || {
+            let mut other = LocalInner::new(config, GlobalRef::from_ref(&hp.state));
+            for _ in 0..4 {
+                assert_eq!(other.flush(), 0);
+            }
+        })
+        .join()
+        .unwrap();
+        hp.advance_generation();
+
+        for _ in 0..2 {
+            assert_eq!(local.flush(), 0);
+            assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+        }
+
+        assert_eq!(local.flush(), 1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn reclaim_sorted_by_address() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let mut local = LocalInner::new(Config::default(), GlobalRef::from_ref(&hp.state));
+
+        let records: Vec<_> = (0..16).map(|_| retired(&COUNT)).collect();
+
+        // protect every other record in reverse order, so the protected pointers are collected in
+        // an order that differs from their address order
+        let hazards: Vec<_> = records
+            .iter()
+            .rev()
+            .step_by(2)
+            .map(|retired| {
+                let protected = NonNull::new(retired.address() as *mut ()).unwrap();
+                let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+                hazard.set_protected(protected);
+                hazard
+            })
+            .collect();
+
+        records.into_iter().for_each(|retired| local.retire(retired));
+        local.try_reclaim();
+        assert_eq!(COUNT.load(Ordering::Relaxed), 8);
+
+        hazards.iter().for_each(|hazard| hazard.set_free(Ordering::Relaxed));
+        local.try_reclaim();
+        assert_eq!(COUNT.load(Ordering::Relaxed), 16);
+    }
+
+    #[test]
+    fn scan_cache_fixed_capacity() {
+        fn flush_in_passes(global: &Global, count: &'static AtomicUsize) {
+            let config = ConfigBuilder::new().scan_cache_fixed_capacity(2).build();
+            let mut local = LocalInner::new(config, GlobalRef::from_ref(global));
+            assert_eq!(local.scan_cache.capacity(), 2);
+
+            let records: Vec<_> = (0..8).map(|_| retired(count)).collect();
+
+            // more pointers are protected than fit into the cache, which requires three passes
+            let hazards: Vec<_> = records
+                .iter()
+                .take(5)
+                .map(|retired| {
+                    let protected = NonNull::new(retired.address() as *mut ()).unwrap();
+                    let hazard = global.get_hazard(ProtectStrategy::ReserveOnly);
+                    hazard.set_protected(protected);
+                    hazard
+                })
+                .collect();
+
+            records.into_iter().for_each(|retired| local.retire(retired));
+            assert_eq!(local.flush(), 3);
+            assert_eq!(local.scan_cache.capacity(), 2);
+
+            hazards.iter().for_each(|hazard| hazard.set_free(Ordering::Relaxed));
+            assert_eq!(local.flush(), 5);
+            assert_eq!(local.scan_cache.capacity(), 2);
+        }
+
+        static GLOBAL_COUNT: AtomicUsize = AtomicUsize::new(0);
+        static LOCAL_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        flush_in_passes(&hp.state, &GLOBAL_COUNT);
+        assert_eq!(GLOBAL_COUNT.load(Ordering::Relaxed), 8);
+
+        let hp = Hp::<LocalRetire>::default();
+        flush_in_passes(&hp.state, &LOCAL_COUNT);
+        assert_eq!(LOCAL_COUNT.load(Ordering::Relaxed), 8);
+    }
+
+    #[test]
+    fn retire_ring_capacity() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<LocalRetire>::default();
+        let config = ConfigBuilder::new().retire_ring_capacity(4).build();
+        let mut local = LocalInner::new(config, GlobalRef::from_ref(&hp.state));
+
+        let mut records: Vec<_> = (0..5).map(|_| retired(&COUNT)).collect();
+
+        // filling the buffer does not reach the ops count threshold
+        let last = records.pop().unwrap();
+        records.into_iter().for_each(|retired| local.retire(retired));
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+        assert!(local.local_node().is_full());
+
+        // retiring into the full buffer forces a scan first
+        local.retire(last);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 4);
+        assert_eq!(local.local_node().len(), 1);
+    }
+
+    #[test]
+    fn retire_ring_capacity_all_protected() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<LocalRetire>::default();
+        let config = ConfigBuilder::new().retire_ring_capacity(4).build();
+        let mut local = LocalInner::new(config, GlobalRef::from_ref(&hp.state));
+
+        let mut hazards = Vec::new();
+        for _ in 0..5 {
+            let retired = retired(&COUNT);
+            let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+            hazard.set_protected(NonNull::new(retired.address() as *mut ()).unwrap());
+            hazards.push(hazard);
+            local.retire(retired);
+        }
+
+        // retiring into a full buffer of protected records abandons them instead of blocking
+        assert_eq!(local.local_node().len(), 1);
+        assert_eq!(
+            local.local_node().allocated_bytes(),
+            RetireNode::with_ring_capacity(4).allocated_bytes()
+        );
+        match &hp.state.retire_state {
+            GlobalRetireState::LocalStrategy(abandoned) => assert!(!abandoned.is_empty()),
+            _ => unreachable!(),
+        }
+
+        hazards.iter().for_each(|hazard| hazard.set_free(Ordering::Relaxed));
+        drop(local);
+        assert_eq!(hp.reclaim_remaining(), 4);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 5);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn report() {
+        let hp = Hp::<LocalRetire>::default();
+        let mut local = LocalInner::new(Config::default(), GlobalRef::from_ref(&hp.state));
+        assert_eq!(local.report().name, None);
+
+        local.set_name("worker-1");
+        let report = local.report();
+        assert_eq!(report.name.as_deref(), Some("worker-1"));
+        assert!(report.to_string().starts_with("local \"worker-1\": "));
+    }
+
+    // the deterministic block reservation fills the entire cache
+    #[cfg(not(feature = "deterministic-hazards"))]
+    #[test]
+    fn initial_reserved_hazards() {
+        let hp = Hp::<LocalRetire>::default();
+
+        let config = ConfigBuilder::new().initial_reserved_hazards(4).build();
+        let local = hp.build_local(Some(config));
+        assert_eq!(local.cached_hazard_count(), 4);
+
+        // the number of pre-reserved hazards is bounded by the cache capacity
+        let config = ConfigBuilder::new().initial_reserved_hazards(HAZARD_CACHE + 1).build();
+        let local = hp.build_local(Some(config));
+        assert_eq!(local.cached_hazard_count(), HAZARD_CACHE);
     }
 }
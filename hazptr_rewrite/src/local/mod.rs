@@ -3,6 +3,10 @@ mod inner;
 use core::cell::UnsafeCell;
 use core::convert::AsRef;
 use core::marker::PhantomData;
+#[cfg(feature = "track-retire-location")]
+use core::panic::Location;
+use core::ptr::NonNull;
+use core::sync::atomic::Ordering;
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "std")] {
@@ -13,17 +17,27 @@ cfg_if::cfg_if! {
     }
 }
 
-use conquer_reclaim::{BuildReclaimRef, RawRetired, Reclaim, ReclaimRef, Retired};
+use conquer_reclaim::typenum::Unsigned;
+use conquer_reclaim::{
+    Atomic, BuildReclaimRef, Protect, RawRetired, Reclaim, ReclaimRef, Retired, Unlinked,
+};
 
-use crate::config::{Config, Operation};
-use crate::global::GlobalRef;
-use crate::guard::Guard;
+use crate::config::{Config, FenceMode, Operation};
+use crate::global::{Global, GlobalRef};
+use crate::guard::{Guard, PinnedRef};
 use crate::hazard::{HazardPtr, ProtectStrategy};
-use crate::retire::RetireStrategy;
+use crate::retire::channel::RetireBatch;
+use crate::retire::{LocalRetire, RetireStrategy};
 use crate::Hp;
 
+#[cfg(feature = "std")]
+pub use self::inner::LocalReport;
+pub use self::inner::LocalStats;
+
 use self::inner::{LocalInner, RecycleError};
 
+pub(crate) use self::inner::HAZARD_CACHE;
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // LocalHandle
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -67,6 +81,231 @@ impl<'local, 'global, R> LocalHandle<'local, 'global, R> {
     pub fn from_ref(local: &'local Local<'global>) -> Self {
         Self { inner: Ref::Ref(local), _marker: PhantomData }
     }
+
+    /// Returns the number of hazard pointers currently cached (reserved) by the
+    /// thread local state.
+    #[inline]
+    pub fn cached_hazard_count(&self) -> usize {
+        self.inner.as_ref().cached_hazard_count()
+    }
+
+    /// Sets the name used for identifying the thread in diagnostics, e.g. the
+    /// [`LocalReport`].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn set_name(&self, name: &str) {
+        self.inner.as_ref().set_name(name)
+    }
+
+    /// Returns a snapshot of the diagnostics of the thread local state.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn report(&self) -> LocalReport {
+        self.inner.as_ref().report()
+    }
+
+    /// Returns the lifetime reclamation statistics of the thread local state,
+    /// e.g. for computing its reclamation efficiency when tuning the
+    /// [`ops_count_threshold`][crate::Config::ops_count_threshold].
+    #[inline]
+    pub fn stats(&self) -> LocalStats {
+        self.inner.as_ref().stats()
+    }
+
+    /// Absorbs the (departing) thread local state `other` into the thread local
+    /// state referenced by `self`.
+    ///
+    /// All of `other`'s pending retired records are merged into `self`'s and
+    /// its hazard pointers are freed, which is cheaper than abandoning the
+    /// records and adopting them again, when e.g. a parent thread outlives its
+    /// child threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` was not built from the same [`Hp`] instance.
+    #[inline]
+    pub fn absorb(&self, other: Local<'global>) {
+        self.inner.as_ref().absorb(other)
+    }
+
+    /// Frees all cached hazard pointers beyond the first `keep` ones, which
+    /// makes them available for acquisition by other threads.
+    ///
+    /// This is useful for long-lived threads that have (temporarily) required
+    /// a large number of hazard pointers.
+    #[inline]
+    pub fn shrink_hazard_cache(&self, keep: usize) {
+        self.inner.as_ref().shrink_hazard_cache(keep)
+    }
+
+    /// Runs a full reclamation pass right away, regardless of the current ops
+    /// count, and returns the number of reclaimed records.
+    ///
+    /// With the local retire strategy, all records abandoned by exited threads
+    /// are adopted first, so this is the entry point for manual maintenance,
+    /// e.g. by a thread that rarely retires records itself.
+    #[inline]
+    pub fn flush(&self) -> usize {
+        self.inner.as_ref().flush()
+    }
+
+    /// Reclaims all unprotected retired records right away and returns the
+    /// number of reclaimed records, e.g. at a known quiescent point in a
+    /// latency-sensitive workload.
+    ///
+    /// Like the implicit reclamation triggered by the ops count, this resets
+    /// the ops count, but unlike [`flush`][LocalHandle::flush], it does
+    /// nothing and returns 0, if there are no retired records.
+    #[inline]
+    pub fn try_reclaim(&self) -> usize {
+        self.inner.as_ref().try_reclaim()
+    }
+}
+
+impl<'local, 'global, R: Reclaim> LocalHandle<'local, 'global, R> {
+    /// Creates a fresh guard and protects the value loaded from `src` with it
+    /// in a single call, which is convenient for one-shot reads.
+    ///
+    /// Returns `None`, if `src` is null, in which case the guard is dropped
+    /// right away again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::atomic::Ordering;
+    ///
+    /// use conquer_reclaim::typenum::U0;
+    /// use hazptr_rewrite::{Hp, LocalHandle, LocalRetire};
+    ///
+    /// type Atomic<T> = conquer_reclaim::Atomic<T, Hp<LocalRetire>, U0>;
+    ///
+    /// let hp = Hp::<LocalRetire>::default();
+    /// let global = Atomic::new(1);
+    ///
+    /// let local = hp.build_local(None);
+    /// let handle = LocalHandle::from_ref(&local);
+    ///
+    /// let value = handle.load(&global, Ordering::Acquire).unwrap();
+    /// assert_eq!(*value, 1);
+    /// ```
+    #[inline]
+    pub fn load<T, N: Unsigned + 'static>(
+        &self,
+        src: &Atomic<T, R, N>,
+        order: Ordering,
+    ) -> Option<PinnedRef<'local, 'global, T, R, N>> {
+        Guard::with_handle(self.clone()).pin(src, order)
+    }
+}
+
+impl<S: RetireStrategy> LocalHandle<'_, '_, Hp<S>>
+where
+    Hp<S>: Reclaim,
+{
+    /// Retires `unlinked` while keeping it protected by `guard` until the
+    /// retirement has been recorded, and releases `guard` afterwards.
+    ///
+    /// A thread retiring a record it may still be reading (e.g. a node it has
+    /// just unlinked after traversing it) must not dereference the record
+    /// anymore once it is retired, unless it is still protected, since any
+    /// scan (including one triggered by the retirement itself) may reclaim
+    /// it.
+    /// With this method, the record remains protected during the retirement
+    /// and any scan it may trigger and the mutable borrow of `guard` ensures
+    /// that no reference protected by it outlives the call, so the thread can
+    /// not hold a stale reference to the record afterwards.
+    ///
+    /// # Safety
+    ///
+    /// The same invariants as for [`RetireVia::retire_via`] apply.
+    #[inline]
+    #[cfg_attr(feature = "track-retire-location", track_caller)]
+    pub unsafe fn retire_guarded<T: 'static, N: Unsigned + 'static>(
+        &self,
+        unlinked: Unlinked<T, Hp<S>, N>,
+        guard: &mut Guard<'_, '_, Hp<S>>,
+    ) {
+        let retired = Unlinked::into_retired(unlinked).into_raw();
+        // the record can not be reclaimed before it is retired, so it does not matter whether the
+        // guard already protects it or only does so from now on
+        guard.set_protected(NonNull::new_unchecked(retired.address() as *mut ()));
+        self.as_ref().retire(retired);
+        guard.release();
+    }
+
+    /// Retires all records in `retired` at once, e.g. after unlinking an entire
+    /// chain of nodes.
+    ///
+    /// The ops count is increased by the number of records, but at most a
+    /// single reclamation is triggered.
+    /// With the global retire strategy, all records are spliced into the
+    /// global queue with a single CAS instead of one for each record.
+    ///
+    /// # Safety
+    ///
+    /// The same invariants as for [`RetireVia::retire_via`] apply for each
+    /// record.
+    #[inline]
+    #[cfg_attr(feature = "track-retire-location", track_caller)]
+    pub unsafe fn retire_many(&self, retired: impl IntoIterator<Item = Retired<Hp<S>>>) {
+        self.as_ref().retire_many(retired.into_iter().map(Retired::into_raw));
+    }
+
+    /// Retires `unlinked` such that it is only ever reclaimed (i.e. dropped) by
+    /// the thread owning this handle's thread local state, e.g. because it
+    /// holds thread-affine resources or is not `Send`.
+    ///
+    /// Such records are never abandoned to or adopted by other threads.
+    /// If any of them are still protected when the thread local state is
+    /// dropped after its final reclamation attempt, the process is aborted, so
+    /// the thread must make sure all of them are reclaimed before it exits,
+    /// e.g. by calling [`flush`][LocalHandle::flush].
+    ///
+    /// # Safety
+    ///
+    /// The same invariants as for [`RetireVia::retire_via`] apply.
+    /// In addition, the thread local state must not be moved to or absorbed
+    /// by another thread, while it holds any local-only records.
+    #[inline]
+    pub unsafe fn retire_local_only<T: 'static, N: Unsigned + 'static>(
+        &self,
+        unlinked: Unlinked<T, Hp<S>, N>,
+    ) {
+        let retired = Unlinked::into_retired(unlinked).into_raw();
+        self.as_ref().retire_local_only(retired);
+    }
+
+    /// Retires the record at `ptr`, which is reclaimed by calling `destructor`
+    /// with `ptr` instead of dropping it, e.g. because it has been allocated
+    /// with a custom allocator or requires additional cleanup like returning
+    /// a buffer to a pool.
+    ///
+    /// With the global retire strategy, a header is allocated separately for
+    /// the record, so it does not need to be allocated with one.
+    ///
+    /// # Safety
+    ///
+    /// The same invariants as for [`RetireVia::retire_via`] apply, i.e. the
+    /// record must no longer be reachable by other threads (except through
+    /// already protected pointers) and must not be retired more than once.
+    /// In addition, `destructor` may be called by any thread, so it must be
+    /// safe to call it with `ptr` from any thread at any later point.
+    #[inline]
+    #[cfg_attr(feature = "track-retire-location", track_caller)]
+    pub unsafe fn retire_with<T>(&self, ptr: NonNull<T>, destructor: unsafe fn(NonNull<T>)) {
+        self.as_ref().retire_with(ptr, destructor);
+    }
+}
+
+impl LocalHandle<'_, '_, Hp<LocalRetire>> {
+    /// Retires all records in `batch` at once, e.g. after receiving it from a
+    /// worker thread through a [`RetireChannel`][crate::RetireChannel].
+    ///
+    /// Each record counts as a separate retire operation.
+    #[inline]
+    pub fn retire_batch(&self, batch: RetireBatch) {
+        self.inner.as_ref().retire_batch(batch)
+    }
 }
 
 /*********** impl AsRef ***************************************************************************/
@@ -115,16 +354,49 @@ where
     }
 
     #[inline]
+    #[cfg_attr(feature = "track-retire-location", track_caller)]
     unsafe fn retire(self, retired: Retired<Self::Reclaimer>) {
         self.inner.as_ref().retire(retired.into_raw())
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// RetireVia (trait)
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An extension trait for retiring [`Unlinked`] records through an explicitly
+/// passed [`LocalHandle`], for [`Hp`] instances without an ambient thread local
+/// state.
+pub trait RetireVia<S> {
+    /// Retires `self` through the thread local state of `local`.
+    ///
+    /// # Safety
+    ///
+    /// The same invariants as for [`ReclaimRef::retire`] apply, i.e. the record
+    /// must no longer be reachable by other threads (except through already
+    /// protected pointers) and must not be retired more than once.
+    unsafe fn retire_via(self, local: &LocalHandle<'_, '_, Hp<S>>);
+}
+
+/********** impl RetireVia ************************************************************************/
+
+impl<T: 'static, S: RetireStrategy, N: Unsigned + 'static> RetireVia<S> for Unlinked<T, Hp<S>, N>
+where
+    Hp<S>: Reclaim,
+{
+    #[inline]
+    #[cfg_attr(feature = "track-retire-location", track_caller)]
+    unsafe fn retire_via(self, local: &LocalHandle<'_, '_, Hp<S>>) {
+        local.as_ref().retire(Unlinked::into_retired(self).into_raw());
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // Local
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 #[derive(Debug)]
+#[must_use = "a thread local state reclaims or abandons its retired records, if it is dropped"]
 pub struct Local<'global> {
     inner: UnsafeCell<LocalInner<'global>>,
 }
@@ -137,16 +409,63 @@ impl<'global> Local<'global> {
         Self { inner: UnsafeCell::new(LocalInner::new(config, global)) }
     }
 
+    /// Returns the (global) fence mode.
+    #[inline]
+    pub(crate) fn fence_mode(&self) -> FenceMode {
+        unsafe { (*self.inner.get()).fence_mode() }
+    }
+
+    /// Returns a pointer to the global state, e.g. for checking whether two
+    /// thread local states belong to the same [`Hp`].
+    #[inline]
+    pub(crate) fn global_ptr(&self) -> *const Global {
+        unsafe { (*self.inner.get()).global() }
+    }
+
+    #[inline]
+    pub(crate) fn set_spinning(&self, spinning: bool) {
+        unsafe { (*self.inner.get()).set_spinning(spinning) }
+    }
+
     #[inline]
     pub(crate) fn try_increase_ops_count(&self, op: Operation) {
         unsafe { (*self.inner.get()).try_increase_ops_count(op) }
     }
 
     #[inline]
+    pub(crate) fn try_count_protect(&self) {
+        unsafe { (*self.inner.get()).try_count_protect() }
+    }
+
+    #[inline]
+    pub(crate) fn try_increase_ops_count_on_clone(&self) {
+        unsafe { (*self.inner.get()).try_increase_ops_count_on_clone() }
+    }
+
+    #[cfg(test)]
+    #[inline]
+    pub(crate) fn ops_count(&self) -> u32 {
+        unsafe { (*self.inner.get()).ops_count() }
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "track-retire-location", track_caller)]
     pub(crate) fn retire(&self, retired: RawRetired) {
         unsafe { (*self.inner.get()).retire(retired) };
     }
 
+    #[cfg(feature = "track-retire-location")]
+    #[inline]
+    pub(crate) fn retire_at(&self, retired: RawRetired, location: &'static Location<'static>) {
+        unsafe { (*self.inner.get()).retire_at(retired, location) };
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "track-retire-location", track_caller)]
+    pub(crate) unsafe fn retire_with<T>(&self, ptr: NonNull<T>, destructor: unsafe fn(NonNull<T>)) {
+        (*self.inner.get()).retire_with(ptr, destructor);
+    }
+
     #[inline]
     pub(crate) fn get_hazard(&self, strategy: ProtectStrategy) -> &HazardPtr {
         unsafe { (*self.inner.get()).get_hazard(strategy) }
@@ -159,6 +478,96 @@ impl<'global> Local<'global> {
     ) -> Result<(), RecycleError> {
         unsafe { (*self.inner.get()).try_recycle_hazard(hazard) }
     }
+
+    #[inline]
+    pub(crate) fn cached_hazard_count(&self) -> usize {
+        unsafe { (*self.inner.get()).cached_hazard_count() }
+    }
+
+    #[inline]
+    pub(crate) fn shrink_hazard_cache(&self, keep: usize) {
+        unsafe { (*self.inner.get()).shrink_hazard_cache(keep) }
+    }
+
+    #[inline]
+    pub(crate) fn flush(&self) -> usize {
+        unsafe { (*self.inner.get()).flush() }
+    }
+
+    #[inline]
+    pub(crate) fn try_reclaim(&self) -> usize {
+        unsafe { (*self.inner.get()).try_reclaim() }
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "track-retire-location", track_caller)]
+    pub(crate) unsafe fn retire_many(&self, retired: impl IntoIterator<Item = RawRetired>) {
+        (*self.inner.get()).retire_many(retired)
+    }
+
+    #[inline]
+    pub(crate) unsafe fn retire_local_only(&self, retired: RawRetired) {
+        (*self.inner.get()).retire_local_only(retired)
+    }
+
+    #[inline]
+    pub(crate) fn retire_batch(&self, batch: RetireBatch) {
+        unsafe { (*self.inner.get()).retire_batch(batch) }
+    }
+
+    #[inline]
+    pub(crate) fn absorb(&self, other: Local<'global>) {
+        unsafe { (*self.inner.get()).absorb(other.inner.into_inner()) }
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn set_name(&self, name: &str) {
+        unsafe { (*self.inner.get()).set_name(name) }
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn report(&self) -> LocalReport {
+        unsafe { (*self.inner.get()).report() }
+    }
+
+    #[inline]
+    pub(crate) fn stats(&self) -> LocalStats {
+        unsafe { (*self.inner.get()).stats() }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// PendingLocal
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A [`Local`] that has not yet been built, which can be sent to the thread
+/// that will own it (see [`Hp::build_locals`]).
+///
+/// A [`Local`] itself must never change threads, since it may hold retired
+/// records of non-`Send` types or local-only records, which must be reclaimed
+/// by the retiring thread.
+#[derive(Debug)]
+#[must_use = "a pending thread local state does nothing unless it is built"]
+pub struct PendingLocal<'global> {
+    config: Config,
+    global: &'global Global,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<'global> PendingLocal<'global> {
+    #[inline]
+    pub(crate) fn new(config: Config, global: &'global Global) -> Self {
+        Self { config, global }
+    }
+
+    /// Builds the [`Local`] on the calling thread, which becomes its owner.
+    #[inline]
+    pub fn into_local(self) -> Local<'global> {
+        Local::new(self.config, GlobalRef::from_ref(self.global))
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -197,3 +606,470 @@ impl<'local, 'global> Clone for Ref<'local, 'global> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::ptr::NonNull;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc;
+    use std::thread;
+
+    use conquer_reclaim::typenum::U0;
+    use conquer_reclaim::{Protect, Reclaim, Unlinked};
+
+    use crate::guard::Guard;
+    use crate::hazard::ProtectStrategy;
+    use crate::retire::{GlobalRetireState, RetireStrategy};
+    use crate::test_util::{retired, DropCount};
+    use crate::{
+        ConfigBuilder, CountStrategy, GlobalRetire, Hp, LocalHandle, LocalRetire, LocalStats,
+    };
+
+    use super::RetireVia;
+
+    type Atomic<T> = conquer_reclaim::Atomic<T, Hp<LocalRetire>, U0>;
+    type Owned<T> = conquer_reclaim::Owned<T, Hp<LocalRetire>, U0>;
+
+    #[test]
+    fn retire_via() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let handle = LocalHandle::from_ref(&local);
+
+        let atomic = Atomic::new(DropCount(&COUNT));
+        let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+        unsafe { unlinked.retire_via(&handle) };
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+
+        // the final reclamation attempt reclaims the unprotected record
+        drop(handle);
+        drop(local);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn retire_guarded() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        // every operation triggers a scan, including the retirement itself
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(Some(ConfigBuilder::new().total_ops_budget(1).build()));
+        let handle = LocalHandle::from_ref(&local);
+        let mut guard = Guard::with_handle(handle.clone());
+
+        let atomic = Atomic::new(DropCount(&COUNT));
+        let _ = guard.protect(&atomic, Ordering::Acquire);
+        let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+        unsafe { handle.retire_guarded(unlinked, &mut guard) };
+
+        // the record remained protected during all scans triggered by retiring and releasing it
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+        assert!(guard.is_reserved());
+        assert_eq!(handle.flush(), 1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn absorb() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<LocalRetire>::default();
+        let parent = hp.build_local(None);
+        let child = hp.build_local(None);
+
+        let child_handle = LocalHandle::from_ref(&child);
+        for _ in 0..3 {
+            let atomic = Atomic::new(DropCount(&COUNT));
+            let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+            unsafe { unlinked.retire_via(&child_handle) };
+        }
+
+        drop(child_handle);
+        LocalHandle::<Hp<LocalRetire>>::from_ref(&parent).absorb(child);
+
+        // the child's records are now pending in the parent instead of being abandoned
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+        assert_eq!(hp.per_thread_pending(), vec![3]);
+        match &hp.state.retire_state {
+            GlobalRetireState::LocalStrategy(abandoned) => assert!(abandoned.is_empty()),
+            _ => panic!("invalid retire state"),
+        }
+
+        drop(parent);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn flush() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp: &'static Hp<LocalRetire> = Box::leak(Box::new(Hp::default()));
+        let atomic: &'static Atomic<DropCount> =
+            Box::leak(Box::new(Atomic::new(DropCount(&COUNT))));
+
+        let local = hp.build_local(None);
+        let handle = LocalHandle::<Hp<LocalRetire>>::from_ref(&local);
+        let mut guard = Guard::with_handle(handle.clone());
+        let _ = guard.protect(atomic, Ordering::SeqCst);
+
+        thread::spawn(move || {
+            let local = hp.build_local(None);
+            let handle = LocalHandle::from_ref(&local);
+            let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+            unsafe { unlinked.retire_via(&handle) };
+        })
+        .join()
+        .unwrap();
+
+        // the exited thread could not reclaim the protected record and had to abandon it
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+        assert_eq!(handle.flush(), 0);
+        assert_eq!(hp.per_thread_pending(), vec![1]);
+
+        drop(guard);
+        assert_eq!(handle.flush(), 1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn drop_below_threshold() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp: &'static Hp<LocalRetire> = Box::leak(Box::new(Hp::default()));
+        let protected: &'static Atomic<DropCount> =
+            Box::leak(Box::new(Atomic::new(DropCount(&COUNT))));
+
+        let local = hp.build_local(None);
+        let handle = LocalHandle::<Hp<LocalRetire>>::from_ref(&local);
+        let mut guard = Guard::with_handle(handle.clone());
+        let _ = guard.protect(protected, Ordering::SeqCst);
+
+        // the exiting thread retires far fewer records than required for triggering a scan
+        thread::spawn(move || {
+            let local = hp.build_local(None);
+            let handle = LocalHandle::from_ref(&local);
+            let atomics = [Atomic::new(DropCount(&COUNT)), Atomic::new(DropCount(&COUNT))];
+            for atomic in atomics.iter().chain(Some(protected)) {
+                let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+                unsafe { unlinked.retire_via(&handle) };
+            }
+
+            assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+        })
+        .join()
+        .unwrap();
+
+        // dropping the thread's local state reclaimed all unprotected records and abandoned the
+        // protected one
+        assert_eq!(COUNT.load(Ordering::Relaxed), 2);
+        match &hp.state.retire_state {
+            GlobalRetireState::LocalStrategy(abandoned) => assert!(!abandoned.is_empty()),
+            _ => panic!("invalid retire state"),
+        }
+
+        drop(guard);
+        assert_eq!(handle.flush(), 1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn retire_local_only() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        thread_local!(static AFFINE: Cell<bool> = Cell::new(false));
+
+        struct ThreadAffine;
+        impl Drop for ThreadAffine {
+            fn drop(&mut self) {
+                assert!(AFFINE.with(Cell::get), "thread-affine record dropped on another thread");
+                COUNT.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let hp: &'static Hp<LocalRetire> = Box::leak(Box::new(Hp::default()));
+        let atomic: &'static Atomic<ThreadAffine> = Box::leak(Box::new(Atomic::new(ThreadAffine)));
+
+        let local = hp.build_local(None);
+        let handle = LocalHandle::<Hp<LocalRetire>>::from_ref(&local);
+        let mut guard = Guard::with_handle(handle.clone());
+        let _ = guard.protect(atomic, Ordering::SeqCst);
+
+        let (retired_tx, retired_rx) = mpsc::channel();
+        let (released_tx, released_rx) = mpsc::channel();
+        let affine = thread::spawn(move || {
+            AFFINE.with(|affine| affine.set(true));
+            let local = hp.build_local(None);
+            let handle = LocalHandle::from_ref(&local);
+            let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+            unsafe { handle.retire_local_only(unlinked) };
+            assert_eq!(handle.flush(), 0);
+
+            retired_tx.send(()).unwrap();
+            released_rx.recv().unwrap();
+            assert_eq!(handle.flush(), 1);
+        });
+
+        // the unprotected record is never adopted by any other thread
+        retired_rx.recv().unwrap();
+        drop(guard);
+        assert_eq!(handle.flush(), 0);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+
+        released_tx.send(()).unwrap();
+        affine.join().unwrap();
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn retire_with() {
+        static FREED: AtomicUsize = AtomicUsize::new(0);
+
+        unsafe fn free(ptr: NonNull<u64>) {
+            drop(Box::from_raw(ptr.as_ptr()));
+            FREED.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn retire_protected<S: RetireStrategy>(hp: &Hp<S>)
+        where
+            Hp<S>: Reclaim,
+        {
+            let local = hp.build_local(None);
+            let handle = LocalHandle::from_ref(&local);
+            let ptr = NonNull::from(Box::leak(Box::new(1u64)));
+            let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+            hazard.set_protected(ptr.cast());
+
+            let before = FREED.load(Ordering::Relaxed);
+            unsafe { handle.retire_with(ptr, free) };
+            assert_eq!(handle.flush(), 0);
+
+            // the custom destructor is called instead of dropping the record
+            hazard.set_free(Ordering::Relaxed);
+            assert_eq!(handle.flush(), 1);
+            assert_eq!(FREED.load(Ordering::Relaxed), before + 1);
+        }
+
+        retire_protected(&Hp::<LocalRetire>::default());
+        retire_protected(&Hp::<GlobalRetire>::default());
+    }
+
+    #[test]
+    fn retire_many() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(Some(ConfigBuilder::new().ops_count_threshold(4).build()));
+        let handle = LocalHandle::from_ref(&local);
+
+        let retired: Vec<_> = (0..10)
+            .map(|_| {
+                let atomic = Atomic::new(DropCount(&COUNT));
+                let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+                Unlinked::into_retired(unlinked)
+            })
+            .collect();
+
+        // the batch exceeds the threshold more than twice, but only triggers a single scan
+        unsafe { handle.retire_many(retired) };
+        assert_eq!(handle.stats(), LocalStats { retired: 10, reclaimed: 10, scans: 1 });
+        assert_eq!(local.ops_count(), 0);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 10);
+    }
+
+    #[test]
+    fn count_both() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let mut config = ConfigBuilder::new().ops_count_threshold(4).build();
+        config.count_strategy = CountStrategy::Both;
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(Some(config));
+        let handle = LocalHandle::from_ref(&local);
+
+        for _ in 0..2 {
+            local.retire(retired(&COUNT));
+        }
+
+        assert_eq!(local.ops_count(), 2);
+
+        // protecting a value is not counted, but releasing it is
+        let atomic = Atomic::new(1);
+        let mut guard = Guard::with_handle(handle.clone());
+        let _ = guard.protect(&atomic, Ordering::Acquire);
+        guard.release();
+        assert_eq!(local.ops_count(), 3);
+
+        // dropping the guard counts as the fourth operation, which triggers a scan and resets the
+        // ops count
+        drop(guard);
+        assert_eq!(handle.stats(), LocalStats { retired: 2, reclaimed: 2, scans: 1 });
+        assert_eq!(local.ops_count(), 0);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn try_reclaim() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let handle = LocalHandle::from_ref(&local);
+        assert_eq!(handle.try_reclaim(), 0);
+
+        let protected = Atomic::new(DropCount(&COUNT));
+        let mut guard = Guard::with_handle(handle.clone());
+        let _ = guard.protect(&protected, Ordering::Acquire);
+
+        for atomic in &[protected, Atomic::new(DropCount(&COUNT))] {
+            let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+            unsafe { unlinked.retire_via(&handle) };
+        }
+
+        assert_ne!(local.ops_count(), 0);
+        assert_eq!(handle.try_reclaim(), 1);
+        assert_eq!(local.ops_count(), 0);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+
+        drop(guard);
+        assert_eq!(handle.try_reclaim(), 1);
+        assert_eq!(handle.try_reclaim(), 0);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn local_spillover() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        thread_local!(static VETO: Cell<bool> = Cell::new(false));
+
+        let config = ConfigBuilder::new()
+            .local_spillover_threshold(4)
+            .can_reclaim(|_| !VETO.with(Cell::get))
+            .build();
+        let hp: &'static Hp<LocalRetire> = Box::leak(Box::new(Hp::default()));
+
+        // the retiring thread can not reclaim any of its own records
+        VETO.with(|veto| veto.set(true));
+        let local = hp.build_local(Some(config));
+        let handle = LocalHandle::from_ref(&local);
+        for _ in 0..8 {
+            let atomic = Atomic::new(DropCount(&COUNT));
+            let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+            unsafe { unlinked.retire_via(&handle) };
+        }
+
+        // half of the records exceeding the threshold are spilled
+        assert_eq!(handle.flush(), 0);
+        assert_eq!(hp.per_thread_pending(), vec![4]);
+
+        let reclaimed = thread::spawn(move || {
+            let local = hp.build_local(Some(config));
+            let handle = LocalHandle::from_ref(&local);
+            handle.flush()
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(reclaimed, 4);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn stats() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        thread_local!(static VETO: Cell<bool> = Cell::new(false));
+
+        fn retire(handle: &LocalHandle<Hp<LocalRetire>>) {
+            let atomic = Atomic::new(DropCount(&COUNT));
+            let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+            unsafe { unlinked.retire_via(handle) };
+        }
+
+        let config = ConfigBuilder::new().can_reclaim(|_| !VETO.with(Cell::get)).build();
+        let hp: &'static Hp<LocalRetire> = Box::leak(Box::new(Hp::default()));
+
+        // the exiting thread can not reclaim its own records and abandons them
+        thread::spawn(move || {
+            VETO.with(|veto| veto.set(true));
+            let local = hp.build_local(Some(config));
+            let handle = LocalHandle::from_ref(&local);
+            retire(&handle);
+            retire(&handle);
+            assert_eq!(handle.flush(), 0);
+            assert_eq!(handle.stats(), LocalStats { retired: 2, reclaimed: 0, scans: 1 });
+        })
+        .join()
+        .unwrap();
+
+        // the adopted records are attributed to the adopting thread once it reclaims them
+        let local = hp.build_local(Some(config));
+        let handle = LocalHandle::from_ref(&local);
+        retire(&handle);
+        assert_eq!(handle.flush(), 3);
+        assert_eq!(handle.stats(), LocalStats { retired: 1, reclaimed: 3, scans: 1 });
+        assert_eq!(COUNT.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn adopt_abandoned_contents() {
+        const THREADS: usize = 4;
+        const RECORDS: usize = 16;
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        thread_local!(static ADOPTER: Cell<bool> = Cell::new(false));
+
+        struct Checked {
+            values: Vec<String>,
+            len: usize,
+        }
+
+        impl Drop for Checked {
+            fn drop(&mut self) {
+                // the adopting thread must observe all writes made by the exited thread
+                assert_eq!(self.values.iter().map(String::len).sum::<usize>(), self.len);
+                COUNT.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        // only the adopting thread may reclaim, so the exiting threads must abandon all records
+        let config = ConfigBuilder::new().can_reclaim(|_| ADOPTER.with(Cell::get)).build();
+        let hp: &'static Hp<LocalRetire> = Box::leak(Box::new(Hp::default()));
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                thread::spawn(move || {
+                    let local = hp.build_local(Some(config));
+                    let handle = LocalHandle::from_ref(&local);
+                    for i in 0..RECORDS {
+                        let values: Vec<_> = (0..=i).map(|j| j.to_string()).collect();
+                        let len = values.iter().map(String::len).sum();
+                        let atomic = Atomic::new(Checked { values, len });
+                        let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+                        unsafe { unlinked.retire_via(&handle) };
+                    }
+                })
+            })
+            .collect();
+
+        // the records are adopted concurrently to the exiting threads, so the only synchronization
+        // is through the abandoned queue
+        ADOPTER.with(|adopter| adopter.set(true));
+        let local = hp.build_local(Some(config));
+        let handle = LocalHandle::from_ref(&local);
+        let mut reclaimed = 0;
+        while reclaimed < THREADS * RECORDS {
+            reclaimed += handle.flush();
+            thread::yield_now();
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(COUNT.load(Ordering::Relaxed), THREADS * RECORDS);
+    }
+}
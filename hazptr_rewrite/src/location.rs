@@ -0,0 +1,123 @@
+//! Debugging aid for tracking the source locations at which records have been
+//! retired (requires feature `track-retire-location`).
+//!
+//! The location of every retire call is stored in a global side table keyed by
+//! the address of the retired record until the record is eventually reclaimed.
+//!
+//! The caller's location is recorded for all retire methods of this crate,
+//! e.g. [`retire`][crate::retire] or
+//! [`RetireVia::retire_via`][crate::RetireVia::retire_via].
+//! Records retired through the methods of `conquer_reclaim` (e.g.
+//! `Unlinked::retire`) are recorded with a location inside that crate instead,
+//! since it does not propagate its callers' locations.
+
+use std::collections::hash_map::{Entry, HashMap};
+use std::panic::Location;
+use std::sync::Mutex;
+
+use conquer_once::Lazy;
+
+/// The global side table mapping the addresses of all currently retired (but
+/// not yet reclaimed) records to the location at which they were retired.
+static LOCATIONS: Lazy<Mutex<HashMap<usize, &'static Location<'static>>>> =
+    Lazy::new(Default::default);
+
+/// Returns the source location at which the record at `ptr` has been retired,
+/// if it has been retired and not yet reclaimed.
+#[inline]
+pub fn retire_location<T>(ptr: *const T) -> Option<&'static Location<'static>> {
+    LOCATIONS.lock().unwrap().get(&(ptr as usize)).copied()
+}
+
+/// Records `location` as the retire site of the record at `addr`.
+///
+/// # Panics
+///
+/// Panics, if the record at `addr` has already been retired but not yet been
+/// reclaimed, reporting both retire sites.
+#[inline]
+pub(crate) fn record(addr: usize, location: &'static Location<'static>) {
+    let mut locations = LOCATIONS.lock().unwrap();
+    match locations.entry(addr) {
+        Entry::Vacant(entry) => {
+            entry.insert(location);
+        }
+        Entry::Occupied(entry) => {
+            let first = *entry.get();
+            // release the lock before panicking to avoid poisoning it
+            drop(locations);
+            panic!(
+                "record at {:#x} retired twice (first retired at {}, again at {})",
+                addr, first, location
+            );
+        }
+    }
+}
+
+/// Removes the record at `addr` from the side table once it gets reclaimed.
+#[inline]
+pub(crate) fn forget(addr: usize) {
+    LOCATIONS.lock().unwrap().remove(&addr);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{self, Location};
+    use std::sync::atomic::Ordering;
+
+    use conquer_reclaim::typenum::U0;
+    use conquer_reclaim::Owned;
+
+    use crate::{GlobalHp, Hp, LocalHandle, LocalRetire, RetireVia};
+
+    #[test]
+    fn retire_site() {
+        type Atomic<T> = conquer_reclaim::Atomic<T, GlobalHp, U0>;
+
+        let atomic = Atomic::new(1);
+        let (ptr, _) = atomic.load_raw(Ordering::Relaxed).decompose();
+        let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+        let line = line!() + 1;
+        unsafe { crate::retire(unlinked) };
+
+        let location = super::retire_location(ptr).unwrap();
+        assert_eq!((location.file(), location.line()), (file!(), line));
+    }
+
+    #[test]
+    fn retire_via_site() {
+        type Atomic<T> = conquer_reclaim::Atomic<T, Hp<LocalRetire>, U0>;
+        type Owned<T> = conquer_reclaim::Owned<T, Hp<LocalRetire>, U0>;
+
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(None);
+        let handle = LocalHandle::from_ref(&local);
+
+        let atomic = Atomic::new(1);
+        let (ptr, _) = atomic.load_raw(Ordering::Relaxed).decompose();
+        let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+        let line = line!() + 1;
+        unsafe { unlinked.retire_via(&handle) };
+
+        let location = super::retire_location(ptr).unwrap();
+        assert_eq!((location.file(), location.line()), (file!(), line));
+    }
+
+    #[test]
+    fn double_retire() {
+        static RECORD: u64 = 0;
+        let addr = &RECORD as *const u64 as usize;
+
+        let first = Location::caller();
+        super::record(addr, first);
+        assert_eq!(super::retire_location(&RECORD), Some(first));
+
+        let res = panic::catch_unwind(|| super::record(addr, Location::caller()));
+        let msg = *res.unwrap_err().downcast::<String>().unwrap();
+        assert!(msg.contains("retired twice"));
+        assert!(msg.contains(&first.to_string()));
+
+        super::forget(addr);
+        assert_eq!(super::retire_location(&RECORD), None);
+    }
+}
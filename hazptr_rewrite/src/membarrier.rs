@@ -0,0 +1,45 @@
+//! Process-wide memory barriers based on the Linux `membarrier` system call,
+//! which allow replacing the `SeqCst` fences on the (frequent) protecting side
+//! with mere compiler fences at the expense of a (much) more expensive barrier
+//! on the (infrequent) scanning side.
+//!
+//! On other platforms or when the `membarrier` feature is disabled, the
+//! registration always fails and the `SeqCst` fences are used instead.
+
+#[cfg(all(target_os = "linux", feature = "membarrier"))]
+mod imp {
+    const MEMBARRIER_CMD_PRIVATE_EXPEDITED: libc::c_int = 1 << 3;
+    const MEMBARRIER_CMD_REGISTER_PRIVATE_EXPEDITED: libc::c_int = 1 << 4;
+
+    /// Registers the process for using expedited private barriers and returns
+    /// `true` on success.
+    #[inline]
+    pub fn register() -> bool {
+        unsafe {
+            libc::syscall(libc::SYS_membarrier, MEMBARRIER_CMD_REGISTER_PRIVATE_EXPEDITED, 0) == 0
+        }
+    }
+
+    /// Issues a memory barrier on all running threads of the process.
+    #[inline]
+    pub fn barrier() {
+        let res =
+            unsafe { libc::syscall(libc::SYS_membarrier, MEMBARRIER_CMD_PRIVATE_EXPEDITED, 0) };
+        assert_eq!(res, 0, "`membarrier` must not fail after successful registration");
+    }
+}
+
+#[cfg(not(all(target_os = "linux", feature = "membarrier")))]
+mod imp {
+    #[inline]
+    pub fn register() -> bool {
+        false
+    }
+
+    #[inline]
+    pub fn barrier() {
+        unreachable!("`membarrier` is not supported on this platform")
+    }
+}
+
+pub(crate) use self::imp::{barrier, register};
@@ -0,0 +1,115 @@
+//! Export of the reclamation statistics in the Prometheus text exposition
+//! format (requires feature `metrics-export`).
+
+use core::fmt::Write;
+
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+use crate::retire::{GlobalRetireState, RetireStrategy};
+use crate::Hp;
+
+/********** impl inherent *************************************************************************/
+
+impl<S: RetireStrategy> Hp<S> {
+    /// Returns the current reclamation statistics in the Prometheus text
+    /// exposition format, e.g. for serving them on a `/metrics` endpoint.
+    ///
+    /// The following metrics are exported:
+    ///
+    /// - `hazptr_retired_total`: the number of records retired by all threads
+    /// - `hazptr_reclaimed_total`: the number of records reclaimed by all
+    ///   threads or by ad-hoc reclamation calls
+    /// - `hazptr_scans_total`: the number of performed scans of all hazard
+    ///   pointers
+    /// - `hazptr_pending_records`: the number of retired but not yet
+    ///   reclaimed records
+    /// - `hazptr_hazards_allocated`: the number of allocated hazard pointers
+    /// - `hazptr_peak_protected`: the highest number of simultaneously
+    ///   protected pointers observed by any scan
+    /// - `hazptr_live_locals`: the number of currently live thread local
+    ///   states
+    ///
+    /// Records reclaimed through [`take_retired`][Hp::take_retired] are not
+    /// counted.
+    #[inline]
+    pub fn metrics_text(&self) -> String {
+        let (retired, reclaimed, scans) = self.state.totals();
+        let pending = match &self.state.retire_state {
+            GlobalRetireState::GlobalStrategy(queue) => queue.pending(),
+            GlobalRetireState::LocalStrategy(_) => self.state.pending().iter().sum(),
+        };
+
+        let mut text = String::new();
+        let metrics = [
+            ("hazptr_retired_total", "counter", "The number of retired records.", retired),
+            ("hazptr_reclaimed_total", "counter", "The number of reclaimed records.", reclaimed),
+            ("hazptr_scans_total", "counter", "The number of performed scans.", scans),
+            ("hazptr_pending_records", "gauge", "The number of unreclaimed records.", pending),
+            (
+                "hazptr_hazards_allocated",
+                "gauge",
+                "The number of allocated hazard pointers.",
+                self.state.hazard_capacity(),
+            ),
+            (
+                "hazptr_peak_protected",
+                "gauge",
+                "The highest number of simultaneously protected pointers.",
+                self.state.peak_protected(),
+            ),
+            (
+                "hazptr_live_locals",
+                "gauge",
+                "The number of live thread local states.",
+                self.state.live_locals(),
+            ),
+        ];
+
+        for (name, kind, help, value) in metrics.iter() {
+            // writing into a `String` can not fail
+            let _ = write!(
+                text,
+                "# HELP {} {}\n# TYPE {} {}\n{} {}\n",
+                name, help, name, kind, name, value
+            );
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use crate::test_util::retired;
+    use crate::{ConfigBuilder, Hp, LocalRetire};
+
+    #[test]
+    fn metrics_text() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<LocalRetire>::default();
+        let local = hp.build_local(Some(ConfigBuilder::new().ops_count_threshold(1000).build()));
+
+        for _ in 0..10 {
+            local.retire(retired(&COUNT));
+        }
+
+        assert_eq!(local.try_reclaim(), 10);
+        let text = hp.metrics_text();
+        for line in &[
+            "# TYPE hazptr_retired_total counter\nhazptr_retired_total 10\n",
+            "# TYPE hazptr_reclaimed_total counter\nhazptr_reclaimed_total 10\n",
+            "hazptr_scans_total 1\n",
+            "hazptr_pending_records 0\n",
+            "# TYPE hazptr_live_locals gauge\nhazptr_live_locals 1\n",
+        ] {
+            assert!(text.contains(line), "missing `{}` in:\n{}", line, text);
+        }
+
+        assert!(text.contains("# HELP hazptr_hazards_allocated "));
+        assert!(text.contains("hazptr_peak_protected 0\n"));
+    }
+}
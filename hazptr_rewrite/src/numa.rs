@@ -0,0 +1,36 @@
+//! Platform queries for the NUMA node the calling thread is currently running
+//! on, which is used for assigning threads to per-node retire queues, if no
+//! explicit node hint is given.
+//!
+//! On other platforms than Linux or when the `numa` feature is disabled, the
+//! query always fails and threads are assigned to queues in round-robin order.
+
+#[cfg(all(target_os = "linux", feature = "numa"))]
+mod imp {
+    use core::ptr;
+
+    /// Returns the NUMA node the calling thread is currently running on.
+    #[inline]
+    pub fn current_node() -> Option<usize> {
+        let (mut cpu, mut node): (libc::c_uint, libc::c_uint) = (0, 0);
+        let res = unsafe {
+            libc::syscall(libc::SYS_getcpu, &mut cpu, &mut node, ptr::null_mut::<libc::c_void>())
+        };
+
+        if res == 0 {
+            Some(node as usize)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(not(all(target_os = "linux", feature = "numa")))]
+mod imp {
+    #[inline]
+    pub fn current_node() -> Option<usize> {
+        None
+    }
+}
+
+pub(crate) use self::imp::current_node;
@@ -0,0 +1,30 @@
+//! Debugging aid for attributing hazard pointers to the threads that have
+//! acquired them (requires feature `track-owner`).
+//!
+//! The owning thread of every acquired hazard pointer is stored in a global
+//! side table keyed by the address of the hazard pointer, which answers e.g.
+//! which thread is keeping a record from being reclaimed.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread::{self, ThreadId};
+
+use conquer_once::Lazy;
+
+use crate::hazard::HazardPtr;
+
+/// The global side table mapping the addresses of all hazard pointers, which
+/// have been acquired at least once, to the thread that last acquired them.
+static OWNERS: Lazy<Mutex<HashMap<usize, ThreadId>>> = Lazy::new(Default::default);
+
+/// Records the current thread as the owner of `hazard`.
+#[inline]
+pub(crate) fn record(hazard: &HazardPtr) {
+    OWNERS.lock().unwrap().insert(hazard as *const _ as usize, thread::current().id());
+}
+
+/// Returns the thread that last acquired `hazard`, if any.
+#[inline]
+pub(crate) fn owner(hazard: &HazardPtr) -> Option<ThreadId> {
+    OWNERS.lock().unwrap().get(&(hazard as *const _ as usize)).copied()
+}
@@ -62,6 +62,7 @@ impl<N: RawNode> RawQueue<N> {
             let head = self.head.load(Ordering::Relaxed);
             N::set_next(node, head);
 
+            // (QUE:1) this `Release` CAS synchronizes-with the `Acquire` swap (QUE:3)
             if self
                 .head
                 .compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed)
@@ -78,6 +79,7 @@ impl<N: RawNode> RawQueue<N> {
             let head = self.head.load(Ordering::Relaxed);
             N::set_next(last, head);
 
+            // (QUE:2) this `Release` CAS synchronizes-with the `Acquire` swap (QUE:3)
             if self
                 .head
                 .compare_exchange_weak(head, first, Ordering::Release, Ordering::Relaxed)
@@ -94,6 +96,10 @@ impl<N: RawNode> RawQueue<N> {
     /// nodes and can deallocate or mutate them as required.
     #[inline]
     pub fn take_all(&self) -> *mut N {
+        // (QUE:3) this `Acquire` swap synchronizes-with the `Release` CAS (QUE:1) or (QUE:2) of
+        // every node in the queue, since every subsequent push (being a RMW operation) continues
+        // the release sequence of all previous ones, so all writes to any node (and the records it
+        // contains) made before its insertion are visible to the taking thread
         self.head.swap(ptr::null_mut(), Ordering::Acquire)
     }
 }
@@ -0,0 +1,159 @@
+//! A lock-free registry of slots, which thread local states use for publishing
+//! their number of pending (i.e. not yet reclaimed) retired records.
+
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// PendingRegistry
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A linked list of [`PendingSlot`]s, one for each live thread local state.
+///
+/// Like the [`HazardList`][crate::hazard::HazardList], the registry never
+/// shrinks, slots of thread local states that have been dropped are instead
+/// re-used by later ones, so that a slot effectively serves as a weak
+/// reference to its owning thread local state.
+#[derive(Debug, Default)]
+pub(crate) struct PendingRegistry {
+    head: AtomicPtr<PendingSlot>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl PendingRegistry {
+    /// Creates a new empty [`PendingRegistry`].
+    #[inline]
+    pub const fn new() -> Self {
+        Self { head: AtomicPtr::new(ptr::null_mut()) }
+    }
+
+    /// Acquires an unused slot or inserts a new one, if there are none.
+    #[cold]
+    #[inline(never)]
+    pub fn acquire(&self) -> &PendingSlot {
+        let mut curr = self.head.load(Ordering::Acquire);
+        while let Some(slot) = unsafe { curr.as_ref() } {
+            if slot
+                .in_use
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
+                return slot;
+            }
+
+            curr = slot.next;
+        }
+
+        let slot = Box::into_raw(Box::new(PendingSlot {
+            pending: AtomicUsize::new(0),
+            buffer_bytes: AtomicUsize::new(0),
+            in_use: AtomicBool::new(true),
+            next: ptr::null_mut(),
+        }));
+
+        loop {
+            let head = self.head.load(Ordering::Relaxed);
+            unsafe { (*slot).next = head };
+            if self
+                .head
+                .compare_exchange_weak(head, slot, Ordering::Release, Ordering::Relaxed)
+                .is_ok()
+            {
+                return unsafe { &*slot };
+            }
+        }
+    }
+
+    /// Returns the total number of bytes allocated for the retire buffers of
+    /// all slots currently in use.
+    #[inline]
+    pub fn buffer_bytes(&self) -> usize {
+        let mut total = 0;
+        let mut curr = self.head.load(Ordering::Acquire);
+        while let Some(slot) = unsafe { curr.as_ref() } {
+            if slot.in_use.load(Ordering::Acquire) {
+                total += slot.buffer_bytes.load(Ordering::Relaxed);
+            }
+
+            curr = slot.next;
+        }
+
+        total
+    }
+
+    /// Returns the number of pending records of every slot currently in use.
+    #[inline]
+    pub fn pending(&self) -> Vec<usize> {
+        let mut vec = Vec::new();
+        let mut curr = self.head.load(Ordering::Acquire);
+        while let Some(slot) = unsafe { curr.as_ref() } {
+            if slot.in_use.load(Ordering::Acquire) {
+                vec.push(slot.pending.load(Ordering::Relaxed));
+            }
+
+            curr = slot.next;
+        }
+
+        vec
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for PendingRegistry {
+    #[inline(never)]
+    fn drop(&mut self) {
+        let mut curr = *self.head.get_mut();
+        while !curr.is_null() {
+            let slot = unsafe { Box::from_raw(curr) };
+            curr = slot.next;
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// PendingSlot
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A slot in the [`PendingRegistry`] owned by a single thread local state.
+#[derive(Debug)]
+pub(crate) struct PendingSlot {
+    pending: AtomicUsize,
+    /// The number of bytes allocated for the owning thread local state's
+    /// retire buffer.
+    buffer_bytes: AtomicUsize,
+    in_use: AtomicBool,
+    /// The next pointer, which is never changed after the slot's insertion.
+    next: *mut Self,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl PendingSlot {
+    /// Publishes the owning thread local state's current number of pending
+    /// retired records and the size of the buffer holding them.
+    #[inline]
+    pub fn set_pending(&self, pending: usize, buffer_bytes: usize) {
+        self.pending.store(pending, Ordering::Relaxed);
+        self.buffer_bytes.store(buffer_bytes, Ordering::Relaxed);
+    }
+
+    /// Releases the slot for re-use by other thread local states.
+    #[inline]
+    pub fn release(&self) {
+        self.pending.store(0, Ordering::Relaxed);
+        self.buffer_bytes.store(0, Ordering::Relaxed);
+        self.in_use.store(false, Ordering::Release);
+    }
+}
+
+/********** impl Sync *****************************************************************************/
+
+// SAFETY: the raw `next` pointer is written only once before the slot is published with a `Release`
+// CAS and never changes afterwards, all other fields are atomic
+unsafe impl Send for PendingSlot {}
+unsafe impl Sync for PendingSlot {}
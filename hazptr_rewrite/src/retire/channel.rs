@@ -0,0 +1,305 @@
+//! A bounded multi-producer channel for handing off batches of retired records
+//! from worker threads to a dedicated reclaimer thread.
+//!
+//! Workers collect their retired records in [`RetireBatch`]es and push them
+//! into the [`RetireChannel`], from which the reclaimer pops them and retires
+//! them into its own thread local state (see
+//! [`LocalHandle::retire_batch`][crate::LocalHandle::retire_batch]).
+//! This decouples retiring records from reclaiming them, so that workers never
+//! have to scan the hazard pointers themselves.
+
+use core::ptr;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+
+use conquer_reclaim::RawRetired;
+use conquer_util::BackOff;
+
+use crate::global::Global;
+use crate::queue::{RawNode, RawQueue};
+
+use super::local_retire::RetireNode;
+use super::GlobalRetireState;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// RetireBatch
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A batch of retired records, which can be sent to a reclaimer thread through
+/// a [`RetireChannel`].
+#[derive(Debug, Default)]
+pub struct RetireBatch {
+    node: Box<RetireNode>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl RetireBatch {
+    /// Creates a new empty [`RetireBatch`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of records in the batch.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.node.len()
+    }
+
+    /// Returns `true` if the batch contains no records.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.node.is_empty()
+    }
+
+    /// Adds the `retired` record to the batch.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure `retired` satisfies the usual invariants for
+    /// retiring and that the batch is eventually retired with
+    /// [`retire_batch`][crate::LocalHandle::retire_batch], since dropping a
+    /// batch reclaims all of its records right away, regardless of whether
+    /// they are still protected.
+    #[inline]
+    pub unsafe fn push(&mut self, retired: RawRetired) {
+        // the generation and scan count are set once the batch is retired by the receiving thread
+        self.node.retire(retired, 0, 0);
+    }
+
+    #[inline]
+    pub(crate) fn into_node(self) -> Box<RetireNode> {
+        self.node
+    }
+}
+
+/********** impl Send *****************************************************************************/
+
+// SAFETY: retired records are no longer reachable by any thread and can hence be reclaimed by any
+// thread, like records in the abandoned queue
+unsafe impl Send for RetireBatch {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// RetireChannel
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A bounded channel for sending [`RetireBatch`]es to a reclaimer thread,
+/// which is created with [`Hp::retire_channel`][crate::Hp::retire_channel].
+///
+/// Producers pushing into a full channel back off until the consumer has
+/// popped a batch, which applies backpressure to retiring threads.
+/// Batches are not necessarily popped in the order in which they were pushed.
+#[derive(Debug)]
+pub struct RetireChannel<'global> {
+    raw: RawQueue<RetireNode>,
+    len: AtomicUsize,
+    capacity: usize,
+    /// The global state, whose hazard pointers may protect the records in the
+    /// channel.
+    global: &'global Global,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<'global> RetireChannel<'global> {
+    /// Creates a new empty [`RetireChannel`] holding at most `capacity`
+    /// batches of records protected by the hazard pointers of `global`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    #[inline]
+    pub(crate) fn new(global: &'global Global, capacity: usize) -> Self {
+        assert!(capacity > 0, "the channel capacity must be at least 1");
+        Self { raw: RawQueue::new(), len: AtomicUsize::new(0), capacity, global }
+    }
+
+    /// Returns the number of batches currently in the channel.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the channel currently contains no batches.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Pushes `batch` into the channel, backing off until there is space, if
+    /// the channel is full.
+    #[inline]
+    pub fn push_batch(&self, batch: RetireBatch) {
+        let backoff = BackOff::new();
+        loop {
+            let len = self.len.load(Ordering::Relaxed);
+            if len < self.capacity
+                && self
+                    .len
+                    .compare_exchange_weak(len, len + 1, Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok()
+            {
+                break;
+            }
+
+            backoff.spin();
+        }
+
+        unsafe { self.raw.push(Box::into_raw(batch.into_node())) };
+    }
+
+    /// Pops a batch from the channel or returns `None`, if it is empty.
+    #[inline]
+    pub fn pop_batch(&self) -> Option<RetireBatch> {
+        let head = self.raw.take_all();
+        if head.is_null() {
+            return None;
+        }
+
+        unsafe {
+            // all remaining batches are pushed back into the channel in bulk
+            let next = RetireNode::next(head);
+            if !next.is_null() {
+                let mut last = next;
+                while !RetireNode::next(last).is_null() {
+                    last = RetireNode::next(last);
+                }
+
+                self.raw.push_many((next, last));
+            }
+
+            RetireNode::set_next(head, ptr::null_mut());
+            self.len.fetch_sub(1, Ordering::Relaxed);
+            Some(RetireBatch { node: Box::from_raw(head) })
+        }
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for RetireChannel<'_> {
+    #[inline(never)]
+    fn drop(&mut self) {
+        if self.is_empty() {
+            return;
+        }
+
+        // all unprotected records remaining in the channel are reclaimed, all others are abandoned
+        // to the global state like those of an exited thread
+        let protected = self.global.protected_snapshot();
+        while let Some(batch) = self.pop_batch() {
+            let mut node = batch.into_node();
+            unsafe { node.reclaim_all_unprotected(&protected, None) };
+            if !node.is_empty() {
+                match &self.global.retire_state {
+                    GlobalRetireState::LocalStrategy(abandoned) => abandoned.push(node),
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+}
+
+/********** impl Send & Sync **********************************************************************/
+
+// SAFETY: all batches are only accessed by a single thread after being popped (see `RetireBatch`)
+unsafe impl Send for RetireChannel<'_> {}
+unsafe impl Sync for RetireChannel<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    use std::ptr::NonNull;
+
+    use crate::hazard::ProtectStrategy;
+    use crate::test_util::retired;
+    use crate::{Hp, LocalHandle, LocalRetire};
+
+    use super::RetireBatch;
+
+    #[test]
+    fn multiple_producers() {
+        const PRODUCERS: usize = 4;
+        const BATCHES: usize = 64;
+        const BATCH_SIZE: usize = 8;
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp: &'static Hp<LocalRetire> = Box::leak(Box::new(Hp::default()));
+        let channel = Arc::new(hp.retire_channel(4));
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|_| {
+                let channel = Arc::clone(&channel);
+                thread::spawn(move || {
+                    for _ in 0..BATCHES {
+                        let mut batch = RetireBatch::new();
+                        for _ in 0..BATCH_SIZE {
+                            unsafe { batch.push(retired(&COUNT)) };
+                        }
+
+                        channel.push_batch(batch);
+                        assert!(channel.len() <= 4);
+                    }
+                })
+            })
+            .collect();
+
+        // the single consumer retires all batches into its own local state
+        let local = hp.build_local(None);
+        let handle = LocalHandle::from_ref(&local);
+
+        let mut received = 0;
+        while received < PRODUCERS * BATCHES {
+            match channel.pop_batch() {
+                Some(batch) => {
+                    assert_eq!(batch.len(), BATCH_SIZE);
+                    handle.retire_batch(batch);
+                    received += 1;
+                }
+                None => thread::yield_now(),
+            }
+        }
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+
+        assert!(channel.is_empty());
+        drop(handle);
+        drop(local);
+        assert_eq!(COUNT.load(Ordering::Relaxed), PRODUCERS * BATCHES * BATCH_SIZE);
+    }
+
+    #[test]
+    fn drop_abandons_protected() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<LocalRetire>::default();
+        let channel = hp.retire_channel(1);
+        let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+
+        let mut batch = RetireBatch::new();
+        for i in 0..4 {
+            let retired = retired(&COUNT);
+            if i == 0 {
+                hazard.set_protected(NonNull::new(retired.address() as *mut ()).unwrap());
+            }
+
+            unsafe { batch.push(retired) };
+        }
+
+        // the protected record survives dropping the undrained channel
+        channel.push_batch(batch);
+        drop(channel);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 3);
+
+        hazard.set_free(Ordering::Relaxed);
+        assert_eq!(hp.reclaim_remaining(), 1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 4);
+    }
+}
@@ -12,13 +12,23 @@
 //! overhead, since every retired record requires a synchronized access to a
 //! single global shared data structure, which limits scalability.
 
+use core::iter::FusedIterator;
+use core::mem;
 use core::ptr;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
 
 use conquer_reclaim::RawRetired;
 
+use crate::global::{Global, ProtectedSet};
 use crate::hazard::ProtectedPtr;
+use crate::numa;
 use crate::queue::{RawNode, RawQueue};
 
+use super::Reclaimable;
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // Header
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -38,12 +48,104 @@ use crate::queue::{RawNode, RawQueue};
 pub struct Header {
     /// The pointer to the header of the next retired record.
     next: *mut Self,
-    /// The handle for the retired record itself.
+    /// The handle for the retired record itself, which is `None` for the
+    /// header of an [`ExternalHeader`].
     retired: Option<RawRetired>,
+    /// The generation in which the record was retired.
+    generation: usize,
+    /// The global scan count (see [`Global::scan_count`]) when the record was
+    /// retired.
+    ///
+    /// [`Global::scan_count`]: crate::global::Global::scan_count
+    scan: usize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Header {
+    /// Initializes the header of the record `retired` for insertion into a
+    /// linked chain of retired records and returns a pointer to it.
+    ///
+    /// See [`Hp::retire_linked_chain`][crate::Hp::retire_linked_chain].
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure `retired` was derived from a
+    /// `Retired<Hp<GlobalRetire>>`.
+    #[inline]
+    pub unsafe fn from_retired(retired: RawRetired) -> *mut Self {
+        let header = retired.as_ptr() as *mut () as *mut Self;
+        (*header).next = ptr::null_mut();
+        (*header).retired = Some(retired);
+        header
+    }
+
+    /// Links the (initialized) `header` to `next`.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure `header` is a valid pointer to a header, which
+    /// is not yet retired.
+    #[inline]
+    pub unsafe fn link(header: *mut Self, next: *mut Self) {
+        (*header).next = next;
+    }
+
+    /// Allocates a separate header for the record `retired`, which has no
+    /// embedded header, and returns a pointer to it.
+    #[inline]
+    unsafe fn alloc_external(retired: Reclaimable) -> *mut Self {
+        let external = Box::new(ExternalHeader { header: Self::default(), retired });
+        Box::into_raw(external).cast()
+    }
+
+    /// Returns `true` if `header` has been allocated separately from its
+    /// record, i.e. as part of an [`ExternalHeader`].
+    #[inline]
+    unsafe fn is_external(header: *mut Self) -> bool {
+        // the handle of an embedded header is always set before it is retired
+        (*header).retired.is_none()
+    }
+
+    /// Returns the address of the record `header` belongs to.
+    #[inline]
+    unsafe fn address(header: *mut Self) -> usize {
+        match Self::is_external(header) {
+            true => (*header.cast::<ExternalHeader>()).retired.address(),
+            false => header as usize,
+        }
+    }
+
+    /// Reclaims the record `header` belongs to and de-allocates the header, if
+    /// it has been allocated separately.
+    #[inline]
+    unsafe fn reclaim(header: *mut Self) {
+        match (*header).retired.take() {
+            Some(mut retired) => retired.reclaim(),
+            None => {
+                let mut external = Box::from_raw(header.cast::<ExternalHeader>());
+                external.retired.reclaim();
+            }
+        }
+    }
 }
 
 /********** impl Sync *****************************************************************************/
 
+// SAFETY: a `Header` is embedded in every record allocated for the global
+// retire strategy and hence shared between all threads that can access the
+// record, but its fields are never accessed through a shared reference:
+//  - all fields are written exactly once by the retiring thread, before the
+//    header is inserted into a `RetiredQueue` with a `Release` CAS
+//  - afterwards, a header can only be accessed by the single thread that has
+//    removed it (along with the entire list) from the queue with an `Acquire`
+//    swap, which grants it exclusive access until the header is reclaimed or
+//    pushed back into the queue (again with `Release` semantics)
+// Hence, there are never any concurrent accesses to any field, regardless of
+// the (non thread-safe) raw `next` pointer or the `RawRetired`.
+// Note, that the `reclaim`-based implementation in the main crate requires no
+// such header (`RecordHeader = ()`), since its retired records are always
+// stored in thread-local (i.e. not shared) data structures.
 unsafe impl Sync for Header {}
 
 /*********** impl Default *************************************************************************/
@@ -51,7 +153,7 @@ unsafe impl Sync for Header {}
 impl Default for Header {
     #[inline]
     fn default() -> Self {
-        Self { next: ptr::null_mut(), retired: None }
+        Self { next: ptr::null_mut(), retired: None, generation: 0, scan: 0 }
     }
 }
 
@@ -69,6 +171,24 @@ impl RawNode for Header {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// ExternalHeader
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A [`Header`] that is allocated separately from its record, e.g. for records
+/// with a custom destructor, which have no embedded header.
+///
+/// Unlike the embedded header, it can hold any [`Reclaimable`], so that the
+/// header embedded in every record does not have to grow for it.
+#[derive(Debug)]
+#[repr(C)]
+struct ExternalHeader {
+    /// The header inserted into the queue, which must be the first field so
+    /// that pointers to both types can be cast into each other.
+    header: Header,
+    retired: Reclaimable,
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // RetiredQueue
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -76,10 +196,14 @@ impl RawNode for Header {
 /// A linked-list based for storing retired records.
 ///
 /// Every record must be allocated with a [`Header`] that allows it to be
-/// inserted into the queue and to be later reclaimed.
+/// inserted into the queue and to be later reclaimed, unless the queue uses
+/// external headers, which are allocated separately for each retired record.
 #[derive(Debug, Default)]
 pub(crate) struct RetiredQueue {
     raw: RawQueue<Header>,
+    /// The number of records currently in the queue.
+    pending: AtomicUsize,
+    external_headers: bool,
 }
 
 /********** impl inherent *************************************************************************/
@@ -88,7 +212,14 @@ impl RetiredQueue {
     /// Creates a new empty [`RetiredQueue`].
     #[inline]
     pub const fn new() -> Self {
-        Self { raw: RawQueue::new() }
+        Self { raw: RawQueue::new(), pending: AtomicUsize::new(0), external_headers: false }
+    }
+
+    /// Creates a new empty [`RetiredQueue`], which allocates a separate header
+    /// for every retired record.
+    #[inline]
+    pub const fn with_external_headers() -> Self {
+        Self { raw: RawQueue::new(), pending: AtomicUsize::new(0), external_headers: true }
     }
 
     /// Returns `true` if the [`RetiredQueue`] is empty.
@@ -97,62 +228,1057 @@ impl RetiredQueue {
         self.raw.is_empty()
     }
 
-    /// Pushes `retired` into the queue.
+    /// Returns the number of records currently in the queue.
+    #[inline]
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// Pushes `retired`, which is retired in the given `generation` after the
+    /// `scan`-th global scan, into the queue.
+    ///
+    /// # Safety
+    ///
+    /// See [`header_for`][RetiredQueue::header_for].
+    #[inline]
+    pub unsafe fn retire(&self, retired: RawRetired, generation: usize, scan: usize) {
+        let header = self.header_for(retired);
+        (*header).generation = generation;
+        (*header).scan = scan;
+        // the count must be increased before the record becomes reclaimable
+        self.pending.fetch_add(1, Ordering::Relaxed);
+        self.raw.push(header);
+    }
+
+    /// Initializes the header of the record `retired` or allocates a separate
+    /// one, if the queue uses external headers, and returns a pointer to it.
     ///
     /// # Safety
     ///
-    /// The caller has to ensure `retired` points at a record that has a header
-    /// of the correct type.
+    /// Unless the queue uses external headers, the caller has to ensure
+    /// `retired` points at a record that has a header of the correct type.
     /// Specifically, this requires that `retired` was derived from a
     /// `Retired<Hp<GlobalRetire>>`.
     #[inline]
-    pub unsafe fn retire(&self, retired: RawRetired) {
-        // `retired` points to a record, which has layout guarantees regarding field ordering
-        // and the record's header is always first
-        let header = retired.as_ptr() as *mut () as *mut Header;
-        // store the retired record in the header itself, because it is necessary for later
-        // reclamation
-        (*header).retired = Some(retired);
+    pub unsafe fn header_for(&self, retired: RawRetired) -> *mut Header {
+        match self.external_headers {
+            true => Header::alloc_external(Reclaimable::Retired(retired)),
+            // `retired` points to a record, which has layout guarantees regarding field ordering
+            // and the record's header is always first, so the retired record is stored in the
+            // header itself, because it is necessary for later reclamation
+            false => Header::from_retired(retired),
+        }
+    }
+
+    /// Pushes the record `custom`, which is reclaimed by a custom destructor,
+    /// into the queue with a separately allocated header.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure `custom` satisfies the usual invariants for
+    /// retiring.
+    #[inline]
+    pub unsafe fn retire_custom(&self, custom: Reclaimable, generation: usize, scan: usize) {
+        let header = Header::alloc_external(custom);
+        (*header).generation = generation;
+        (*header).scan = scan;
+        // the count must be increased before the record becomes reclaimable
+        self.pending.fetch_add(1, Ordering::Relaxed);
         self.raw.push(header);
     }
 
+    /// Retires an entire chain of records from `head` to `tail`, which are
+    /// already linked through their headers, at once.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure all headers in the chain have been initialized
+    /// with [`Header::from_retired`] (or
+    /// [`header_for`][RetiredQueue::header_for]) and are linked from `head` to
+    /// `tail`.
+    #[inline]
+    pub unsafe fn retire_chain(
+        &self,
+        head: *mut Header,
+        tail: *mut Header,
+        generation: usize,
+        scan: usize,
+    ) {
+        let mut curr = head;
+        let mut len = 1;
+        loop {
+            (*curr).generation = generation;
+            (*curr).scan = scan;
+            if curr == tail {
+                break;
+            }
+
+            curr = (*curr).next;
+            len += 1;
+        }
+
+        self.pending.fetch_add(len, Ordering::Relaxed);
+        self.raw.push_many((head, tail));
+    }
+
+    /// Returns an iterator over the address and generation of all records
+    /// currently in the queue, e.g. for counting the pending records per
+    /// address for diagnostics.
+    ///
+    /// The iterator takes all records out of the queue, which grants it
+    /// exclusive access to them, and pushes them back once it is dropped, so
+    /// records retired after it has been created are not visited.
+    /// The taken records can not be reclaimed by any thread until then.
     #[inline]
-    pub unsafe fn reclaim_all_unprotected(&self, protected: &[ProtectedPtr]) {
-        // take all retired records from the global queue
+    pub fn iter(&self) -> Iter<'_> {
+        let mut headers = Vec::new();
+        let mut curr = self.raw.take_all();
+        while !curr.is_null() {
+            headers.push(curr);
+            curr = unsafe { (*curr).next };
+        }
+
+        let back = headers.len();
+        Iter { queue: self, headers, front: 0, back }
+    }
+
+    /// Takes all records from the queue and returns the first one, which links
+    /// to all others.
+    #[inline]
+    pub fn take_all(&self) -> *mut Header {
+        self.raw.take_all()
+    }
+
+    /// Removes all records from the queue without reclaiming them, i.e. leaks
+    /// them.
+    #[inline]
+    pub fn leak_all(&mut self) {
+        let _ = self.raw.take_all();
+        *self.pending.get_mut() = 0;
+    }
+
+    /// Pushes the previously taken record `header` back into the queue.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure `header` has been taken from this queue and is
+    /// no longer linked to any other record.
+    #[inline]
+    unsafe fn requeue(&self, header: *mut Header) {
+        self.raw.push(header);
+    }
+
+    /// Reclaims the previously taken record `header`.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure `header` has been taken from this queue and is
+    /// no longer protected.
+    #[inline]
+    unsafe fn reclaim_taken(&self, header: *mut Header) {
+        #[cfg(feature = "track-retire-location")]
+        crate::location::forget(Header::address(header));
+
+        Header::reclaim(header);
+        self.pending.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Reclaims all records in the queue that are neither protected nor vetoed
+    /// by `can_reclaim` and returns the number of reclaimed records.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure `protected` is sorted and contains all
+    /// currently protected pointers.
+    #[inline]
+    pub unsafe fn reclaim_all_unprotected(
+        &self,
+        protected: &[ProtectedPtr],
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        self.reclaim_unless(|header| {
+            super::is_retained(protected, can_reclaim, Header::address(header))
+        })
+    }
+
+    /// Reclaims all records in the queue retired before `generation`, which
+    /// are neither protected nor vetoed by `can_reclaim`, and returns the
+    /// number of reclaimed records.
+    ///
+    /// # Safety
+    ///
+    /// See [`reclaim_all_unprotected`][RetiredQueue::reclaim_all_unprotected].
+    #[inline]
+    pub unsafe fn reclaim_older_than(
+        &self,
+        generation: usize,
+        protected: &[ProtectedPtr],
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        self.reclaim_unless(|header| {
+            (*header).generation >= generation
+                || super::is_retained(protected, can_reclaim, Header::address(header))
+        })
+    }
+
+    /// Reclaims all records in the queue retired before the `scan`-th global
+    /// scan, which are neither protected nor vetoed by `can_reclaim`, and
+    /// returns the number of reclaimed records.
+    ///
+    /// # Safety
+    ///
+    /// See [`reclaim_all_unprotected`][RetiredQueue::reclaim_all_unprotected].
+    #[inline]
+    pub unsafe fn reclaim_before_scan(
+        &self,
+        scan: usize,
+        protected: &[ProtectedPtr],
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        self.reclaim_unless(|header| {
+            (*header).scan >= scan
+                || super::is_retained(protected, can_reclaim, Header::address(header))
+        })
+    }
+
+    /// Reclaims at most `max` records in the queue, which are neither
+    /// protected according to `set` nor vetoed by `can_reclaim`, and returns
+    /// the number of reclaimed records.
+    ///
+    /// Before checking a record, which is not covered by `set` (see
+    /// [`ProtectedSet::covers`]), `refresh` is called for collecting the set
+    /// anew.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure `refresh` collects all currently protected
+    /// pointers into `set`.
+    #[inline]
+    pub unsafe fn reclaim_chunk(
+        &self,
+        max: usize,
+        set: &mut ProtectedSet,
+        can_reclaim: Option<fn(usize) -> bool>,
+        refresh: &mut impl FnMut(&mut ProtectedSet),
+    ) -> usize {
+        let mut remaining = max;
+        self.reclaim_unless(|header| {
+            // all records beyond the chunk's size are retained
+            if remaining == 0 {
+                return true;
+            }
+
+            if !set.covers((*header).generation) {
+                refresh(set);
+            }
+
+            let retain = super::is_retained(set.protected(), can_reclaim, Header::address(header));
+            if !retain {
+                remaining -= 1;
+            }
+
+            retain
+        })
+    }
+
+    /// Reclaims all records in the queue like
+    /// [`reclaim_all_unprotected`][RetiredQueue::reclaim_all_unprotected], but
+    /// collects the protected pointers into `scan_cache` in as many passes as
+    /// required, each collecting at most `capacity` of them, so the scan cache
+    /// never grows.
+    ///
+    /// If `older_than` is given, only records retired before this global scan
+    /// are reclaimed.
+    ///
+    /// # Safety
+    ///
+    /// See [`reclaim_all_unprotected`][RetiredQueue::reclaim_all_unprotected].
+    #[inline]
+    pub unsafe fn reclaim_in_passes(
+        &self,
+        (global, scan_cache, capacity): (&Global, &mut Vec<ProtectedPtr>, usize),
+        older_than: Option<usize>,
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        // records protected in any pass are set aside and only the remaining ones can be reclaimed
+        // once all hazards have been visited
         let mut curr = self.raw.take_all();
-        // these variables are used to create a simple inline linked list structure
-        // all records which can not be reclaimed are put back into this list and are
-        // eventually pushed back into the global queue.
-        let (mut first, mut last): (*mut Header, *mut Header) = (ptr::null_mut(), ptr::null_mut());
+        let mut retained = (ptr::null_mut(), ptr::null_mut());
+        let mut start = 0;
+        while !curr.is_null() {
+            let next = global.collect_protected_hazards_from(scan_cache, start, capacity);
+            scan_cache.sort_unstable_by_key(|protected| protected.address());
+
+            let mut unprotected = (ptr::null_mut(), ptr::null_mut());
+            while !curr.is_null() {
+                let following = (*curr).next;
+                match super::is_protected(scan_cache, Header::address(curr)) {
+                    true => append(&mut retained, curr),
+                    false => append(&mut unprotected, curr),
+                }
+
+                curr = following;
+            }
 
+            curr = unprotected.0;
+            match next {
+                Some(idx) => start = idx,
+                None => break,
+            }
+        }
+
+        self.reclaim_chain_unless(curr, retained, |header| {
+            older_than.map_or(false, |scan| (*header).scan >= scan)
+                || super::is_retained(&[], can_reclaim, Header::address(header))
+        })
+    }
+
+    /// Reclaims all records in the queue for which `retain` returns `false`
+    /// and returns the number of reclaimed records.
+    #[inline]
+    unsafe fn reclaim_unless(&self, retain: impl FnMut(*mut Header) -> bool) -> usize {
+        // take all retired records from the global queue
+        let curr = self.raw.take_all();
+        self.reclaim_chain_unless(curr, (ptr::null_mut(), ptr::null_mut()), retain)
+    }
+
+    /// Reclaims all records in the chain starting at `curr` for which `retain`
+    /// returns `false`, pushes all others back into the queue along with the
+    /// (possibly empty) list of already `retained` records and returns the
+    /// number of reclaimed records.
+    #[inline]
+    unsafe fn reclaim_chain_unless(
+        &self,
+        mut curr: *mut Header,
+        mut retained: (*mut Header, *mut Header),
+        mut retain: impl FnMut(*mut Header) -> bool,
+    ) -> usize {
+        let mut reclaimed = 0;
         // iterate all retired records and reclaim all which are no longer protected
         while !curr.is_null() {
-            let addr = curr as usize;
             let next = (*curr).next;
-            match protected.binary_search_by(|protected| protected.address().cmp(&addr)) {
-                // the record is still protected by some hazard pointer
-                Ok(_) => {
-                    // the next pointer must be zeroed since it may still point at some record
-                    // from the global queue
-                    (*curr).next = ptr::null_mut();
-                    if first.is_null() {
-                        first = curr;
-                        last = curr;
-                    } else {
-                        (*last).next = curr;
-                        last = curr;
-                    }
-                }
+            match retain(curr) {
+                // the record is still protected by some hazard pointer or must be retained otherwise
+                true => append(&mut retained, curr),
                 // the record can be reclaimed
-                Err(_) => (*curr).retired.take().unwrap().reclaim(),
+                false => {
+                    #[cfg(feature = "track-retire-location")]
+                    crate::location::forget(Header::address(curr));
+
+                    Header::reclaim(curr);
+                    reclaimed += 1;
+                }
             }
 
             curr = next;
         }
 
         // not all records were reclaimed, push all others back into the global queue in bulk.
-        if !first.is_null() {
-            self.raw.push_many((first, last));
+        if !retained.0.is_null() {
+            self.raw.push_many(retained);
+        }
+
+        self.pending.fetch_sub(reclaimed, Ordering::Relaxed);
+        reclaimed
+    }
+}
+
+/// Appends `header` to the simple inline linked `list` (first and last
+/// header) of records, which are eventually pushed back into the queue.
+#[inline]
+unsafe fn append(list: &mut (*mut Header, *mut Header), header: *mut Header) {
+    // the next pointer must be zeroed since it may still point at some record from the global
+    // queue
+    (*header).next = ptr::null_mut();
+    match list.0.is_null() {
+        true => *list = (header, header),
+        false => {
+            (*list.1).next = header;
+            list.1 = header;
+        }
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for RetiredQueue {
+    #[inline(never)]
+    fn drop(&mut self) {
+        // exclusive access guarantees that no records can be protected anymore
+        unsafe { self.reclaim_all_unprotected(&[], None) };
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Iter
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An iterator over the address and generation of the records taken from a
+/// [`RetiredQueue`], which are pushed back into the queue once it is dropped.
+///
+/// See [`RetiredQueue::iter`].
+#[derive(Debug)]
+pub(crate) struct Iter<'queue> {
+    queue: &'queue RetiredQueue,
+    /// The taken headers, which are still linked in this order.
+    headers: Vec<*mut Header>,
+    front: usize,
+    back: usize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Iter<'_> {
+    #[inline]
+    fn entry(&self, idx: usize) -> (usize, usize) {
+        // SAFETY: the headers have been taken from the queue and are hence exclusively owned by the
+        // iterator until it is dropped
+        let header = self.headers[idx];
+        unsafe { (Header::address(header), (*header).generation) }
+    }
+}
+
+/********** impl Iterator *************************************************************************/
+
+impl Iterator for Iter<'_> {
+    type Item = (usize, usize);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.front += 1;
+        Some(self.entry(self.front - 1))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+/********** impl DoubleEndedIterator **************************************************************/
+
+impl DoubleEndedIterator for Iter<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
         }
+
+        self.back -= 1;
+        Some(self.entry(self.back))
+    }
+}
+
+/********** impl ExactSizeIterator ****************************************************************/
+
+impl ExactSizeIterator for Iter<'_> {}
+
+/********** impl FusedIterator ********************************************************************/
+
+impl FusedIterator for Iter<'_> {}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Iter<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        // the headers are still linked, so the entire chain can be pushed back at once
+        if let (Some(&first), Some(&last)) = (self.headers.first(), self.headers.last()) {
+            unsafe { self.queue.raw.push_many((first, last)) };
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// ShardedQueue
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A fixed number of [`RetiredQueue`]s (shards) among which the retired records
+/// of all threads are distributed.
+///
+/// Each thread is assigned one shard (in round-robin order) into which it
+/// retires all of its records, which reduces the contention on each individual
+/// queue.
+/// Reclamation always drains all shards, so any thread can still reclaim any
+/// other thread's retired records.
+#[derive(Debug)]
+pub(crate) struct ShardedQueue {
+    shards: Box<[RetiredQueue]>,
+    /// If set, each shard belongs to a NUMA node and threads are assigned to
+    /// the shard of the node they are running on.
+    per_node: bool,
+    next_shard: AtomicUsize,
+    /// The number of records reclaimed by the most recent scan.
+    last_reclaimed: AtomicUsize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl ShardedQueue {
+    /// Creates a new [`ShardedQueue`] with `shards` empty queues.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is 0.
+    #[inline]
+    pub fn new(shards: usize) -> Self {
+        Self::with_shards(shards, false, RetiredQueue::new)
+    }
+
+    /// Creates a new [`ShardedQueue`] with one empty queue for each of the
+    /// `nodes` NUMA nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nodes` is 0.
+    #[inline]
+    pub fn per_node(nodes: usize) -> Self {
+        Self::with_shards(nodes, true, RetiredQueue::new)
+    }
+
+    /// Creates a new [`ShardedQueue`] with `shards` empty queues, which
+    /// allocate a separate header for every retired record.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is 0.
+    #[inline]
+    pub fn with_external_headers(shards: usize) -> Self {
+        Self::with_shards(shards, false, RetiredQueue::with_external_headers)
+    }
+
+    #[inline]
+    fn with_shards(shards: usize, per_node: bool, queue: fn() -> RetiredQueue) -> Self {
+        assert!(shards > 0, "at least one shard is required");
+        let shards: Vec<_> = (0..shards).map(|_| queue()).collect();
+        Self {
+            shards: shards.into_boxed_slice(),
+            per_node,
+            next_shard: AtomicUsize::new(0),
+            last_reclaimed: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of shards.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns `true` if all shards are empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(RetiredQueue::is_empty)
+    }
+
+    /// Returns the number of records currently pending in all shards.
+    #[inline]
+    pub fn pending(&self) -> usize {
+        self.shards.iter().map(RetiredQueue::pending).sum()
+    }
+
+    /// Returns the number of records reclaimed by the most recent scan.
+    #[inline]
+    pub fn last_reclaimed(&self) -> usize {
+        self.last_reclaimed.load(Ordering::Relaxed)
+    }
+
+    /// Returns the index of the shard the next thread should retire into.
+    #[inline]
+    pub fn assign_shard(&self) -> usize {
+        self.next_shard.fetch_add(1, Ordering::Relaxed) % self.shards.len()
+    }
+
+    /// Returns the index of the shard a new thread should retire into.
+    ///
+    /// If the queue has been created [per node][ShardedQueue::per_node], this
+    /// is the shard of the given NUMA `node` hint or of the node the calling
+    /// thread is running on, falling back to the round-robin assignment if
+    /// neither is known.
+    /// Otherwise, the `node` hint is ignored.
+    #[inline]
+    pub fn assign_shard_for_node(&self, node: Option<usize>) -> usize {
+        match node.or_else(numa::current_node).filter(|_| self.per_node) {
+            Some(node) => node % self.shards.len(),
+            None => self.assign_shard(),
+        }
+    }
+
+    /// Returns the shard with the given `idx`.
+    #[inline]
+    pub fn shard(&self, idx: usize) -> &RetiredQueue {
+        &self.shards[idx]
+    }
+
+    /// Reclaims all records in all shards and resets the round-robin shard
+    /// assignment.
+    #[inline]
+    pub fn reset(&mut self) {
+        // exclusive access guarantees that no records can be protected anymore
+        unsafe { self.reclaim_all_unprotected(&[], None) };
+        *self.next_shard.get_mut() = 0;
+        *self.last_reclaimed.get_mut() = 0;
+    }
+
+    /// Removes all records from all shards without reclaiming them, i.e. leaks
+    /// them.
+    #[inline]
+    pub fn leak_all(&mut self) {
+        self.shards.iter_mut().for_each(RetiredQueue::leak_all);
+    }
+
+    /// Reclaims all unprotected records in all shards and returns the number
+    /// of reclaimed records.
+    ///
+    /// # Safety
+    ///
+    /// See [`RetiredQueue::reclaim_all_unprotected`].
+    #[inline]
+    pub unsafe fn reclaim_all_unprotected(
+        &self,
+        protected: &[ProtectedPtr],
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        self.reclaim_all_unprotected_from(0, protected, can_reclaim)
+    }
+
+    /// Reclaims all unprotected records in all shards starting with the shard
+    /// at index `first` (e.g. the calling thread's own NUMA node) and returns
+    /// the number of reclaimed records.
+    ///
+    /// # Safety
+    ///
+    /// See [`RetiredQueue::reclaim_all_unprotected`].
+    #[inline]
+    pub unsafe fn reclaim_all_unprotected_from(
+        &self,
+        first: usize,
+        protected: &[ProtectedPtr],
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        let len = self.shards.len();
+        let reclaimed = (0..len)
+            .map(|idx| {
+                self.shards[(first + idx) % len].reclaim_all_unprotected(protected, can_reclaim)
+            })
+            .sum();
+
+        self.record_scan(reclaimed)
+    }
+
+    /// Reclaims all records in all shards retired before `generation`, which
+    /// are neither protected nor vetoed by `can_reclaim`, and returns the
+    /// number of reclaimed records.
+    ///
+    /// # Safety
+    ///
+    /// See [`RetiredQueue::reclaim_all_unprotected`].
+    #[inline]
+    pub unsafe fn reclaim_older_than(
+        &self,
+        generation: usize,
+        protected: &[ProtectedPtr],
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        let reclaimed = self
+            .shards
+            .iter()
+            .map(|shard| shard.reclaim_older_than(generation, protected, can_reclaim))
+            .sum();
+
+        self.record_scan(reclaimed)
+    }
+
+    /// Reclaims all records in all shards retired before the `scan`-th global
+    /// scan, see [`RetiredQueue::reclaim_before_scan`].
+    ///
+    /// # Safety
+    ///
+    /// See [`RetiredQueue::reclaim_all_unprotected`].
+    #[inline]
+    pub unsafe fn reclaim_before_scan(
+        &self,
+        scan: usize,
+        protected: &[ProtectedPtr],
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        let reclaimed = self
+            .shards
+            .iter()
+            .map(|shard| shard.reclaim_before_scan(scan, protected, can_reclaim))
+            .sum();
+
+        self.record_scan(reclaimed)
+    }
+
+    /// Reclaims all unprotected records in all shards starting with the shard
+    /// at index `first` using a scan cache of fixed `capacity`, see
+    /// [`RetiredQueue::reclaim_in_passes`].
+    ///
+    /// # Safety
+    ///
+    /// See [`RetiredQueue::reclaim_all_unprotected`].
+    #[inline]
+    pub unsafe fn reclaim_in_passes_from(
+        &self,
+        first: usize,
+        (global, scan_cache, capacity): (&Global, &mut Vec<ProtectedPtr>, usize),
+        older_than: Option<usize>,
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        let len = self.shards.len();
+        let reclaimed = (0..len)
+            .map(|idx| {
+                let shard = &self.shards[(first + idx) % len];
+                shard.reclaim_in_passes(
+                    (global, &mut *scan_cache, capacity),
+                    older_than,
+                    can_reclaim,
+                )
+            })
+            .sum();
+
+        self.record_scan(reclaimed)
+    }
+
+    /// Reclaims at most `max` records from all shards, see
+    /// [`RetiredQueue::reclaim_chunk`].
+    ///
+    /// # Safety
+    ///
+    /// See [`RetiredQueue::reclaim_chunk`].
+    #[inline]
+    pub unsafe fn reclaim_chunk(
+        &self,
+        max: usize,
+        set: &mut ProtectedSet,
+        can_reclaim: Option<fn(usize) -> bool>,
+        refresh: &mut impl FnMut(&mut ProtectedSet),
+    ) -> usize {
+        let mut reclaimed = 0;
+        for shard in self.shards.iter() {
+            if reclaimed == max {
+                break;
+            }
+
+            reclaimed += shard.reclaim_chunk(max - reclaimed, set, can_reclaim, refresh);
+        }
+
+        self.record_scan(reclaimed)
+    }
+
+    /// Records the number of records `reclaimed` by the most recent scan and
+    /// returns it.
+    #[inline]
+    fn record_scan(&self, reclaimed: usize) -> usize {
+        self.last_reclaimed.store(reclaimed, Ordering::Relaxed);
+        reclaimed
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Taken
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// All records taken from the global queue at once, see
+/// [`Hp::take_retired`][crate::Hp::take_retired].
+///
+/// The protected hazards are collected right after the records have been
+/// taken, so iterating the [`Taken`] records yields each record along with
+/// whether it is protected, which allows implementing custom reclamation
+/// policies (e.g. logging or rate limiting).
+/// All records that are neither reclaimed nor explicitly requeued are pushed
+/// back into the global queue when they (or the [`Taken`] records) are
+/// dropped.
+#[derive(Debug)]
+pub struct Taken<'global> {
+    queue: &'global ShardedQueue,
+    /// The remaining taken records of each shard.
+    lists: Vec<*mut Header>,
+    /// The index of the shard currently being iterated.
+    shard: usize,
+    protected: Vec<ProtectedPtr>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<'global> Taken<'global> {
+    /// Takes all records from all shards of `queue`, calling `collect` for
+    /// collecting the currently protected hazards afterwards.
+    #[inline]
+    pub(crate) fn new(
+        queue: &'global ShardedQueue,
+        collect: impl FnOnce(&mut Vec<ProtectedPtr>),
+    ) -> Self {
+        let lists = queue.shards.iter().map(RetiredQueue::take_all).collect();
+        // the records are no longer reachable when they are retired, so the collected hazards are
+        // complete for all of them
+        let mut protected = Vec::new();
+        collect(&mut protected);
+        protected.sort_unstable_by_key(|protected| protected.address());
+
+        Self { queue, lists, shard: 0, protected }
+    }
+}
+
+/********** impl Iterator *************************************************************************/
+
+impl<'global> Iterator for Taken<'global> {
+    type Item = TakenRecord<'global>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.shard < self.lists.len() {
+            let header = self.lists[self.shard];
+            if header.is_null() {
+                self.shard += 1;
+                continue;
+            }
+
+            unsafe {
+                self.lists[self.shard] = (*header).next;
+                (*header).next = ptr::null_mut();
+            }
+
+            return Some(TakenRecord {
+                shard: self.queue.shard(self.shard),
+                header,
+                protected: super::is_protected(&self.protected, unsafe { Header::address(header) }),
+            });
+        }
+
+        None
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for Taken<'_> {
+    #[inline(never)]
+    fn drop(&mut self) {
+        // all remaining records are requeued by dropping them
+        self.for_each(drop);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// TakenRecord
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A single record of the [`Taken`] records, which is requeued when dropped,
+/// unless it has been reclaimed.
+#[derive(Debug)]
+pub struct TakenRecord<'global> {
+    shard: &'global RetiredQueue,
+    header: *mut Header,
+    protected: bool,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl TakenRecord<'_> {
+    /// Returns the address of the record.
+    #[inline]
+    pub fn address(&self) -> usize {
+        unsafe { Header::address(self.header) }
+    }
+
+    /// Returns the generation in which the record was retired.
+    #[inline]
+    pub fn generation(&self) -> usize {
+        unsafe { (*self.header).generation }
+    }
+
+    /// Returns `true` if the record was protected when it was taken.
+    #[inline]
+    pub fn is_protected(&self) -> bool {
+        self.protected
+    }
+
+    /// Reclaims the record.
+    ///
+    /// # Errors
+    ///
+    /// Fails and returns the record, if it was protected when it was taken.
+    #[inline]
+    pub fn reclaim(self) -> Result<(), Self> {
+        if self.protected {
+            return Err(self);
+        }
+
+        unsafe { self.shard.reclaim_taken(self.header) };
+        mem::forget(self);
+        Ok(())
+    }
+
+    /// Pushes the record back into the global queue, which is equivalent to
+    /// dropping it.
+    #[inline]
+    pub fn requeue(self) {}
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl Drop for TakenRecord<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { self.shard.requeue(self.header) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+    use std::ptr::NonNull;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    use conquer_reclaim::RawRetired;
+
+    use crate::retire::{GlobalRetireState, Reclaimable};
+    use crate::test_util::{retired, DropCount};
+    use crate::{GlobalRetire, Hp};
+
+    use super::{Header, RetiredQueue, ShardedQueue};
+
+    #[test]
+    fn default_single_shard() {
+        let hp = Hp::<GlobalRetire>::default();
+        match &hp.state.retire_state {
+            GlobalRetireState::GlobalStrategy(queue) => assert_eq!(queue.len(), 1),
+            _ => panic!("invalid retire state"),
+        }
+    }
+
+    #[test]
+    fn header_size() {
+        // only external headers can hold custom destructors, so the embedded header does not grow
+        let size = 3 * mem::size_of::<usize>() + mem::size_of::<RawRetired>();
+        assert_eq!(mem::size_of::<Header>(), size);
+    }
+
+    #[test]
+    fn external_headers() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        unsafe fn destroy(ptr: NonNull<DropCount>) {
+            drop(Box::from_raw(ptr.as_ptr()));
+        }
+
+        let queue = RetiredQueue::with_external_headers();
+        let retired = retired(&COUNT);
+        let address = retired.address();
+        let custom = NonNull::from(Box::leak(Box::new(DropCount(&COUNT))));
+        unsafe {
+            queue.retire(retired, 0, 0);
+            queue.retire_custom(Reclaimable::custom(custom, destroy), 1, 0);
+        }
+
+        let visited: Vec<_> = queue.iter().collect();
+        assert_eq!(visited, [(custom.as_ptr() as usize, 1), (address, 0)]);
+        assert_eq!(unsafe { queue.reclaim_all_unprotected(&[], None) }, 2);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn assign_shard_for_node() {
+        // nodes are mapped to the shard with the same index (modulo the number of shards)
+        let queue = ShardedQueue::per_node(4);
+        let shards: Vec<_> = (0..8).map(|node| queue.assign_shard_for_node(Some(node))).collect();
+        assert_eq!(shards, [0, 1, 2, 3, 0, 1, 2, 3]);
+
+        // node hints are ignored by queues that are not per node
+        let queue = ShardedQueue::new(4);
+        assert_eq!(queue.assign_shard_for_node(Some(3)), 0);
+        assert_eq!(queue.assign_shard_for_node(Some(3)), 1);
+        assert_eq!(queue.assign_shard_for_node(None), 2);
+    }
+
+    #[test]
+    fn iter() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let queue = RetiredQueue::new();
+        assert!(queue.iter().next().is_none());
+
+        let addresses: Vec<_> = (0..4)
+            .map(|generation| {
+                let retired = retired(&COUNT);
+                let address = retired.address();
+                unsafe { queue.retire(retired, generation, 0) };
+                address
+            })
+            .collect();
+
+        // records are visited in reverse order of their insertion and can be visited from both ends
+        let mut iter = queue.iter();
+        assert_eq!(iter.len(), 4);
+        assert!(queue.is_empty());
+        assert_eq!(iter.next_back(), Some((addresses[0], 0)));
+        let visited: Vec<_> = iter.by_ref().collect();
+        let expected: Vec<_> = addresses.iter().copied().zip(0..4).skip(1).rev().collect();
+        assert_eq!(visited, expected);
+        assert_eq!(iter.len(), 0);
+        assert!(iter.next().is_none());
+        drop(iter);
+
+        // iterating does not remove any records
+        assert_eq!(queue.pending(), 4);
+        assert_eq!(queue.iter().rev().map(|(addr, _)| addr).collect::<Vec<_>>(), addresses);
+        assert_eq!(unsafe { queue.reclaim_all_unprotected(&[], None) }, 4);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn reclaim_all_shards() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let queue = ShardedQueue::new(4);
+        for _ in 0..4 {
+            let shard = queue.assign_shard();
+            for _ in 0..16 {
+                unsafe { queue.shard(shard).retire(retired(&COUNT), 0, 0) };
+            }
+        }
+
+        assert!((0..4).all(|idx| !queue.shard(idx).is_empty()));
+
+        assert_eq!(unsafe { queue.reclaim_all_unprotected(&[], None) }, 64);
+        assert!(queue.is_empty());
+        assert_eq!(COUNT.load(Ordering::Relaxed), 64);
+    }
+
+    #[test]
+    fn retire_across_threads() {
+        const THREADS: usize = 4;
+        const PER_THREAD: usize = 256;
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Arc::new(Hp::<GlobalRetire>::default());
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let hp = Arc::clone(&hp);
+                thread::spawn(move || {
+                    let local = hp.build_local(None);
+                    for _ in 0..PER_THREAD {
+                        local.retire(retired(&COUNT));
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // records retired by other threads may be reclaimed by any thread
+        hp.reclaim_until_quiescent(1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), THREADS * PER_THREAD);
     }
 }
@@ -1,6 +1,6 @@
-use core::cmp;
 use core::mem;
 use core::ptr;
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 cfg_if::cfg_if! {
     if #[cfg(not(feature = "std"))] {
@@ -14,6 +14,8 @@ use conquer_reclaim::RawRetired;
 use crate::hazard::ProtectedPtr;
 use crate::queue::{RawNode, RawQueue};
 
+use super::Reclaimable;
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // RetireNode
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -21,6 +23,8 @@ use crate::queue::{RawNode, RawQueue};
 #[derive(Debug)]
 pub(crate) struct RetireNode {
     vec: Vec<ReclaimOnDrop>,
+    /// The fixed capacity of a bounded node, which never grows beyond it.
+    ring_capacity: Option<usize>,
     next: *mut Self,
 }
 
@@ -29,6 +33,35 @@ pub(crate) struct RetireNode {
 impl RetireNode {
     const DEFAULT_INITIAL_CAPACITY: usize = 128;
 
+    /// Creates a new bounded [`RetireNode`], which allocates storage for
+    /// exactly `capacity` records up front.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    #[inline]
+    pub fn with_ring_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0, "the retire ring capacity must be at least 1");
+        Self {
+            vec: Vec::with_capacity(capacity),
+            ring_capacity: Some(capacity),
+            next: ptr::null_mut(),
+        }
+    }
+
+    /// Returns `true` if the node is bounded.
+    #[inline]
+    pub fn is_bounded(&self) -> bool {
+        self.ring_capacity.is_some()
+    }
+
+    /// Returns `true` if the node is bounded and has no room for any further
+    /// records.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.ring_capacity.map_or(false, |capacity| self.vec.len() >= capacity)
+    }
+
     #[inline]
     pub fn into_inner(self) -> Vec<ReclaimOnDrop> {
         self.vec
@@ -39,6 +72,23 @@ impl RetireNode {
         self.vec.is_empty()
     }
 
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns the number of bytes allocated for the node and its buffer.
+    #[inline]
+    pub fn allocated_bytes(&self) -> usize {
+        mem::size_of::<Self>() + self.vec.capacity() * mem::size_of::<ReclaimOnDrop>()
+    }
+
+    /// Takes all records out of the node, leaving it empty.
+    #[inline]
+    pub fn take_records(&mut self) -> Vec<ReclaimOnDrop> {
+        mem::take(&mut self.vec)
+    }
+
     #[inline]
     pub fn merge(&mut self, mut other: Vec<ReclaimOnDrop>) {
         if (other.capacity() - other.len()) > self.vec.capacity() {
@@ -48,17 +98,185 @@ impl RetireNode {
         self.vec.append(&mut other);
     }
 
+    /// Splits off half of the records into a new node, if the node holds more
+    /// than `threshold` records.
+    #[inline]
+    pub fn spill(&mut self, threshold: usize) -> Option<Box<Self>> {
+        let len = self.vec.len();
+        if len <= threshold {
+            return None;
+        }
+
+        let vec = self.vec.split_off(len / 2);
+        Some(Box::new(Self { vec, ring_capacity: None, next: ptr::null_mut() }))
+    }
+
+    /// Moves all records into a new unbounded node, keeping the node's buffer
+    /// capacity.
+    #[inline]
+    pub fn spill_all(&mut self) -> Box<Self> {
+        let vec = mem::replace(&mut self.vec, Vec::with_capacity(self.vec.capacity()));
+        Box::new(Self { vec, ring_capacity: None, next: ptr::null_mut() })
+    }
+
+    /// Retires `retired` in the given `generation` after the owning thread's
+    /// `scan`-th scan.
     #[inline]
-    pub unsafe fn retire(&mut self, retired: RawRetired) {
-        self.vec.push(ReclaimOnDrop::new(retired));
+    pub unsafe fn retire(&mut self, retired: RawRetired, generation: usize, scan: usize) {
+        self.push(ReclaimOnDrop::new(Reclaimable::Retired(retired), generation, scan));
     }
 
+    /// Retires `retired`, which may be reclaimed by a custom destructor, in
+    /// the given `generation` after the owning thread's `scan`-th scan.
     #[inline]
-    pub unsafe fn reclaim_all_unprotected(&mut self, protected: &[ProtectedPtr]) {
+    pub unsafe fn retire_reclaimable(
+        &mut self,
+        retired: Reclaimable,
+        generation: usize,
+        scan: usize,
+    ) {
+        self.push(ReclaimOnDrop::new(retired, generation, scan));
+    }
+
+    /// Sets the generation of all records in the node, e.g. when a batch of
+    /// records is handed over to a thread local state.
+    #[inline]
+    pub fn set_generation(&mut self, generation: usize) {
+        self.vec.iter_mut().for_each(|retired| retired.generation = generation);
+    }
+
+    /// Sets the scan count of all records in the node, e.g. when a batch of
+    /// records is handed over to a thread local state.
+    #[inline]
+    pub fn set_scan(&mut self, scan: usize) {
+        self.vec.iter_mut().for_each(|retired| retired.scan = scan);
+    }
+
+    /// Replaces the scan count of all records by their age, i.e. the number of
+    /// scans the owning thread has performed since (up to its current count
+    /// `scan`), before the node is abandoned.
+    ///
+    /// Each thread counts its scans separately, so only the age of a record is
+    /// meaningful to an adopting thread.
+    #[inline]
+    pub fn abandon_scans(&mut self, scan: usize) {
+        self.vec.iter_mut().for_each(|retired| retired.scan = scan.saturating_sub(retired.scan));
+    }
+
+    /// Replaces the age of all records in an abandoned node by the scan count
+    /// of the adopting thread (with the current count `scan`), in which they
+    /// would have been retired, see [`abandon_scans`][RetireNode::abandon_scans].
+    #[inline]
+    pub fn adopt_scans(&mut self, scan: usize) {
+        self.vec.iter_mut().for_each(|retired| retired.scan = scan.saturating_sub(retired.scan));
+    }
+
+    #[inline]
+    pub fn push(&mut self, record: ReclaimOnDrop) {
+        debug_assert!(!self.is_full(), "bounded retire node must not grow");
+        self.vec.push(record);
+    }
+
+    /// Reclaims all records that are neither protected nor vetoed by
+    /// `can_reclaim` and returns the number of reclaimed records.
+    #[inline]
+    pub unsafe fn reclaim_all_unprotected(
+        &mut self,
+        protected: &[ProtectedPtr],
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        let len = self.vec.len();
+        // if no pointers are protected at all, all records can be reclaimed right away
+        if protected.is_empty() && can_reclaim.is_none() {
+            self.vec.clear();
+            return len;
+        }
+
         self.vec.retain(|retired| {
             // retain (i.e. DON'T drop) all records found within the scan cache of protected hazards
-            protected.binary_search_by(|&protected| retired.compare_with(protected)).is_ok()
+            // or vetoed by the callback
+            super::is_retained(protected, can_reclaim, retired.address())
+        });
+
+        len - self.vec.len()
+    }
+
+    /// Moves all records following the first `retained` ones, which are
+    /// protected by any of the (sorted) `protected` pointers, to the front and
+    /// returns the new number of retained records.
+    ///
+    /// This allows checking the records against the protected pointers in
+    /// multiple passes, if they can not be collected all at once.
+    #[inline]
+    pub fn retain_protected(&mut self, mut retained: usize, protected: &[ProtectedPtr]) -> usize {
+        for idx in retained..self.vec.len() {
+            if super::is_protected(protected, self.vec[idx].address()) {
+                self.vec.swap(retained, idx);
+                retained += 1;
+            }
+        }
+
+        retained
+    }
+
+    /// Reclaims all records following the first `retained` ones, which were
+    /// retired before the scan `older_than`, if given, and are not vetoed by
+    /// `can_reclaim`, and returns the number of reclaimed records.
+    #[inline]
+    pub unsafe fn reclaim_unretained(
+        &mut self,
+        retained: usize,
+        older_than: Option<usize>,
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        let len = self.vec.len();
+        let mut idx = 0;
+        self.vec.retain(|retired| {
+            let keep = idx < retained
+                || older_than.map_or(false, |scan| retired.scan >= scan)
+                || super::is_retained(&[], can_reclaim, retired.address());
+            idx += 1;
+            keep
+        });
+
+        len - self.vec.len()
+    }
+
+    /// Reclaims all records retired before `generation`, which are neither
+    /// protected nor vetoed by `can_reclaim`, and returns the number of
+    /// reclaimed records.
+    #[inline]
+    pub unsafe fn reclaim_older_than(
+        &mut self,
+        generation: usize,
+        protected: &[ProtectedPtr],
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        let len = self.vec.len();
+        self.vec.retain(|retired| {
+            retired.generation >= generation
+                || super::is_retained(protected, can_reclaim, retired.address())
+        });
+
+        len - self.vec.len()
+    }
+
+    /// Reclaims all records retired before the owning thread's `scan`-th
+    /// scan, which are neither protected nor vetoed by `can_reclaim`, and
+    /// returns the number of reclaimed records.
+    #[inline]
+    pub unsafe fn reclaim_before_scan(
+        &mut self,
+        scan: usize,
+        protected: &[ProtectedPtr],
+        can_reclaim: Option<fn(usize) -> bool>,
+    ) -> usize {
+        let len = self.vec.len();
+        self.vec.retain(|retired| {
+            retired.scan >= scan || super::is_retained(protected, can_reclaim, retired.address())
         });
+
+        len - self.vec.len()
     }
 }
 
@@ -67,7 +285,11 @@ impl RetireNode {
 impl Default for RetireNode {
     #[inline]
     fn default() -> Self {
-        Self { vec: Vec::with_capacity(Self::DEFAULT_INITIAL_CAPACITY), next: ptr::null_mut() }
+        Self {
+            vec: Vec::with_capacity(Self::DEFAULT_INITIAL_CAPACITY),
+            ring_capacity: None,
+            next: ptr::null_mut(),
+        }
     }
 }
 
@@ -89,9 +311,18 @@ impl RawNode for RetireNode {
 // AbandonedQueue
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// The maximum number of empty nodes kept for re-use by later threads.
+pub(crate) const SPARE_NODES: usize = 4;
+
 #[derive(Debug, Default)]
 pub(crate) struct AbandonedQueue {
     raw: RawQueue<RetireNode>,
+    /// Empty nodes of exited threads, which new threads can re-use instead of
+    /// allocating.
+    spare: RawQueue<RetireNode>,
+    spare_count: AtomicUsize,
+    /// The number of bytes allocated for all abandoned and spare nodes.
+    allocated_bytes: AtomicUsize,
 }
 
 /********** impl inherent *************************************************************************/
@@ -99,15 +330,90 @@ pub(crate) struct AbandonedQueue {
 impl AbandonedQueue {
     #[inline]
     pub const fn new() -> Self {
-        Self { raw: RawQueue::new() }
+        Self {
+            raw: RawQueue::new(),
+            spare: RawQueue::new(),
+            spare_count: AtomicUsize::new(0),
+            allocated_bytes: AtomicUsize::new(0),
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.raw.is_empty()
     }
 
+    /// Returns the number of bytes allocated for all nodes currently in the
+    /// queue, including spare nodes.
     #[inline]
-    pub fn push(&self, node: Box<RetireNode>) {
+    pub fn allocated_bytes(&self) -> usize {
+        self.allocated_bytes.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn push(&self, mut node: Box<RetireNode>) {
+        // abandoned records are no longer subject to their former thread's bound
+        node.ring_capacity = None;
+        self.allocated_bytes.fetch_add(node.allocated_bytes(), Ordering::Relaxed);
         let node = Box::leak(node);
         unsafe { self.raw.push(node) };
     }
 
+    /// Stores the empty `node` for re-use by a later thread or de-allocates
+    /// it, if there are already enough spare nodes.
+    #[inline]
+    pub fn push_spare(&self, mut node: Box<RetireNode>) {
+        debug_assert!(node.is_empty());
+        let mut count = self.spare_count.load(Ordering::Relaxed);
+        loop {
+            if count >= SPARE_NODES {
+                return;
+            }
+
+            match self.spare_count.compare_exchange_weak(
+                count,
+                count + 1,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(curr) => count = curr,
+            }
+        }
+
+        node.ring_capacity = None;
+        self.allocated_bytes.fetch_add(node.allocated_bytes(), Ordering::Relaxed);
+        unsafe { self.spare.push(Box::into_raw(node)) };
+    }
+
+    /// Pops an empty spare node or returns `None`, if there are none.
+    #[inline]
+    pub fn pop_spare(&self) -> Option<Box<RetireNode>> {
+        let head = self.spare.take_all();
+        if head.is_null() {
+            return None;
+        }
+
+        unsafe {
+            // all remaining spare nodes are pushed back in bulk
+            let next = RetireNode::next(head);
+            if !next.is_null() {
+                let mut last = next;
+                while !RetireNode::next(last).is_null() {
+                    last = RetireNode::next(last);
+                }
+
+                self.spare.push_many((next, last));
+            }
+
+            RetireNode::set_next(head, ptr::null_mut());
+            self.spare_count.fetch_sub(1, Ordering::Relaxed);
+            let node = Box::from_raw(head);
+            self.allocated_bytes.fetch_sub(node.allocated_bytes(), Ordering::Relaxed);
+            Some(node)
+        }
+    }
+
     #[inline]
     pub fn take_all_and_merge(&self) -> Option<Box<RetireNode>> {
         unsafe {
@@ -115,13 +421,18 @@ impl AbandonedQueue {
                 ptr if ptr.is_null() => None,
                 ptr => {
                     let mut boxed = Box::from_raw(ptr);
+                    let mut taken = boxed.allocated_bytes();
                     let mut curr = boxed.next;
                     while !curr.is_null() {
-                        let RetireNode { vec: container, next } = *Box::from_raw(curr);
+                        let node = Box::from_raw(curr);
+                        taken += node.allocated_bytes();
+                        let RetireNode { vec: container, next, .. } = *node;
                         boxed.merge(container);
                         curr = next;
                     }
 
+                    self.allocated_bytes.fetch_sub(taken, Ordering::Relaxed);
+
                     Some(boxed)
                 }
             }
@@ -129,24 +440,48 @@ impl AbandonedQueue {
     }
 }
 
+/********** impl Drop *****************************************************************************/
+
+impl Drop for AbandonedQueue {
+    #[inline(never)]
+    fn drop(&mut self) {
+        // dropping the merged node drops all of its `ReclaimOnDrop` elements, which reclaims all
+        // remaining abandoned records
+        let _ = self.take_all_and_merge();
+
+        let mut curr = self.spare.take_all();
+        while !curr.is_null() {
+            let node = unsafe { Box::from_raw(curr) };
+            curr = node.next;
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // ReclaimOnDrop
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 #[derive(Debug)]
-pub(crate) struct ReclaimOnDrop(RawRetired);
+pub(crate) struct ReclaimOnDrop {
+    retired: Reclaimable,
+    /// The generation in which the record was retired.
+    generation: usize,
+    /// The number of scans the owning thread had performed when the record was
+    /// retired (or adopted).
+    scan: usize,
+}
 
 /********** impl inherent *************************************************************************/
 
 impl ReclaimOnDrop {
     #[inline]
-    unsafe fn new(retired: RawRetired) -> Self {
-        Self(retired)
+    unsafe fn new(retired: Reclaimable, generation: usize, scan: usize) -> Self {
+        Self { retired, generation, scan }
     }
 
     #[inline]
-    fn compare_with(&self, protected: ProtectedPtr) -> cmp::Ordering {
-        protected.address().cmp(&self.0.address())
+    fn address(&self) -> usize {
+        self.retired.address()
     }
 }
 
@@ -155,6 +490,9 @@ impl ReclaimOnDrop {
 impl Drop for ReclaimOnDrop {
     #[inline(always)]
     fn drop(&mut self) {
-        unsafe { self.0.reclaim() };
+        #[cfg(feature = "track-retire-location")]
+        crate::location::forget(self.retired.address());
+
+        unsafe { self.retired.reclaim() };
     }
 }
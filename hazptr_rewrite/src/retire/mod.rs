@@ -1,14 +1,133 @@
+pub(crate) mod channel;
 pub(crate) mod global_retire;
 pub(crate) mod local_retire;
 
-use self::global_retire::RetiredQueue;
+use core::mem;
+use core::ptr::NonNull;
+
+use conquer_reclaim::RawRetired;
+
+use crate::hazard::ProtectedPtr;
+
+use self::global_retire::ShardedQueue;
 use self::local_retire::{AbandonedQueue, RetireNode};
 
+/// All addresses below this bound lie within the first page of the address
+/// space, which is never mapped and hence never returned by an allocator.
+const DANGLING_BOUND: usize = 4096;
+
+/// Returns `true` if `addr` is a dangling (i.e. well-aligned but never
+/// allocated) address, as used for zero-sized records.
+///
+/// Zero-sized records do not occupy any memory, so all of them share the
+/// same few addresses (their alignment) and may even be indistinguishable
+/// from the sentinel values of hazard pointers.
+/// Since there is no memory that could be accessed after reclamation, they
+/// never need to be protected.
+#[inline]
+pub(crate) fn is_dangling(addr: usize) -> bool {
+    addr < DANGLING_BOUND
+}
+
+/// Returns `true` if the record at `addr` is contained in the sorted slice of
+/// `protected` pointers.
+///
+/// Dangling addresses of zero-sized records are never protected (see
+/// [`is_dangling`]), even if some hazard pointer holds the same address.
+#[inline]
+pub(crate) fn is_protected(protected: &[ProtectedPtr], addr: usize) -> bool {
+    // if no pointers are protected at all, no search is required
+    if protected.is_empty() || is_dangling(addr) {
+        return false;
+    }
+
+    protected.binary_search_by(|protected| protected.address().cmp(&addr)).is_ok()
+}
+
+/// Returns `true` if the record at `addr` must be retained, because it is
+/// either protected or its reclamation is vetoed by the `can_reclaim` callback
+/// (see [`Config::can_reclaim`][crate::Config::can_reclaim]).
+#[inline]
+pub(crate) fn is_retained(
+    protected: &[ProtectedPtr],
+    can_reclaim: Option<fn(usize) -> bool>,
+    addr: usize,
+) -> bool {
+    is_protected(protected, addr) || can_reclaim.map_or(false, |can_reclaim| !can_reclaim(addr))
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Reclaimable
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A retired record, which is reclaimed either through its `Drop` glue or by
+/// a custom destructor.
+#[derive(Debug)]
+pub(crate) enum Reclaimable {
+    Retired(RawRetired),
+    Custom { ptr: NonNull<()>, destructor: unsafe fn(NonNull<()>) },
+}
+
+/********** impl inherent *************************************************************************/
+
+impl Reclaimable {
+    /// Creates a new [`Reclaimable`] for the record at `ptr`, which is
+    /// reclaimed by calling `destructor`.
+    #[inline]
+    pub fn custom<T>(ptr: NonNull<T>, destructor: unsafe fn(NonNull<T>)) -> Self {
+        // SAFETY: `NonNull<T>` and `NonNull<()>` are ABI compatible (both are thin pointers)
+        let destructor =
+            unsafe { mem::transmute::<unsafe fn(NonNull<T>), unsafe fn(NonNull<()>)>(destructor) };
+
+        Reclaimable::Custom { ptr: ptr.cast(), destructor }
+    }
+
+    /// Returns the address of the record.
+    #[inline]
+    pub fn address(&self) -> usize {
+        match self {
+            Reclaimable::Retired(retired) => retired.address(),
+            Reclaimable::Custom { ptr, .. } => ptr.as_ptr() as usize,
+        }
+    }
+
+    /// Reclaims the record.
+    ///
+    /// # Safety
+    ///
+    /// The record must be reclaimed at most once.
+    #[inline]
+    pub unsafe fn reclaim(&mut self) {
+        match self {
+            Reclaimable::Retired(retired) => retired.reclaim(),
+            Reclaimable::Custom { ptr, destructor } => destructor(*ptr),
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // RetireStrategy (trait)
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-pub trait RetireStrategy: Sized + 'static {}
+pub trait RetireStrategy: Sized + 'static {
+    /// The kind of the strategy, for branching on it at runtime.
+    const KIND: StrategyKind;
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// StrategyKind
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The runtime representation of a [`RetireStrategy`], see
+/// [`Hp::strategy_kind`][crate::Hp::strategy_kind].
+#[derive(Copy, Clone, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub enum StrategyKind {
+    /// All retired records are stored in a global queue ([`GlobalRetire`] or
+    /// [`GlobalRetireExternal`]).
+    Global,
+    /// Each thread stores its retired records locally ([`LocalRetire`]).
+    Local,
+}
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // GlobalRetire
@@ -19,7 +138,27 @@ pub struct GlobalRetire;
 
 /********** impl RetireStrategy *******************************************************************/
 
-impl RetireStrategy for GlobalRetire {}
+impl RetireStrategy for GlobalRetire {
+    const KIND: StrategyKind = StrategyKind::Global;
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// GlobalRetireExternal
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The global retire strategy with externally allocated headers.
+///
+/// Records require no embedded header, instead a small header is allocated
+/// separately for each record when it is retired, which trades an additional
+/// allocation per retired record for an unconstrained record layout.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct GlobalRetireExternal;
+
+/********** impl RetireStrategy *******************************************************************/
+
+impl RetireStrategy for GlobalRetireExternal {
+    const KIND: StrategyKind = StrategyKind::Global;
+}
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // GlobalRetireState
@@ -27,20 +166,51 @@ impl RetireStrategy for GlobalRetire {}
 
 #[derive(Debug)]
 pub(crate) enum GlobalRetireState {
-    GlobalStrategy(RetiredQueue),
+    GlobalStrategy(ShardedQueue),
     LocalStrategy(AbandonedQueue),
 }
 
 /********** impl inherent *************************************************************************/
 
 impl GlobalRetireState {
-    pub(crate) const fn global_strategy() -> Self {
-        GlobalRetireState::GlobalStrategy(RetiredQueue::new())
+    pub(crate) fn global_strategy(shards: usize) -> Self {
+        GlobalRetireState::GlobalStrategy(ShardedQueue::new(shards))
+    }
+
+    pub(crate) fn global_strategy_per_node(nodes: usize) -> Self {
+        GlobalRetireState::GlobalStrategy(ShardedQueue::per_node(nodes))
+    }
+
+    pub(crate) fn global_strategy_with_external_headers(shards: usize) -> Self {
+        GlobalRetireState::GlobalStrategy(ShardedQueue::with_external_headers(shards))
     }
 
     pub(crate) const fn local_strategy() -> Self {
         GlobalRetireState::LocalStrategy(AbandonedQueue::new())
     }
+
+    /// Reclaims all globally stored records, which are not contained in the
+    /// sorted slice of `protected` pointers, and leaks all others.
+    ///
+    /// This is used for tearing down the global state, at which point any
+    /// remaining protected pointers must stem from leaked hazard pointers
+    /// (e.g. of forgotten guards), so their records can never be reclaimed
+    /// safely.
+    #[inline]
+    pub(crate) fn reclaim_or_leak_all(&mut self, protected: &[ProtectedPtr]) {
+        match self {
+            GlobalRetireState::GlobalStrategy(queue) => {
+                unsafe { queue.reclaim_all_unprotected(protected, None) };
+                queue.leak_all();
+            }
+            GlobalRetireState::LocalStrategy(abandoned) => {
+                if let Some(mut node) = abandoned.take_all_and_merge() {
+                    unsafe { node.reclaim_all_unprotected(protected, None) };
+                    mem::forget(node.into_inner());
+                }
+            }
+        }
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -52,7 +222,9 @@ pub struct LocalRetire;
 
 /********** impl RetireStrategy *******************************************************************/
 
-impl RetireStrategy for LocalRetire {}
+impl RetireStrategy for LocalRetire {
+    const KIND: StrategyKind = StrategyKind::Local;
+}
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // LocalRetireState
@@ -60,21 +232,36 @@ impl RetireStrategy for LocalRetire {}
 
 #[derive(Debug)]
 pub(crate) enum LocalRetireState {
-    GlobalStrategy,
+    /// The index of the global queue shard the thread retires into.
+    GlobalStrategy(usize),
     LocalStrategy(Box<RetireNode>),
 }
 
-/********** impl From *****************************************************************************/
+/********** impl inherent *************************************************************************/
 
-impl From<&GlobalRetireState> for LocalRetireState {
+impl LocalRetireState {
+    /// Creates a new [`LocalRetireState`] for a thread with the given NUMA
+    /// `node` hint, if there is any, which stores its retired records in a
+    /// bounded buffer, if a `ring_capacity` is given.
     #[inline]
-    fn from(retire_state: &GlobalRetireState) -> Self {
-        match retire_state {
-            GlobalRetireState::GlobalStrategy(_) => LocalRetireState::GlobalStrategy,
-            GlobalRetireState::LocalStrategy(abandoned) => {
+    pub(crate) fn new(
+        retire_state: &GlobalRetireState,
+        node: Option<usize>,
+        ring_capacity: Option<usize>,
+    ) -> Self {
+        match (retire_state, ring_capacity) {
+            (GlobalRetireState::GlobalStrategy(queue), _) => {
+                LocalRetireState::GlobalStrategy(queue.assign_shard_for_node(node))
+            }
+            // a bounded buffer is allocated up front and never adopts abandoned records
+            (GlobalRetireState::LocalStrategy(_), Some(capacity)) => {
+                LocalRetireState::LocalStrategy(Box::new(RetireNode::with_ring_capacity(capacity)))
+            }
+            (GlobalRetireState::LocalStrategy(abandoned), None) => {
                 // check if there are any abandoned records that can be used by
-                // the new thread instead of allocating a new local queue
-                match abandoned.take_all_and_merge() {
+                // the new thread instead of allocating a new local queue and
+                // otherwise prefer the empty node of an exited thread
+                match abandoned.take_all_and_merge().or_else(|| abandoned.pop_spare()) {
                     Some(node) => LocalRetireState::LocalStrategy(node),
                     None => LocalRetireState::LocalStrategy(Box::new(Default::default())),
                 }
@@ -82,3 +269,82 @@ impl From<&GlobalRetireState> for LocalRetireState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::ptr::NonNull;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::hazard::ProtectStrategy;
+    use crate::test_util::retired;
+    use crate::{Hp, LocalRetire};
+
+    use super::global_retire::RetiredQueue;
+    use super::local_retire::{RetireNode, SPARE_NODES};
+    use super::{GlobalRetireState, LocalRetireState};
+
+    #[test]
+    fn reclaim_without_hazards() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        // a hazard protecting some unrelated value
+        let hp = Hp::<LocalRetire>::default();
+        let hazard = hp.state.get_hazard(ProtectStrategy::ReserveOnly);
+        hazard.set_protected(NonNull::from(&hp).cast());
+        let unrelated = [hazard.protected(Ordering::Relaxed).protected().unwrap()];
+
+        let mut node = RetireNode::default();
+        let queue = RetiredQueue::new();
+        for _ in 0..8 {
+            unsafe { node.retire(retired(&COUNT), 0, 0) };
+            unsafe { queue.retire(retired(&COUNT), 0, 0) };
+        }
+
+        // with no protected pointers, no (non-dangling) address is ever protected
+        let addr = &hp as *const _ as usize;
+        assert!(!super::is_protected(&[], addr));
+        assert!(!super::is_protected(&unrelated, addr + 1));
+        assert!(super::is_protected(&unrelated, addr));
+
+        // ...so all records are reclaimed
+        assert_eq!(unsafe { node.reclaim_all_unprotected(&[], None) }, 8);
+        assert_eq!(unsafe { queue.reclaim_all_unprotected(&[], None) }, 8);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 16);
+
+        unsafe { node.retire(retired(&COUNT), 0, 0) };
+        unsafe { queue.retire(retired(&COUNT), 0, 0) };
+        assert_eq!(unsafe { node.reclaim_all_unprotected(&unrelated, None) }, 1);
+        assert_eq!(unsafe { queue.reclaim_all_unprotected(&unrelated, None) }, 1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 18);
+    }
+
+    #[test]
+    fn reuse_spare_nodes() {
+        let state = GlobalRetireState::local_strategy();
+        let abandoned = match &state {
+            GlobalRetireState::LocalStrategy(abandoned) => abandoned,
+            _ => unreachable!(),
+        };
+
+        // a new thread re-uses the empty node of an exited one
+        let node = Box::new(RetireNode::default());
+        let addr = &*node as *const RetireNode;
+        abandoned.push_spare(node);
+        match LocalRetireState::new(&state, None, None) {
+            LocalRetireState::LocalStrategy(node) => assert_eq!(&*node as *const _, addr),
+            _ => unreachable!(),
+        }
+
+        // at most `SPARE_NODES` nodes are kept, the remaining ones are de-allocated
+        for _ in 0..SPARE_NODES + 2 {
+            abandoned.push_spare(Box::new(RetireNode::with_ring_capacity(8)));
+        }
+
+        let mut count = 0;
+        while let Some(node) = abandoned.pop_spare() {
+            assert!(node.is_empty());
+            count += 1;
+        }
+
+        assert_eq!(count, SPARE_NODES);
+    }
+}
@@ -0,0 +1,289 @@
+//! Scheduling of reclamation attempts.
+//!
+//! The passage of time is abstracted behind the [`Clock`] trait, so that the
+//! cadence of reclamation attempts can be driven deterministically in tests
+//! using a [`ManualClock`].
+//! Reclamation attempts can be dispatched to a custom [`ReclaimExecutor`]
+//! instead of running them on the calling thread.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Clock (trait)
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A monotonic source of time.
+pub trait Clock {
+    /// Returns the time elapsed since some arbitrary but fixed point in time.
+    fn now(&self) -> Duration;
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// SystemClock
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A [`Clock`] backed by the system's monotonic (wall) clock.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+/********** impl Default **************************************************************************/
+
+#[cfg(feature = "std")]
+impl Default for SystemClock {
+    #[inline]
+    fn default() -> Self {
+        Self { start: Instant::now() }
+    }
+}
+
+/********** impl Clock ****************************************************************************/
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    #[inline]
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// ManualClock
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A [`Clock`] that only advances when explicitly told to.
+///
+/// The clock counts whole milliseconds in an [`AtomicUsize`], so it is
+/// available on all targets with pointer-sized atomics, and wraps around to
+/// zero after `usize::MAX` milliseconds (about 49.7 days on 32-bit targets).
+/// Sub-millisecond fractions of advanced durations are truncated.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    millis: AtomicUsize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl ManualClock {
+    /// Creates a new [`ManualClock`] starting at zero.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { millis: AtomicUsize::new(0) }
+    }
+
+    /// Advances the clock by `duration`, wrapping around on overflow.
+    #[inline]
+    pub fn advance(&self, duration: Duration) {
+        self.millis.fetch_add(duration.as_millis() as usize, Ordering::Relaxed);
+    }
+}
+
+/********** impl Clock ****************************************************************************/
+
+impl Clock for ManualClock {
+    #[inline]
+    fn now(&self) -> Duration {
+        Duration::from_millis(self.millis.load(Ordering::Relaxed) as u64)
+    }
+}
+
+impl<C: Clock> Clock for &'_ C {
+    #[inline]
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// ReclaimSchedule
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A schedule for periodic reclamation attempts at a fixed interval, as
+/// measured by a [`Clock`].
+///
+/// The schedule is polled through [`Hp::poll_reclaim`][crate::Hp::poll_reclaim].
+#[derive(Debug)]
+pub struct ReclaimSchedule<C> {
+    clock: C,
+    interval: Duration,
+    next: Duration,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<C: Clock> ReclaimSchedule<C> {
+    /// Creates a new [`ReclaimSchedule`] with the first reclamation attempt
+    /// becoming due after `interval` has elapsed on `clock`.
+    #[inline]
+    pub fn new(clock: C, interval: Duration) -> Self {
+        let next = clock.now() + interval;
+        Self { clock, interval, next }
+    }
+
+    /// Returns a reference to the schedule's clock.
+    #[inline]
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Returns the schedule's interval.
+    #[inline]
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Returns `true` if a reclamation attempt is due and schedules the next
+    /// one.
+    ///
+    /// If several intervals have elapsed since the last attempt, only a single
+    /// attempt is due and the next one is scheduled relative to the current
+    /// time.
+    #[inline]
+    pub fn poll(&mut self) -> bool {
+        let now = self.clock.now();
+        if now < self.next {
+            return false;
+        }
+
+        self.next = now + self.interval;
+        true
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// ReclaimExecutor (trait)
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An executor onto which reclamation work can be scheduled, e.g. a thread
+/// pool or an async runtime.
+///
+/// See [`Hp::schedule_reclaim`][crate::Hp::schedule_reclaim].
+pub trait ReclaimExecutor {
+    /// Schedules `job` for (eventual) execution.
+    fn schedule(&self, job: impl FnOnce() + Send + 'static);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// InlineExecutor
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A [`ReclaimExecutor`] that runs all jobs immediately on the calling thread.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct InlineExecutor;
+
+/********** impl ReclaimExecutor ******************************************************************/
+
+impl ReclaimExecutor for InlineExecutor {
+    #[inline]
+    fn schedule(&self, job: impl FnOnce() + Send + 'static) {
+        job();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use crate::test_util::retired;
+    use crate::{GlobalRetire, Hp};
+
+    use super::{Clock, InlineExecutor, ManualClock, ReclaimExecutor, ReclaimSchedule};
+
+    #[derive(Default)]
+    struct MockExecutor(Mutex<Vec<Box<dyn FnOnce() + Send>>>);
+
+    impl MockExecutor {
+        fn run_all(&self) -> usize {
+            let jobs: Vec<_> = self.0.lock().unwrap().drain(..).collect();
+            let count = jobs.len();
+            jobs.into_iter().for_each(|job| job());
+            count
+        }
+    }
+
+    impl ReclaimExecutor for MockExecutor {
+        fn schedule(&self, job: impl FnOnce() + Send + 'static) {
+            self.0.lock().unwrap().push(Box::new(job));
+        }
+    }
+
+    #[test]
+    fn dispatch_to_executor() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Arc::new(Hp::<GlobalRetire>::default());
+        let local = hp.build_local(None);
+        let retire = || {
+            local.retire(retired(&COUNT));
+        };
+
+        let executor = MockExecutor::default();
+        retire();
+        hp.schedule_reclaim(&executor);
+        // the scan is only dispatched, not run inline
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+        assert_eq!(executor.run_all(), 1);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+
+        retire();
+        hp.schedule_reclaim(&InlineExecutor);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn manual_clock() {
+        let clock = ManualClock::new();
+        assert_eq!(clock.now(), Duration::from_millis(0));
+        // sub-millisecond fractions are truncated
+        clock.advance(Duration::from_micros(1500));
+        assert_eq!(clock.now(), Duration::from_millis(1));
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(clock.now(), Duration::from_millis(1001));
+    }
+
+    #[test]
+    fn one_scan_per_tick() {
+        const TICK: Duration = Duration::from_millis(10);
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hp = Hp::<GlobalRetire>::default();
+        let local = hp.build_local(None);
+
+        let clock = ManualClock::new();
+        let mut schedule = ReclaimSchedule::new(&clock, TICK);
+
+        let retire = || {
+            local.retire(retired(&COUNT));
+        };
+
+        retire();
+        retire();
+        assert_eq!(hp.poll_reclaim(&mut schedule), None);
+        clock.advance(TICK / 2);
+        assert_eq!(hp.poll_reclaim(&mut schedule), None);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+
+        clock.advance(TICK / 2);
+        assert_eq!(hp.poll_reclaim(&mut schedule), Some(2));
+        assert_eq!(hp.poll_reclaim(&mut schedule), None);
+        assert_eq!(COUNT.load(Ordering::Relaxed), 2);
+
+        // several elapsed intervals still result in only a single scan
+        retire();
+        clock.advance(TICK * 3);
+        assert_eq!(hp.poll_reclaim(&mut schedule), Some(1));
+        assert_eq!(hp.poll_reclaim(&mut schedule), None);
+
+        clock.advance(TICK);
+        assert_eq!(hp.poll_reclaim(&mut schedule), Some(0));
+        assert_eq!(COUNT.load(Ordering::Relaxed), 3);
+    }
+}
@@ -0,0 +1,32 @@
+//! Helpers shared by the unit tests of all modules.
+//!
+//! The integration tests can not access these crate-internal helpers and
+//! define their own drop counting records instead.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use conquer_reclaim::typenum::U0;
+use conquer_reclaim::{Atomic, Owned, RawRetired, Unlinked};
+
+use crate::{GlobalRetire, Hp};
+
+/// A value incrementing the referenced counter when it is dropped.
+pub(crate) struct DropCount(pub &'static AtomicUsize);
+
+impl Drop for DropCount {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Returns an unlinked and retired record containing a [`DropCount`] for
+/// `count`.
+///
+/// The record is allocated with the header required by the global retire
+/// strategy, so it can be retired with either strategy.
+pub(crate) fn retired(count: &'static AtomicUsize) -> RawRetired {
+    let atomic = Atomic::<_, Hp<GlobalRetire>, U0>::new(DropCount(count));
+    let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+    Unlinked::into_retired(unlinked).into_raw()
+}
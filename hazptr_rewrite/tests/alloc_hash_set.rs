@@ -0,0 +1,623 @@
+//! An `alloc`-only port of the concurrent hash set example for `no_std`
+//! targets.
+//!
+//! Unlike the `std` version, the set uses an explicitly created [`Hp`] instance
+//! and explicitly passed thread local states ([`Local`]s) instead of the global
+//! default, which is only available with the `std` feature.
+//! The `set` module only depends on `core` and `alloc` and can be copied as is
+//! into an embedded project.
+//! The test crate itself is not `no_std`, since it links `std` for the test
+//! harness, for spawning threads and as backing store for the stub allocator.
+//!
+//! Run with `cargo test --no-default-features --test alloc_hash_set`.
+
+extern crate alloc;
+
+use core::borrow::Borrow;
+use core::cmp;
+use core::hash::{Hash, Hasher};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::thread;
+
+use conquer_reclaim::typenum::U0;
+use conquer_reclaim::{Protect, ReclaimRef};
+use hazptr_rewrite::{ConfigBuilder, Hp, LocalHandle, LocalRetire, RetireVia};
+
+use crate::set::{Guards, HashSet};
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// StubAllocator
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A stub allocator standing in for the platform's heap implementation (e.g.
+/// a linked list allocator over a static memory region) on embedded targets.
+struct StubAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for StubAllocator {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: StubAllocator = StubAllocator;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Elem
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A set element, which counts its drops.
+#[derive(Debug)]
+struct Elem {
+    key: usize,
+    drops: &'static AtomicUsize,
+}
+
+impl Elem {
+    fn new(key: usize, drops: &'static AtomicUsize) -> Self {
+        Self { key, drops }
+    }
+}
+
+impl Borrow<usize> for Elem {
+    fn borrow(&self) -> &usize {
+        &self.key
+    }
+}
+
+impl Hash for Elem {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+impl PartialEq for Elem {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Elem {}
+
+impl PartialOrd for Elem {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Elem {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl Drop for Elem {
+    fn drop(&mut self) {
+        self.drops.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn insert_remove() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    const ELEMS: usize = 256;
+
+    let hp = Hp::<LocalRetire>::default();
+    let local = hp.build_local(None);
+    let mut guards = Guards::new(&local);
+
+    let set = HashSet::with_buckets(8);
+    for key in 0..ELEMS {
+        assert!(set.insert(Elem::new(key, &DROPS), &mut guards));
+    }
+
+    // rejected duplicates are dropped right away
+    for key in 0..ELEMS {
+        assert!(!set.insert(Elem::new(key, &DROPS), &mut guards));
+    }
+    assert_eq!(DROPS.load(Ordering::Relaxed), ELEMS);
+
+    for key in (0..ELEMS).filter(|key| key % 2 == 0) {
+        assert!(set.remove(&key, &mut guards));
+        assert!(!set.remove(&key, &mut guards));
+    }
+
+    for key in 0..ELEMS {
+        assert_eq!(set.contains(&key, &mut guards), key % 2 == 1);
+    }
+
+    assert!(ALLOCATIONS.load(Ordering::Relaxed) > 0);
+
+    // removed elements are reclaimed at the latest when their local is dropped, the remaining
+    // ones when the set is dropped
+    drop(guards);
+    drop(local);
+    drop(set);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 2 * ELEMS);
+}
+
+#[test]
+fn concurrent_insert_remove() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    const THREADS: usize = 4;
+    const ELEMS: usize = 512;
+
+    // threads must be able to borrow the global state for `'static`, it is freed at the end
+    let hp: &'static Hp<LocalRetire> = Box::leak(Default::default());
+    let set = Arc::new(HashSet::with_buckets(16));
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|id| {
+            let set = Arc::clone(&set);
+            thread::spawn(move || {
+                let local = hp.build_local(None);
+                let mut guards = Guards::new(&local);
+
+                let keys = (id * ELEMS)..((id + 1) * ELEMS);
+                for key in keys.clone() {
+                    assert!(set.insert(Elem::new(key, &DROPS), &mut guards));
+                }
+
+                for key in keys.filter(|key| key % 2 == 0) {
+                    assert!(set.remove(&key, &mut guards));
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let local = hp.build_local(None);
+    let mut guards = Guards::new(&local);
+    for key in 0..THREADS * ELEMS {
+        assert_eq!(set.contains(&key, &mut guards), key % 2 == 1);
+    }
+
+    // all removed elements are reclaimed once all threads have exited, the remaining ones when the
+    // set is dropped
+    drop(guards);
+    drop(local);
+    hp.reclaim_remaining();
+    assert_eq!(DROPS.load(Ordering::Relaxed), THREADS * ELEMS / 2);
+    drop(set);
+    assert_eq!(DROPS.load(Ordering::Relaxed), THREADS * ELEMS);
+
+    // SAFETY: all threads and locals borrowing the leaked global state have been dropped
+    drop(unsafe { Box::from_raw(hp as *const Hp<LocalRetire> as *mut Hp<LocalRetire>) });
+}
+
+#[test]
+fn fixed_scan_cache() {
+    type Atomic<T> = conquer_reclaim::Atomic<T, Hp<LocalRetire>, U0>;
+    type Owned<T> = conquer_reclaim::Owned<T, Hp<LocalRetire>, U0>;
+
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    const PROTECTED: usize = 5;
+
+    // more pointers are protected than fit into the scan cache, so every scan requires three passes
+    let hp = Hp::<LocalRetire>::default();
+    let local = hp.build_local(Some(ConfigBuilder::new().scan_cache_fixed_capacity(2).build()));
+    let handle = LocalHandle::from_ref(&local);
+
+    let atomics: Vec<Atomic<Elem>> =
+        (0..2 * PROTECTED).map(|key| Atomic::new(Elem::new(key, &DROPS))).collect();
+    let mut guards: Vec<_> = atomics[..PROTECTED]
+        .iter()
+        .map(|atomic| {
+            let mut guard = handle.clone().into_guard();
+            let _ = guard.protect(atomic, Ordering::Acquire);
+            guard
+        })
+        .collect();
+
+    for atomic in &atomics {
+        let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+        unsafe { unlinked.retire_via(&handle) };
+    }
+
+    // records protected in any of the passes are retained
+    assert_eq!(handle.flush(), PROTECTED);
+    assert_eq!(DROPS.load(Ordering::Relaxed), PROTECTED);
+
+    guards.clear();
+    assert_eq!(handle.flush(), PROTECTED);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 2 * PROTECTED);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// set
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+mod set {
+    use core::borrow::Borrow;
+    use core::cmp::Ordering::{Equal, Greater, Less};
+    use core::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+    use core::mem;
+    use core::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+
+    use conquer_reclaim::conquer_pointer::{
+        MarkedPtr,
+        MaybeNull::{self, NotNull, Null},
+    };
+    use conquer_reclaim::typenum::U1;
+    use conquer_reclaim::{Protect, ReclaimRef};
+    use hazptr_rewrite::{Hp, Local, LocalHandle, LocalRetire, RetireVia};
+
+    type Handle<'local, 'global> = LocalHandle<'local, 'global, Hp<LocalRetire>>;
+    type Guard<'local, 'global> = <Handle<'local, 'global> as ReclaimRef>::Guard;
+
+    type Atomic<T> = conquer_reclaim::Atomic<T, Hp<LocalRetire>, U1>;
+    type Owned<T> = conquer_reclaim::Owned<T, Hp<LocalRetire>, U1>;
+    type Shared<'g, T> = conquer_reclaim::Shared<'g, T, Hp<LocalRetire>, U1>;
+    type Ptr<T> = MarkedPtr<Node<T>, U1>;
+
+    const DELETE_TAG: usize = 1;
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+    // Guards
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    /// The explicitly passed thread local state and the two guards required for
+    /// traversing a bucket.
+    ///
+    /// Each thread must use its own instance for all operations on the set.
+    pub struct Guards<'local, 'global> {
+        handle: Handle<'local, 'global>,
+        prev: Guard<'local, 'global>,
+        curr: Guard<'local, 'global>,
+    }
+
+    impl<'local, 'global> Guards<'local, 'global> {
+        /// Creates a new set of guards for the given thread local state.
+        #[inline]
+        pub fn new(local: &'local Local<'global>) -> Self {
+            let handle = Handle::from_ref(local);
+            Self { prev: handle.clone().into_guard(), curr: handle.clone().into_guard(), handle }
+        }
+
+        #[inline]
+        fn release_all(&mut self) {
+            self.prev.release();
+            self.curr.release();
+        }
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+    // FnvHasher
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    /// A minimal FNV-1a hasher, since `core` provides no (randomized) default
+    /// hasher.
+    #[derive(Clone, Copy, Debug)]
+    pub struct FnvHasher(u64);
+
+    impl Default for FnvHasher {
+        #[inline]
+        fn default() -> Self {
+            Self(0xcbf2_9ce4_8422_2325)
+        }
+    }
+
+    impl Hasher for FnvHasher {
+        #[inline]
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        #[inline]
+        fn write(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.0 ^= u64::from(*byte);
+                self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+    // HashSet
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    /// A concurrent hash set with a fixed number of buckets, each of which is a
+    /// lock-free ordered linked list.
+    pub struct HashSet<T, S = BuildHasherDefault<FnvHasher>> {
+        buckets: Box<[OrderedSet<T>]>,
+        hash_builder: S,
+    }
+
+    impl<T: Hash + Ord + 'static> HashSet<T> {
+        /// Creates a new hash set with the specified number of buckets.
+        ///
+        /// # Panics
+        ///
+        /// This function will panic, if `buckets` is 0.
+        #[inline]
+        pub fn with_buckets(buckets: usize) -> Self {
+            Self::with_hasher_and_buckets(Default::default(), buckets)
+        }
+    }
+
+    impl<T: Hash + Ord + 'static, S: BuildHasher> HashSet<T, S> {
+        /// Creates a new hash set with the specified number of buckets and the
+        /// given `hash_builder`.
+        ///
+        /// # Panics
+        ///
+        /// This function will panic, if `buckets` is 0.
+        #[inline]
+        pub fn with_hasher_and_buckets(hash_builder: S, buckets: usize) -> Self {
+            assert!(buckets > 0, "hash set needs at least one bucket");
+            let buckets: Vec<_> = (0..buckets).map(|_| OrderedSet::default()).collect();
+            Self { buckets: buckets.into_boxed_slice(), hash_builder }
+        }
+
+        /// Returns `true` if the set contains the given `value`.
+        #[inline]
+        pub fn contains<Q>(&self, value: &Q, guards: &mut Guards) -> bool
+        where
+            T: Borrow<Q>,
+            Q: Hash + Ord,
+        {
+            self.bucket(value).contains(value, guards)
+        }
+
+        /// Inserts the given `value` and returns `true`, if it did not already
+        /// exist in the set.
+        #[inline]
+        pub fn insert(&self, value: T, guards: &mut Guards) -> bool {
+            self.bucket(&value).insert(value, guards)
+        }
+
+        /// Removes the given `value` and returns `true`, if it was found in the
+        /// set.
+        #[inline]
+        pub fn remove<Q>(&self, value: &Q, guards: &mut Guards) -> bool
+        where
+            T: Borrow<Q>,
+            Q: Hash + Ord,
+        {
+            self.bucket(value).remove(value, guards)
+        }
+
+        #[inline]
+        fn bucket<Q: Hash>(&self, value: &Q) -> &OrderedSet<T> {
+            let mut hasher = self.hash_builder.build_hasher();
+            value.hash(&mut hasher);
+            &self.buckets[hasher.finish() as usize % self.buckets.len()]
+        }
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+    // OrderedSet
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    /// A lock-free ordered linked list (Harris & Michael).
+    struct OrderedSet<T> {
+        head: Atomic<Node<T>>,
+    }
+
+    impl<T> Default for OrderedSet<T> {
+        #[inline]
+        fn default() -> Self {
+            Self { head: Atomic::null() }
+        }
+    }
+
+    impl<T: Ord + 'static> OrderedSet<T> {
+        #[inline]
+        fn contains<Q>(&self, value: &Q, guards: &mut Guards) -> bool
+        where
+            T: Borrow<Q>,
+            Q: Ord,
+        {
+            let found = self.find(value, guards).found;
+            guards.release_all();
+            found
+        }
+
+        #[inline]
+        fn insert(&self, value: T, guards: &mut Guards) -> bool {
+            let mut node = Owned::new(Node { elem: value, next: Atomic::null() });
+
+            let success = loop {
+                let pos = self.find(&node.elem, guards);
+                if pos.found {
+                    break false;
+                }
+
+                node.next.store(unsafe { shared(pos.curr) }, Relaxed);
+                // (ORD:1) this `Release` CAS synchronizes-with the `Acquire` loads in `find`
+                match pos.prev.compare_exchange(unsafe { shared(pos.curr) }, node, Release, Relaxed)
+                {
+                    Ok(_) => break true,
+                    Err(failure) => node = failure.input,
+                }
+            };
+
+            guards.release_all();
+            success
+        }
+
+        #[inline]
+        fn remove<Q>(&self, value: &Q, guards: &mut Guards) -> bool
+        where
+            T: Borrow<Q>,
+            Q: Ord,
+        {
+            let success = loop {
+                let pos = self.find(value, guards);
+                if !pos.found {
+                    break false;
+                }
+
+                // curr is protected by `guards.curr`
+                let curr = unsafe { node(pos.curr) };
+                let next = curr.next.load_raw(Acquire);
+                if next.decompose().1 == DELETE_TAG {
+                    continue;
+                }
+
+                // logically delete curr by marking its next pointer
+                // (ORD:2) this `Acquire` CAS synchronizes-with the `Release` CAS (ORD:1)
+                let marked = MarkedPtr::compose(next.decompose().0, DELETE_TAG);
+                let res = unsafe {
+                    curr.next.compare_exchange(shared(next), shared(marked), Acquire, Relaxed)
+                };
+                if res.is_err() {
+                    continue;
+                }
+
+                // physically unlink curr or leave it to a subsequent traversal
+                // (ORD:3) this `Release` CAS synchronizes-with the `Acquire` loads in `find`
+                match unsafe {
+                    pos.prev.compare_exchange(shared(pos.curr), shared(next), Release, Relaxed)
+                } {
+                    Ok(NotNull(unlinked)) => unsafe { unlinked.retire_via(&guards.handle) },
+                    _ => {
+                        let _ = self.find(value, guards);
+                    }
+                }
+
+                break true;
+            };
+
+            guards.release_all();
+            success
+        }
+
+        /// Searches the list for `value`, unlinking all logically deleted nodes
+        /// along the way.
+        ///
+        /// On return, the node containing `prev` (unless it is the head) is
+        /// protected by `guards.prev` and `curr` (unless it is null) by
+        /// `guards.curr`.
+        fn find<'set, Q>(&'set self, value: &Q, guards: &mut Guards) -> Position<'set, T>
+        where
+            T: Borrow<Q>,
+            Q: Ord,
+        {
+            'retry: loop {
+                let mut prev = &self.head;
+                let mut curr = prev.load_raw(Acquire);
+
+                loop {
+                    if let Null(_) = MaybeNull::from(curr) {
+                        return Position { prev, curr, found: false };
+                    }
+
+                    // protect curr and validate it is still reachable through prev, which fails if
+                    // the node containing prev has been marked in the meantime
+                    if guards.curr.protect_raw_if_equal(prev, curr, Acquire).is_err() {
+                        continue 'retry;
+                    }
+
+                    let node = unsafe { node(curr) };
+                    let next = node.next.load_raw(Acquire);
+                    let (next_ptr, next_tag) = next.decompose();
+
+                    if next_tag == DELETE_TAG {
+                        let unmarked = MarkedPtr::compose(next_ptr, 0);
+                        let res = unsafe {
+                            prev.compare_exchange(shared(curr), shared(unmarked), Release, Relaxed)
+                        };
+                        match res {
+                            Ok(NotNull(unlinked)) => unsafe { unlinked.retire_via(&guards.handle) },
+                            _ => continue 'retry,
+                        }
+
+                        // the next node is protected and validated in the next iteration
+                        curr = unmarked;
+                    } else {
+                        match node.elem.borrow().cmp(value) {
+                            Equal => return Position { prev, curr, found: true },
+                            Greater => return Position { prev, curr, found: false },
+                            Less => {}
+                        }
+
+                        // the node containing the new prev must remain protected, so curr's guard
+                        // becomes the guard for prev
+                        prev = unsafe { &*(&node.next as *const Atomic<Node<T>>) };
+                        mem::swap(&mut guards.prev, &mut guards.curr);
+                        curr = next;
+                    }
+                }
+            }
+        }
+    }
+
+    impl<T> Drop for OrderedSet<T> {
+        #[inline]
+        fn drop(&mut self) {
+            let mut node = self.head.take();
+            while let Some(mut curr) = node {
+                node = curr.next.take();
+            }
+        }
+    }
+
+    /// Returns a reference to the node pointed to by `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null and protected for the lifetime `'a`.
+    #[inline]
+    unsafe fn node<'a, T>(ptr: Ptr<T>) -> &'a Node<T> {
+        &*ptr.decompose().0
+    }
+
+    /// Converts `ptr` into a (possibly null) [`Shared`] for use in a CAS.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must either be protected or only be used for comparisons or for
+    /// storing it into a node, through which it is reachable.
+    #[inline]
+    unsafe fn shared<'a, T>(ptr: Ptr<T>) -> MaybeNull<Shared<'a, Node<T>>> {
+        match MaybeNull::from(ptr) {
+            NotNull(ptr) => NotNull(Shared::from_marked_non_null(ptr)),
+            Null(tag) => Null(tag),
+        }
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+    // Node
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    struct Node<T> {
+        elem: T,
+        next: Atomic<Node<T>>,
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+    // Position
+    ////////////////////////////////////////////////////////////////////////////////////////////////
+
+    /// The position in a list at which a value was found or has to be inserted.
+    struct Position<'set, T> {
+        prev: &'set Atomic<Node<T>>,
+        curr: Ptr<T>,
+        found: bool,
+    }
+}
@@ -0,0 +1,89 @@
+//! Tests for the core integration with `conquer_reclaim`, i.e. unlinking records
+//! with `Atomic::compare_exchange` and retiring the returned `Unlinked` records
+//! through an explicitly passed thread local state, with both retire
+//! strategies.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use conquer_reclaim::conquer_pointer::MaybeNull::NotNull;
+use conquer_reclaim::typenum::U0;
+use conquer_reclaim::{Protect, ReclaimRef};
+use hazptr_rewrite::{GlobalRetire, Hp, LocalHandle, LocalRetire, RetireVia};
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// DropCount
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A record, which counts its drops.
+struct DropCount(&'static AtomicUsize);
+
+impl Drop for DropCount {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+macro_rules! cas_retire {
+    ($name:ident, $strategy:ty) => {
+        #[test]
+        fn $name() {
+            type Atomic<T> = conquer_reclaim::Atomic<T, Hp<$strategy>, U0>;
+            type Owned<T> = conquer_reclaim::Owned<T, Hp<$strategy>, U0>;
+
+            static DROPS: AtomicUsize = AtomicUsize::new(0);
+            const ITERATIONS: usize = 64;
+
+            let hp = Hp::<$strategy>::default();
+            let local = hp.build_local(None);
+            let handle = LocalHandle::from_ref(&local);
+            let atomic = Atomic::new(DropCount(&DROPS));
+
+            for i in 0..ITERATIONS {
+                let mut guard = handle.clone().into_guard();
+                let shared = match guard.protect(&atomic, Ordering::Acquire) {
+                    NotNull(shared) => shared,
+                    _ => unreachable!(),
+                };
+
+                let new = Owned::new(DropCount(&DROPS));
+                match atomic.compare_exchange(shared, new, Ordering::AcqRel, Ordering::Relaxed) {
+                    Ok(NotNull(unlinked)) => unsafe { unlinked.retire_via(&handle) },
+                    _ => panic!("the CAS must succeed without contention"),
+                }
+
+                // a CAS with an outdated pointer fails and returns its input, which is not retired
+                let new = Owned::new(DropCount(&DROPS));
+                match atomic.compare_exchange(shared, new, Ordering::AcqRel, Ordering::Relaxed) {
+                    Err(failure) => drop(failure.input),
+                    Ok(_) => panic!("the CAS must fail with an outdated pointer"),
+                }
+
+                // the unlinked record is still protected and must not be reclaimed
+                assert_eq!(DROPS.load(Ordering::Relaxed), 2 * i + 1);
+                handle.flush();
+                assert_eq!(DROPS.load(Ordering::Relaxed), 2 * i + 1);
+
+                guard.release();
+                handle.flush();
+                assert_eq!(DROPS.load(Ordering::Relaxed), 2 * i + 2);
+            }
+
+            let unlinked = atomic.swap(Owned::none(), Ordering::Relaxed).unwrap();
+            unsafe { unlinked.retire_via(&handle) };
+            handle.flush();
+
+            // every allocated record has been dropped exactly once
+            assert_eq!(DROPS.load(Ordering::Relaxed), 2 * ITERATIONS + 1);
+            drop(handle);
+            drop(local);
+            assert_eq!(DROPS.load(Ordering::Relaxed), 2 * ITERATIONS + 1);
+        }
+    };
+}
+
+cas_retire!(cas_retire_global, GlobalRetire);
+cas_retire!(cas_retire_local, LocalRetire);
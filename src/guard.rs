@@ -102,6 +102,9 @@ unsafe impl<L: LocalAccess> Protect for Guard<L> {
     ) -> Result<Marked<Shared<T, N>>, NotEqualError> {
         let raw = atomic.load_raw(Relaxed);
         if raw != expected {
+            // (GUA:7) this `Release` store synchronizes-with the `SeqCst` fence (LOC:2) but WITHOUT
+            // enforcing a total order
+            self.hazard.set_thread_reserved(Release);
             return Err(NotEqualError);
         }
 
@@ -195,6 +198,22 @@ mod tests {
         assert!(guard.hazard.protected(Relaxed).is_none());
     }
 
+    #[test]
+    fn protected_matches() {
+        let local = Local::new();
+        let mut guard = Guard::with_access(&local);
+        let mut other_guard = Guard::with_access(&local);
+
+        let atomic = Atomic::new(1);
+        let other = Atomic::new(2);
+        let shared = guard.protect(&atomic, Relaxed).unwrap_value();
+        let other_shared = other_guard.protect(&other, Relaxed).unwrap_value();
+
+        let protected = guard.hazard.protected(Relaxed).unwrap();
+        assert!(protected.matches(shared));
+        assert!(!protected.matches(other_shared));
+    }
+
     #[test]
     fn protect_if_equal() {
         let local = Local::new();
@@ -221,10 +240,10 @@ mod tests {
         assert_eq!(reference, &1);
         assert_eq!(guard.hazard.protected(Relaxed).unwrap().address(), marked.into_usize());
 
-        // a failed protection attempt must not alter the previous state
+        // a failed protection attempt must not leave the previous value protected
         let res = guard.protect_if_equal(&null, marked, Relaxed);
         assert!(res.is_err());
-        assert_eq!(guard.hazard.protected(Relaxed).unwrap().address(), marked.into_usize());
+        assert!(guard.hazard.protected(Relaxed).is_none());
 
         let res = guard.protect_if_equal(&null, null_ptr, Relaxed);
         assert_matches!(res, Ok(Null(0)));
@@ -24,6 +24,12 @@ mod list;
 use core::ptr::NonNull;
 use core::sync::atomic::{AtomicPtr, Ordering};
 
+use reclaim::prelude::*;
+use reclaim::typenum::Unsigned;
+use reclaim::MarkedPtr;
+
+use crate::Shared;
+
 pub(crate) use self::list::HazardList;
 
 const FREE: *mut () = 0 as *mut ();
@@ -114,6 +120,20 @@ impl Protected {
     pub fn into_inner(self) -> NonNull<()> {
         self.0
     }
+
+    /// Returns `true` if the protected pointer is the same as the (untagged)
+    /// pointer of `shared`.
+    #[inline]
+    pub fn matches<T, N: Unsigned>(self, shared: Shared<'_, T, N>) -> bool {
+        self.matches_marked(Shared::as_marked_ptr(&shared))
+    }
+
+    /// Returns `true` if the protected pointer is the same as the (untagged)
+    /// pointer of `marked`.
+    #[inline]
+    pub fn matches_marked<T, N: Unsigned>(self, marked: MarkedPtr<T, N>) -> bool {
+        marked.decompose_ptr() as usize == self.address()
+    }
 }
 
 #[cfg(test)]
@@ -121,6 +141,8 @@ mod tests {
     use std::ptr::NonNull;
     use std::sync::atomic::Ordering;
 
+    use reclaim::typenum::U1;
+
     use super::*;
 
     #[test]
@@ -141,4 +163,17 @@ mod tests {
         hazard.set_protected(ptr.cast(), Ordering::SeqCst);
         assert_eq!(ptr.as_ptr() as usize, hazard.protected(Ordering::Relaxed).unwrap().address());
     }
+
+    #[test]
+    fn matches_marked() {
+        let value = 1;
+        let ptr = &value as *const i32 as *mut i32;
+        let other = &2 as *const i32 as *mut i32;
+
+        let hazard = Hazard::new(ptr.cast());
+        let protected = hazard.protected(Ordering::Relaxed).unwrap();
+        assert!(protected.matches_marked(MarkedPtr::<_, U1>::new(ptr)));
+        assert!(protected.matches_marked(MarkedPtr::<_, U1>::compose(ptr, 1)));
+        assert!(!protected.matches_marked(MarkedPtr::<_, U1>::new(other)));
+    }
 }
@@ -223,7 +223,9 @@ impl LocalInner {
         // are scanned and unprotected retired records are reclaimed.
         GLOBAL.collect_protected_hazards(&mut self.scan_cache, SeqCst);
 
-        self.scan_cache.sort_unstable();
+        // the sort key must match the key used for the binary search in
+        // `reclaim_unprotected_records`
+        self.scan_cache.sort_unstable_by_key(|protected| protected.address());
         unsafe { self.reclaim_unprotected_records() };
     }
 
@@ -395,6 +397,36 @@ mod tests {
         assert_eq!(threshold as usize, count.load(Ordering::Relaxed));
     }
 
+    #[test]
+    fn scan_sorted_by_address() {
+        let count = AtomicUsize::new(0);
+        let local = Local::new();
+
+        let records: Vec<_> =
+            (0..8).map(|_| NonNull::from(Box::leak(Box::new(DropCount(&count))))).collect();
+        // protect every other record in reverse order, so the protected pointers are collected in
+        // an order that differs from their address order
+        let hazards: Vec<_> = records
+            .iter()
+            .rev()
+            .step_by(2)
+            .map(|record| local.get_hazard(Some(record.cast())))
+            .collect();
+
+        records
+            .iter()
+            .map(|&record| unsafe { Retired::new_unchecked(record) })
+            .for_each(|retired| local.retire_record(retired));
+
+        // only the unprotected records must be reclaimed
+        local.try_flush();
+        assert_eq!(4, count.load(Ordering::Relaxed));
+
+        hazards.iter().for_each(|hazard| hazard.set_free(Ordering::Relaxed));
+        mem::drop(local);
+        assert_eq!(8, count.load(Ordering::Relaxed));
+    }
+
     #[test]
     fn drop() {
         let below_threshold = Config::default().scan_threshold() / 2;
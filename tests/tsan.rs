@@ -0,0 +1,67 @@
+//! A stress test for the hazard pointer protocol intended to be run with the
+//! ThreadSanitizer, which is able to catch data races caused by insufficient
+//! memory orderings that other tests (and loom) may miss.
+//!
+//! The test compiles and runs as part of the regular test suite with a small
+//! number of operations, the heavy run is only enabled with the
+//! `sanitize-threads` feature, e.g. (requires a nightly toolchain):
+//!
+//! ```text
+//! RUSTFLAGS="-Zsanitizer=thread" RUSTDOCFLAGS="-Zsanitizer=thread" \
+//!     cargo +nightly test --features sanitize-threads --test tsan \
+//!     -Zbuild-std --target x86_64-unknown-linux-gnu
+//! ```
+//!
+//! The test only fails if TSAN reports a race (or records are leaked).
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+#[path = "../examples/treiber/stack.rs"]
+mod stack;
+
+use crate::stack::Stack;
+
+const THREADS: usize = 4;
+#[cfg(not(feature = "sanitize-threads"))]
+const OPS_PER_THREAD: usize = 1_000;
+#[cfg(feature = "sanitize-threads")]
+const OPS_PER_THREAD: usize = 100_000;
+
+struct DropCount<'a>(&'a AtomicUsize);
+impl Drop for DropCount<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn treiber_push_pop() {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let stack = Arc::new(Stack::new());
+    let barrier = Arc::new(Barrier::new(THREADS));
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let stack = Arc::clone(&stack);
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                // maximize contention by starting all threads at the same time
+                barrier.wait();
+                for _ in 0..OPS_PER_THREAD {
+                    stack.push(DropCount(&COUNT));
+                    let _res = stack.pop();
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    drop(stack);
+    assert_eq!(COUNT.load(Ordering::Relaxed), THREADS * OPS_PER_THREAD);
+}